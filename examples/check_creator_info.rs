@@ -1,6 +1,6 @@
 use anyhow::{anyhow, Result};
-use std::fs;
-use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use xhs_rs::auth::AuthService;
 use xhs_rs::api::creator::info::{get_creator_user_info, get_creator_home_info};
 
 #[tokio::main]
@@ -8,26 +8,18 @@ async fn main() -> Result<()> {
     // initialize tracing
     tracing_subscriber::fmt::init();
     
-    // 1. Load cookie-creator.json
-    let cookie_path = "cookie-creator.json";
-    if !std::path::Path::new(cookie_path).exists() {
-        return Err(anyhow!("cookie-creator.json not found! Please run 'cargo run' and scan QR code first."));
-    }
+    // 1. Load the default creator session (sessions-creator/, legacy cookie-creator.json is imported)
+    let auth = AuthService::new(PathBuf::from("sessions-creator")).await?;
+    auth.import_file(Path::new("cookie-creator.json")).await?;
     
-    #[derive(serde::Deserialize)]
-    struct CookieFile {
-        cookies: HashMap<String, String>,
-    }
-
-    let cookie_content = fs::read_to_string(cookie_path)?;
-    let cookie_file: CookieFile = serde_json::from_str(&cookie_content)?;
-    let cookies = cookie_file.cookies;
+    let creds = auth.try_get_credentials().await?
+        .ok_or_else(|| anyhow!("No creator session found! Please run 'cargo run' and scan QR code first."))?;
     
-    println!("Loaded {} cookies from {}", cookies.len(), cookie_path);
+    println!("Loaded {} cookies for creator account {}", creds.cookies.len(), creds.user_id);
     
     // 2. Call User Info
     println!("\n=== Testing get_creator_user_info ===");
-    match get_creator_user_info(&cookies).await {
+    match get_creator_user_info(&auth, &creds).await {
         Ok(info) => {
             println!("Success!");
             println!("User ID: {:?}", info.user_id);
@@ -46,7 +38,7 @@ async fn main() -> Result<()> {
     
     // 3. Call Home Info
     println!("\n=== Testing get_creator_home_info ===");
-    match get_creator_home_info(&cookies).await {
+    match get_creator_home_info(&auth, &creds).await {
         Ok(info) => {
             println!("Success!");
             println!("Name: {:?}", info.name);
@@ -0,0 +1,71 @@
+//! Compare the native Rust signer against recorded Agent signatures
+//!
+//! Record fixtures first (requires a running Agent):
+//!   python scripts/record_signatures.py
+//!
+//! Then run:
+//!   cargo run --example check_signer_parity [tests/fixtures/agent_signatures.json]
+
+use anyhow::{anyhow, Result};
+use xhs_rs::signature::{NativeSigner, SignatureRecord};
+
+fn main() -> Result<()> {
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "tests/fixtures/agent_signatures.json".to_string());
+
+    if !std::path::Path::new(&path).exists() {
+        return Err(anyhow!("{} not found! Please run 'python scripts/record_signatures.py' first.", path));
+    }
+
+    let records: Vec<SignatureRecord> = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+    println!("Loaded {} recorded signatures from {}\n", records.len(), path);
+
+    let signer = NativeSigner::new();
+    let mut failures = 0;
+
+    for record in &records {
+        let label = format!("{} {}", record.method, record.uri);
+
+        // Re-sign with the Agent's random inputs so the output is comparable
+        let Some(ctx) = NativeSigner::recover_context(&record.x_s, &record.x_s_common) else {
+            println!("❌ {} - cannot decode recorded x-s", label);
+            failures += 1;
+            continue;
+        };
+
+        let native = signer.sign_with_context(&record.request(), ctx);
+
+        let mut mismatches = Vec::new();
+        if native.x_s != record.x_s {
+            mismatches.push("x-s");
+        }
+        if native.x_t != record.x_t {
+            mismatches.push("x-t");
+        }
+        if native.x_s_common != record.x_s_common {
+            mismatches.push("x-s-common");
+        }
+        if native.x_b3_traceid.len() != record.x_b3_traceid.len() {
+            mismatches.push("x-b3-traceid (length)");
+        }
+        if native.x_xray_traceid.len() != record.x_xray_traceid.len() {
+            mismatches.push("x-xray-traceid (length)");
+        }
+
+        if mismatches.is_empty() {
+            println!("✅ {}", label);
+        } else {
+            println!("❌ {} - mismatch: {}", label, mismatches.join(", "));
+            failures += 1;
+        }
+    }
+
+    println!("\n{}/{} signatures match", records.len() - failures, records.len());
+
+    if failures > 0 {
+        return Err(anyhow!("{} signature(s) differ from the Agent output", failures));
+    }
+
+    Ok(())
+}
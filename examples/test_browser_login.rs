@@ -4,8 +4,9 @@
 //! 1. Launching a browser window
 //! 2. User scanning QR code to login
 //! 3. Capturing credentials
-//! 4. Storing to MongoDB
+//! 4. Storing to the session registry (`sessions/`)
 
+use std::path::PathBuf;
 use xhs_rs::auth::AuthService;
 use xhs_rs::utils::print_qr_to_terminal;
 
@@ -17,9 +18,9 @@ async fn main() -> anyhow::Result<()> {
     println!("║       XHS 浏览器登录测试                                    ║");
     println!("╚════════════════════════════════════════════════════════════╝\n");
     
-    // Initialize auth service with MongoDB
-    println!("正在连接 MongoDB...");
-    let auth_service = AuthService::new("mongodb://localhost:27017").await?;
+    // Initialize auth service (backend selected by XHS_STORAGE)
+    println!("正在打开凭据存储...");
+    let auth_service = AuthService::new(PathBuf::from("sessions")).await?;
     
     // Get or trigger login
     println!("正在检查凭据...\n");
@@ -28,7 +29,7 @@ async fn main() -> anyhow::Result<()> {
     println!("\n✅ 登录成功!");
     println!("   用户 ID: {}", credentials.user_id);
     println!("   Cookies: {} 个", credentials.cookies.len());
-    println!("   x-s-common: {:.50}...", credentials.x_s_common);
+    println!("   x-s-common: {:.50}...", credentials.x_s_common.unwrap_or_default());
     
     // Test QR code display
     println!("\n测试终端二维码显示:");
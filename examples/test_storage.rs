@@ -1,35 +1,44 @@
-use xhs_rs::auth::{CredentialStorage, UserCredentials};
+//! Test the configured credential storage backend
+//!
+//! Backend is selected by XHS_STORAGE (json | encrypted | sqlite).
+//! The SQLite backend additionally records credential history and validity transitions:
+//!
+//!     XHS_STORAGE=sqlite cargo run --example test_storage --features sqlite
+
+use xhs_rs::auth::storage::open_store;
+use xhs_rs::auth::UserCredentials;
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     tracing_subscriber::fmt::init();
-    
-    println!("Testing MongoDB connection...");
-    
-    // Connect to MongoDB
-    let storage = CredentialStorage::new("mongodb://localhost:27017").await?;
-    
-    println!("✅ MongoDB connected successfully!");
-    
+
+    println!("Testing credential storage...");
+
+    // Open the configured backend
+    let storage = open_store(PathBuf::from("sessions-test")).await?;
+
+    println!("✅ Opened {} storage", storage.name());
+
     // Create test credentials
     let mut cookies = HashMap::new();
     cookies.insert("a1".to_string(), "test_a1_value".to_string());
     cookies.insert("web_session".to_string(), "test_session".to_string());
-    
+
     let creds = UserCredentials::new(
         "test_user_123".to_string(),
         cookies,
-        "test_x_s_common".to_string(),
+        Some("test_x_s_common".to_string()),
     );
-    
+
     // Save credentials
     storage.save_credentials(&creds).await?;
     println!("✅ Credentials saved!");
-    
+
     // Retrieve credentials
     let retrieved = storage.get_active_credentials().await?;
-    
+
     match retrieved {
         Some(c) => {
             println!("✅ Retrieved credentials for user: {}", c.user_id);
@@ -37,18 +46,28 @@ async fn main() -> anyhow::Result<()> {
         }
         None => println!("❌ No credentials found"),
     }
-    
+
     // Invalidate
     storage.invalidate_all().await?;
     println!("✅ Credentials invalidated!");
-    
+
     // Verify invalidation
     let after_invalidate = storage.get_active_credentials().await?;
     if after_invalidate.is_none() {
         println!("✅ Verified: No active credentials after invalidation");
     }
-    
-    println!("\n🎉 All MongoDB tests passed!");
-    
+
+    // History (empty for file backends)
+    let history = storage.credential_history(&creds.user_id).await?;
+    println!("✅ {} stored versions", history.len());
+    for event in storage.validity_events(&creds.user_id).await? {
+        println!("   {} {} (valid: {})", event.at, event.event, event.is_valid);
+    }
+
+    // Clean up
+    storage.remove_credentials(&creds.user_id).await?;
+
+    println!("\n🎉 All storage tests passed!");
+
     Ok(())
 }
@@ -17,6 +17,7 @@ Endpoints:
     GET /health - Health check
 """
 import asyncio
+import inspect
 import json
 import uvicorn
 import os
@@ -49,6 +50,23 @@ app = FastAPI(
 # Initialize Xhshow client (singleton)
 xhs_client = Xhshow()
 
+# Fingerprint arguments accepted by the installed xhshow version
+SIGN_HEADERS_PARAMS = inspect.signature(xhs_client.sign_headers).parameters
+_unsupported_profile_fields = set()
+
+
+class ClientProfile(BaseModel):
+    """Browser fingerprint of the account (mirrors the Rust ClientProfile).
+
+    The Rust core sends `platform` and `sec_ch_ua` already resolved, matching the
+    `sec-ch-ua-platform` / User-Agent headers of the signed request.
+    """
+    user_agent: Optional[str] = None
+    sec_ch_ua: Optional[str] = None
+    platform: Optional[str] = None
+    mobile: bool = False
+    accept_language: Optional[str] = None
+
 
 class SignRequest(BaseModel):
     """Request model for signature generation"""
@@ -57,6 +75,22 @@ class SignRequest(BaseModel):
     cookies: Dict[str, str]
     params: Optional[Dict[str, Any]] = None
     payload: Optional[Dict[str, Any]] = None
+    profile: ClientProfile = ClientProfile()
+
+
+def profile_kwargs(profile: ClientProfile) -> Dict[str, str]:
+    """Platform / User-Agent arguments for xhs_client.sign_headers"""
+    kwargs = {}
+    for field in ("platform", "user_agent"):
+        value = getattr(profile, field)
+        if not value:
+            continue
+        if field in SIGN_HEADERS_PARAMS:
+            kwargs[field] = value
+        elif field not in _unsupported_profile_fields:
+            _unsupported_profile_fields.add(field)
+            logger.warning(f"xhshow sign_headers does not accept '{field}', signature may not match the request headers")
+    return kwargs
 
 
 class SignResponse(BaseModel):
@@ -96,7 +130,8 @@ async def generate_signature(request: SignRequest):
             uri=uri_path,
             cookies=request.cookies,
             params=params if params else None,
-            payload=request.payload
+            payload=request.payload,
+            **profile_kwargs(request.profile)
         )
         
         return SignResponse(
@@ -0,0 +1,106 @@
+"""
+Record Agent signatures as fixtures
+
+Calls the running Python Agent's /sign endpoint for every URI that
+`endpoint_to_uri` in src/api/common.rs maps, and writes the results to
+tests/fixtures/agent_signatures.json. The file is consumed by
+tests/signer_parity.rs and examples/check_signer_parity.rs to compare the
+native Rust signer against the Agent output.
+
+Usage:
+    python scripts/record_signatures.py [--agent http://127.0.0.1:8765] [--cookie cookie.json]
+"""
+import argparse
+import json
+import os
+import urllib.request
+
+# Keep in sync with endpoint_to_uri() in src/api/common.rs
+GET_URIS = [
+    "/api/sns/web/v2/user/me",
+    "/api/sns/web/v1/user/selfinfo",
+    "/api/sns/web/v1/search/querytrending",
+    "/api/sns/web/v1/you/mentions?num=20&cursor=",
+    "/api/sns/web/v1/you/connections?num=20&cursor=",
+    "/api/sns/web/v1/you/likes?num=20&cursor=",
+    "/api/sns/web/unread_count",
+]
+
+POST_URIS = {
+    "/api/sns/web/v1/homefeed": {
+        "cursor_score": "",
+        "num": 20,
+        "refresh_type": 1,
+        "note_index": 0,
+        "unread_begin_note_id": "",
+        "unread_end_note_id": "",
+        "unread_note_count": 0,
+        "category": "homefeed_recommend",
+        "search_key": "",
+        "need_num": 18,
+        "image_formats": ["jpg", "webp", "avif"],
+        "need_filter_image": False,
+    },
+    "/api/sns/web/v1/search/notes": {
+        "keyword": "test",
+        "page": 1,
+        "page_size": 20,
+        "search_id": "2fvzx0000000000000000",
+        "sort": "general",
+        "note_type": 0,
+    },
+}
+
+
+def sign(agent: str, method: str, uri: str, cookies: dict, payload=None) -> dict:
+    body = {"method": method, "uri": uri, "cookies": cookies}
+    if payload is not None:
+        body["payload"] = payload
+    req = urllib.request.Request(
+        f"{agent}/sign",
+        data=json.dumps(body).encode("utf-8"),
+        headers={"Content-Type": "application/json"},
+    )
+    with urllib.request.urlopen(req, timeout=10) as response:
+        result = json.loads(response.read().decode("utf-8"))
+    if not result.get("success"):
+        raise RuntimeError(f"Agent signing failed for {uri}: {result.get('error')}")
+    return {
+        "method": method,
+        "uri": uri,
+        "cookies": cookies,
+        "payload": payload,
+        "x_s": result["x_s"],
+        "x_t": result["x_t"],
+        "x_s_common": result["x_s_common"],
+        "x_b3_traceid": result.get("x_b3_traceid") or "",
+        "x_xray_traceid": result.get("x_xray_traceid") or "",
+    }
+
+
+def main():
+    parser = argparse.ArgumentParser(description="Record Agent signatures as fixtures")
+    parser.add_argument("--agent", default=os.environ.get("XHS_AGENT_URL", "http://127.0.0.1:8765"))
+    parser.add_argument("--cookie", default="cookie.json", help="credentials file providing a1")
+    parser.add_argument("--output", default="tests/fixtures/agent_signatures.json")
+    args = parser.parse_args()
+
+    cookies = {"a1": "187d2defea8dz1fgwydnci40kw265ikh9fsxn66qs50000726043"}
+    if os.path.exists(args.cookie):
+        with open(args.cookie, encoding="utf-8") as f:
+            stored = json.load(f).get("cookies", {})
+        if "a1" in stored:
+            cookies = {"a1": stored["a1"]}
+
+    records = [sign(args.agent, "GET", uri, cookies) for uri in GET_URIS]
+    records += [sign(args.agent, "POST", uri, cookies, payload) for uri, payload in POST_URIS.items()]
+
+    os.makedirs(os.path.dirname(args.output), exist_ok=True)
+    with open(args.output, "w", encoding="utf-8") as f:
+        json.dump(records, f, ensure_ascii=False, indent=2)
+
+    print(f"Recorded {len(records)} signatures to {args.output}")
+
+
+if __name__ == "__main__":
+    main()
@@ -24,7 +24,7 @@ impl AgentManager {
 
     /// 启动 Python Agent Server
     /// 
-    /// 在容器模式下（检测到 XHS_AGENT_URL 环境变量），跳过子进程启动
+    /// 在容器模式下（配置了 `agent.url` / XHS_AGENT_URL），跳过子进程启动
     pub fn start(&self) -> anyhow::Result<()> {
         // 容器模式：跳过子进程管理
         if crate::config::is_container_mode() {
@@ -33,18 +33,19 @@ impl AgentManager {
             return Ok(());
         }
         
+        let config = &crate::config::get_config().agent;
         let script_path = self.get_agent_script_path()?;
         
-        info!("[AgentManager] Starting Python Agent: {:?}", script_path);
+        info!("[AgentManager] Starting Python Agent: {:?} ({}, port {})", script_path, config.python, config.port);
         
-        let child = Command::new("python")
+        let child = Command::new(&config.python)
             .arg("-m")
             .arg("uvicorn")
             .arg("scripts.agent_server:app")
             .arg("--host")
             .arg("127.0.0.1")
             .arg("--port")
-            .arg("8765")
+            .arg(config.port.to_string())
             .current_dir(self.get_project_root()?)
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -0,0 +1,396 @@
+//! 响应缓存 (Response Cache)
+//!
+//! 只读接口（热搜、搜索筛选、笔记详情等）的相同请求直接返回缓存，不再消耗签名与频率额度：
+//! - 缓存键：账号 + 方法 + URI + 规范化请求体（对象键排序）
+//! - 各端点的缓存时长见 [`crate::config::CacheConfig`]，未配置的端点不缓存
+//! - 内存 LRU，可选磁盘后端（`XHS_CACHE_DIR`，重启后仍可命中）
+//! - 只缓存业务成功的响应
+//!
+//! HTTP 层由 [`cache_layer`] 处理：请求头 `Cache-Control: no-cache` 跳过缓存读取（仍会写入新结果），
+//! 响应头 `X-Cache: HIT/MISS` 标记本次请求的上游调用是否全部命中缓存。
+
+use anyhow::Result;
+use axum::{
+    extract::Request,
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use chrono::{DateTime, Utc};
+use futures::Stream;
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::auth::storage::{create_private_dir, write_atomic};
+use crate::config::get_cache_config;
+
+/// 响应缓存
+pub struct ResponseCache {
+    memory: Mutex<LruCache<String, CachedResponse>>,
+    /// 磁盘缓存目录（None 表示仅内存）
+    dir: Option<PathBuf>,
+    /// 各端点缓存时长
+    ttls: HashMap<String, Duration>,
+}
+
+/// 缓存条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedResponse {
+    key: String,
+    body: String,
+    expires_at: DateTime<Utc>,
+}
+
+impl CachedResponse {
+    fn is_fresh(&self) -> bool {
+        self.expires_at > Utc::now()
+    }
+}
+
+impl ResponseCache {
+    /// 创建缓存，`ttls` 为各端点的缓存时长（秒）
+    pub async fn new(capacity: NonZeroUsize, dir: Option<PathBuf>, ttls: HashMap<String, u64>) -> Result<Self> {
+        if let Some(dir) = &dir {
+            create_private_dir(dir).await?;
+        }
+        Ok(Self {
+            memory: Mutex::new(LruCache::new(capacity)),
+            dir,
+            ttls: ttls.into_iter().map(|(key, ttl)| (key, Duration::from_secs(ttl))).collect(),
+        })
+    }
+
+    /// 按 `XHS_CACHE_*` 配置创建缓存，容量为 0 时返回 None（禁用缓存）
+    pub async fn from_config() -> Result<Option<Self>> {
+        let config = get_cache_config();
+        let Some(capacity) = NonZeroUsize::new(config.capacity) else {
+            info!("[ResponseCache] Disabled (XHS_CACHE_CAPACITY=0)");
+            return Ok(None);
+        };
+        let cache = Self::new(capacity, config.dir.clone(), config.ttls()).await?;
+        info!(
+            "[ResponseCache] Capacity {}, disk: {}, endpoints: {:?}",
+            capacity,
+            config.dir.as_ref().map(|d| d.display().to_string()).unwrap_or_else(|| "off".to_string()),
+            config.ttls()
+        );
+        Ok(Some(cache))
+    }
+
+    /// 端点的缓存时长，未配置时返回 None（不缓存）
+    pub fn ttl(&self, endpoint: &str) -> Option<Duration> {
+        self.ttls.get(endpoint).copied()
+    }
+
+    /// 缓存键：账号 + 方法 + URI + 规范化请求体
+    pub fn key(user_id: &str, method: &str, uri: &str, payload: Option<&serde_json::Value>) -> String {
+        let payload = payload.map(|p| canonical(p).to_string()).unwrap_or_default();
+        format!("{} {} {} {}", user_id, method.to_uppercase(), uri, payload)
+    }
+
+    /// 读取未过期的缓存（先内存，后磁盘）
+    pub async fn get(&self, key: &str) -> Option<String> {
+        {
+            let mut memory = self.memory.lock().unwrap();
+            match memory.get(key) {
+                Some(entry) if entry.is_fresh() => return Some(entry.body.clone()),
+                Some(_) => {
+                    memory.pop(key);
+                }
+                None => {}
+            }
+        }
+
+        let path = self.disk_path(key)?;
+        let content = tokio::fs::read(&path).await.ok()?;
+        let entry = match serde_json::from_slice::<CachedResponse>(&content) {
+            Ok(entry) if entry.key == key && entry.is_fresh() => entry,
+            Ok(entry) if entry.key != key => return None,
+            _ => {
+                let _ = tokio::fs::remove_file(&path).await;
+                return None;
+            }
+        };
+        let body = entry.body.clone();
+        self.memory.lock().unwrap().put(key.to_string(), entry);
+        Some(body)
+    }
+
+    /// 写入缓存，`ttl` 后过期
+    pub async fn insert(&self, key: String, body: String, ttl: Duration) {
+        let expires_at = Utc::now() + chrono::Duration::from_std(ttl).unwrap_or(chrono::Duration::zero());
+        let entry = CachedResponse { key, body, expires_at };
+
+        if let Some(path) = self.disk_path(&entry.key) {
+            let result = match serde_json::to_vec(&entry) {
+                Ok(content) => write_atomic(&path, &content).await,
+                Err(e) => Err(e.into()),
+            };
+            if let Err(e) = result {
+                warn!("[ResponseCache] Failed to write {}: {}", path.display(), e);
+            }
+        }
+        self.memory.lock().unwrap().put(entry.key.clone(), entry);
+    }
+
+    fn disk_path(&self, key: &str) -> Option<PathBuf> {
+        let dir = self.dir.as_ref()?;
+        Some(dir.join(format!("{:x}.json", md5::compute(key))))
+    }
+}
+
+/// 对象键排序后的 JSON，使等价的请求体得到相同的缓存键
+fn canonical(value: &serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            serde_json::Value::Object(keys.into_iter().map(|k| (k.clone(), canonical(&map[k]))).collect())
+        }
+        serde_json::Value::Array(items) => serde_json::Value::Array(items.iter().map(canonical).collect()),
+        other => other.clone(),
+    }
+}
+
+/// 响应是否业务成功（只缓存成功的响应）
+pub(crate) fn is_cacheable(body: &str) -> bool {
+    #[derive(Deserialize)]
+    struct Status {
+        success: Option<bool>,
+        code: Option<i32>,
+    }
+    match serde_json::from_str::<Status>(body) {
+        Ok(Status { success: Some(success), .. }) => success,
+        Ok(Status { code: Some(code), .. }) => code == 0,
+        _ => false,
+    }
+}
+
+// ============================================================================
+// Axum Layer
+// ============================================================================
+
+/// 缓存命中状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    Hit,
+    Miss,
+}
+
+impl CacheStatus {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Hit => "HIT",
+            Self::Miss => "MISS",
+        }
+    }
+}
+
+/// 当前 HTTP 请求的缓存上下文
+struct RequestCache {
+    /// 跳过缓存读取 (Cache-Control: no-cache)
+    bypass: bool,
+    /// 本次请求的命中状态，任一上游调用未命中即为 MISS
+    status: Mutex<Option<CacheStatus>>,
+}
+
+tokio::task_local! {
+    static REQUEST_CACHE: RequestCache;
+}
+
+/// 当前请求是否要求跳过缓存（不在 HTTP 请求中时为 false）
+pub(crate) fn bypass_requested() -> bool {
+    REQUEST_CACHE.try_with(|c| c.bypass).unwrap_or(false)
+}
+
+/// 在当前请求的缓存上下文中轮询 `stream`
+///
+/// 流式响应在处理函数返回后才被轮询，已离开 [`cache_layer`] 的作用域；
+/// 包装后后续页面同样遵循 `Cache-Control: no-cache`（`X-Cache` 已随响应头发送，不再更新）
+pub(crate) fn in_request_scope<S>(stream: S) -> impl Stream<Item = S::Item> + Send
+where
+    S: Stream + Send + 'static,
+{
+    let bypass = bypass_requested();
+    let mut stream = Box::pin(stream);
+    futures::stream::poll_fn(move |cx| {
+        let context = RequestCache { bypass, status: Mutex::new(None) };
+        REQUEST_CACHE.sync_scope(context, || stream.as_mut().poll_next(cx))
+    })
+}
+
+/// 记录一次上游调用的命中状态
+pub(crate) fn record(status: CacheStatus) {
+    debug!("[ResponseCache] {}", status.as_str());
+    let _ = REQUEST_CACHE.try_with(|c| {
+        let mut current = c.status.lock().unwrap();
+        if *current != Some(CacheStatus::Miss) {
+            *current = Some(status);
+        }
+    });
+}
+
+/// 缓存中间件：识别 `Cache-Control: no-cache`，并在响应中添加 `X-Cache: HIT/MISS`
+///
+/// 没有经过缓存的请求（端点未配置缓存或缓存已禁用）不添加 `X-Cache`
+pub async fn cache_layer(request: Request, next: Next) -> Response {
+    let bypass = request
+        .headers()
+        .get_all(header::CACHE_CONTROL)
+        .iter()
+        .chain(request.headers().get_all(header::PRAGMA).iter())
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .any(|directive| matches!(directive.trim().to_ascii_lowercase().as_str(), "no-cache" | "no-store"));
+
+    let context = RequestCache { bypass, status: Mutex::new(None) };
+    REQUEST_CACHE
+        .scope(context, async move {
+            let mut response = next.run(request).await;
+            if let Some(status) = REQUEST_CACHE.with(|c| *c.status.lock().unwrap()) {
+                response.headers_mut().insert("x-cache", HeaderValue::from_static(status.as_str()));
+            }
+            response
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use serde_json::json;
+
+    async fn cache(capacity: usize, dir: Option<PathBuf>) -> ResponseCache {
+        let ttls = HashMap::from([("note_detail".to_string(), 600)]);
+        ResponseCache::new(NonZeroUsize::new(capacity).unwrap(), dir, ttls).await.unwrap()
+    }
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[tokio::test]
+    async fn evicts_least_recently_used_entry() {
+        let cache = cache(2, None).await;
+        cache.insert("a".to_string(), "A".to_string(), TTL).await;
+        cache.insert("b".to_string(), "B".to_string(), TTL).await;
+        assert_eq!(cache.get("a").await.as_deref(), Some("A"));
+
+        cache.insert("c".to_string(), "C".to_string(), TTL).await;
+        assert_eq!(cache.get("b").await, None);
+        assert_eq!(cache.get("a").await.as_deref(), Some("A"));
+        assert_eq!(cache.get("c").await.as_deref(), Some("C"));
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_not_served() {
+        let dir = std::env::temp_dir().join(format!("xhs-cache-{}", uuid::Uuid::new_v4()));
+        let running = cache(10, Some(dir.clone())).await;
+
+        running.insert("old".to_string(), "stale".to_string(), Duration::ZERO).await;
+        running.insert("new".to_string(), "fresh".to_string(), TTL).await;
+        assert_eq!(running.get("old").await, None);
+        assert_eq!(running.get("new").await.as_deref(), Some("fresh"));
+
+        // 重启后从磁盘读取，过期的文件被删除
+        let restarted = cache(10, Some(dir.clone())).await;
+        assert_eq!(restarted.get("new").await.as_deref(), Some("fresh"));
+        assert_eq!(restarted.get("old").await, None);
+        let files = std::fs::read_dir(&dir).unwrap().count();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(files, 1);
+    }
+
+    #[tokio::test]
+    async fn only_configured_endpoints_are_cached() {
+        let cache = cache(10, None).await;
+        assert_eq!(cache.ttl("note_detail"), Some(Duration::from_secs(600)));
+        assert_eq!(cache.ttl("search_notes"), None);
+    }
+
+    #[test]
+    fn key_covers_account_path_and_params() {
+        let payload = json!({ "keyword": "咖啡", "page": 1, "filters": [{ "type": "sort", "tags": ["general"] }] });
+        let reordered = json!({ "page": 1, "filters": [{ "tags": ["general"], "type": "sort" }], "keyword": "咖啡" });
+        let key = |user: &str, method: &str, uri: &str, payload: Option<&serde_json::Value>| {
+            ResponseCache::key(user, method, uri, payload)
+        };
+
+        let base = key("u1", "post", "/api/sns/web/v1/search/notes", Some(&payload));
+        assert_eq!(base, key("u1", "POST", "/api/sns/web/v1/search/notes", Some(&reordered)));
+        assert_ne!(base, key("u2", "POST", "/api/sns/web/v1/search/notes", Some(&payload)));
+        assert_ne!(base, key("u1", "POST", "/api/sns/web/v1/search/onebox", Some(&payload)));
+        assert_ne!(base, key("u1", "POST", "/api/sns/web/v1/search/notes", Some(&json!({ "keyword": "咖啡", "page": 2 }))));
+
+        let get = key("u1", "GET", "/api/sns/web/v1/user/otherinfo?target_user_id=a", None);
+        assert_ne!(get, key("u1", "GET", "/api/sns/web/v1/user/otherinfo?target_user_id=b", None));
+    }
+
+    #[test]
+    fn only_successful_responses_are_cacheable() {
+        assert!(is_cacheable(r#"{"success": true, "code": 0}"#));
+        assert!(is_cacheable(r#"{"code": 0}"#));
+        assert!(!is_cacheable(r#"{"success": false, "code": 0}"#));
+        assert!(!is_cacheable(r#"{"code": -100}"#));
+        assert!(!is_cacheable("<html>"));
+    }
+
+    #[tokio::test]
+    async fn no_cache_header_bypasses_cache() {
+        use axum::{middleware, routing::get, Router};
+
+        // 模拟命中缓存的处理函数，返回是否被要求跳过缓存
+        let app = Router::new()
+            .route("/hit", get(|| async {
+                let bypass = bypass_requested();
+                if !bypass {
+                    record(CacheStatus::Hit);
+                }
+                bypass.to_string()
+            }))
+            .route("/uncached", get(|| async { "ok" }))
+            .layer(middleware::from_fn(cache_layer));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let client = reqwest::Client::new();
+        let request = |path: &str, header: Option<(&'static str, &'static str)>| {
+            let mut request = client.get(format!("http://{}{}", addr, path));
+            if let Some((name, value)) = header {
+                request = request.header(name, value);
+            }
+            request.send()
+        };
+
+        let response = request("/hit", None).await.unwrap();
+        assert_eq!(response.headers()["x-cache"], "HIT");
+        assert_eq!(response.text().await.unwrap(), "false");
+
+        for header in [("cache-control", "max-age=0, no-cache"), ("pragma", "no-cache"), ("cache-control", "no-store")] {
+            let response = request("/hit", Some(header)).await.unwrap();
+            assert!(response.headers().get("x-cache").is_none());
+            assert_eq!(response.text().await.unwrap(), "true", "{:?}", header);
+        }
+
+        let response = request("/uncached", None).await.unwrap();
+        assert!(response.headers().get("x-cache").is_none());
+    }
+
+    #[tokio::test]
+    async fn stream_keeps_request_bypass_after_scope_ends() {
+        let seen = || futures::stream::repeat_with(bypass_requested).take(2);
+
+        let context = RequestCache { bypass: true, status: Mutex::new(None) };
+        let (plain, scoped) = REQUEST_CACHE.scope(context, async { (seen(), in_request_scope(seen())) }).await;
+
+        // 在作用域外轮询（流式响应体的情形）
+        assert_eq!(plain.collect::<Vec<_>>().await, [false, false]);
+        assert_eq!(scoped.collect::<Vec<_>>().await, [true, true]);
+    }
+}
@@ -7,19 +7,29 @@
 //! 使得每个具体接口只需关注 URL 和 Payload 的构造。
 //!
 //! ## 签名策略 (Signature Strategy)
-//! 1. **纯算法优先**: 调用 Python Agent 生成签名 (xhshow)
-//! 2. **浏览器兜底**: 若 Agent 不可用，回退到存储的签名
+//! 签名统一由构造时传入的 [`Signer`] 生成。降级（如 Agent → 原生 → 录制回放）
+//! 由 [`crate::signature::ChainedSigner`] 负责，客户端本身不再区分签名来源。
 
-use crate::auth::AuthService;
+use crate::api::cache::{self, CacheStatus, ResponseCache};
+use crate::api::limiter::{EndpointFamily, RateLimiter};
+use crate::api::retry::RetryPolicy;
 use crate::auth::credentials::ApiSignature;
-use crate::client::XhsClient;
-use crate::signature::{SignatureService, Signature, parse_cookie_string};
-use anyhow::{Result, anyhow};
+use crate::auth::{AuthService, UserCredentials};
+use crate::client::{set_cookie_headers, Site, XhsClient};
+use crate::signature::{SignRequest, Signature, Signer};
+use crate::error::{is_login_required_code, XhsError, XhsResult};
+use anyhow::anyhow;
+use std::future::Future;
+use tracing::Instrument;
 use std::sync::Arc;
 
-const ORIGIN: &str = "https://www.xiaohongshu.com";
-const REFERER: &str = "https://www.xiaohongshu.com/";
-const USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/143.0.0.0 Safari/537.36";
+
+/// 响应中的业务码（仅用于识别未登录 / 登录过期）
+#[derive(serde::Deserialize)]
+struct BusinessCode {
+    code: Option<i32>,
+    msg: Option<String>,
+}
 
 /// Endpoint Key 到 API URI 的映射
 /// 用于纯算法签名生成
@@ -29,21 +39,36 @@ fn endpoint_to_uri(endpoint_key: &str) -> Option<&'static str> {
         // User
         "user_me" => Some("/api/sns/web/v2/user/me"),
         "user_selfinfo" => Some("/api/sns/web/v1/user/selfinfo"),
+        "user_otherinfo" | "user_posted" => None,  // 需要 user_id 和 xsec_token，无法静态映射
         // Search
         "search_trending" => Some("/api/sns/web/v1/search/querytrending"),
         "search_notes" => Some("/api/sns/web/v1/search/notes"),
         "notification_mentions" => Some("/api/sns/web/v1/you/mentions?num=20&cursor="),
         "notification_connections" => Some("/api/sns/web/v1/you/connections?num=20&cursor="),
         "notification_likes" => Some("/api/sns/web/v1/you/likes?num=20&cursor="),
+        "notification_unread_count" => Some("/api/sns/web/unread_count"),
         // Home Feed
         "home_feed_recommend" => Some("/api/sns/web/v1/homefeed"),
         key if key.starts_with("home_feed_") => Some("/api/sns/web/v1/homefeed"),
         // Note (动态参数，需要特殊处理)
         "note_page" => None,  // 需要 note_id 和 xsec_token，无法静态映射
+        "note_sub_comments" => None,
         _ => None,
     }
 }
 
+/// API 路径到 Endpoint Key 的反向映射
+/// 用于直接指定 URI 的请求（如 post_algo），以便按端点配置缓存；未映射时使用路径本身
+fn path_to_endpoint(path: &str) -> &str {
+    match path {
+        "/api/sns/web/v1/feed" => "note_detail",
+        "/api/sns/web/v1/search/notes" => "search_notes",
+        "/api/sns/web/v1/search/onebox" => "search_onebox",
+        "/api/sns/web/v1/search/usersearch" => "search_user",
+        other => other,
+    }
+}
+
 /// 解析 URI，分离 path 和 query params
 /// 注意：空值参数会被过滤（与 Python parse_qs 默认行为一致）
 /// 例如: "/api/foo?num=20&cursor=" -> ("/api/foo", [("num", "20")])
@@ -69,118 +94,175 @@ fn parse_uri_with_params(uri: &str) -> (&str, Vec<(&str, &str)>) {
     }
 }
 
+/// 从完整 URL 中截取 edith 域名后的 URI（path + query）
+/// 例如: "https://edith.xiaohongshu.com/api/foo?a=1" -> "/api/foo?a=1"
+fn uri_from_url(url: &str) -> Option<&str> {
+    let idx = url.find("edith.xiaohongshu.com")?;
+    let uri_start = url[idx..].find('/').map(|i| idx + i).unwrap_or(url.len());
+    Some(&url[uri_start..])
+}
+
+/// 构建签名请求
+///
+/// 签名中的平台与请求头 (`sec-ch-ua-platform` / User-Agent) 都取自账号的 [`crate::client::ClientProfile`]，
+/// 传给签名器的是补全了 `platform` / `sec_ch_ua` 的副本，远程签名器无需自行推导
+fn sign_request(method: &str, uri: &str, credentials: &UserCredentials, payload: Option<serde_json::Value>) -> SignRequest {
+    SignRequest {
+        method: method.to_uppercase(),
+        uri: uri.to_string(),
+        cookies: credentials.cookies.clone(),
+        params: None,
+        payload,
+        profile: credentials.profile().resolved(),
+    }
+}
+
 /// XHS API 公共客户端
 /// 
 /// 封装了所有 API 请求的公共逻辑：
 /// - 从 AuthService 获取 Cookie
-/// - 优先使用 SignatureService (纯算法) 生成签名
-/// - 回退到存储的签名 (浏览器捕获)
+/// - 通过 [`Signer`] 生成签名（降级策略由签名器自身决定）
 /// - 构建标准浏览器 Headers
+#[derive(Clone)]
 pub struct XhsApiClient {
     http_client: XhsClient,
     auth: Arc<AuthService>,
-    signature_service: SignatureService,
+    signer: Arc<dyn Signer>,
+    /// 账号选择（None 表示默认账号）
+    account: Option<String>,
+    /// 响应缓存（None 表示不缓存）
+    cache: Option<Arc<ResponseCache>>,
+    /// 客户端限速（各账号共享）
+    limiter: Arc<RateLimiter>,
+    /// 临时错误的重试策略
+    retry: RetryPolicy,
 }
 
 impl XhsApiClient {
     /// 创建新的 API 客户端
-    pub fn new(http_client: XhsClient, auth: Arc<AuthService>) -> Self {
+    ///
+    /// # Arguments
+    /// * `signer` - 签名器，通常为 [`crate::signature::default_signer`]
+    pub fn new(http_client: XhsClient, auth: Arc<AuthService>, signer: Arc<dyn Signer>) -> Self {
         Self { 
             http_client, 
             auth,
-            signature_service: SignatureService::new(),
+            signer,
+            account: None,
+            cache: None,
+            limiter: Arc::new(RateLimiter::from_config()),
+            retry: RetryPolicy::from_config(),
         }
     }
 
+    /// 启用响应缓存（见 [`crate::api::cache`]）
+    pub fn with_cache(mut self, cache: Arc<ResponseCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// 返回绑定到指定账号的客户端（共享连接池、认证服务和签名器）
+    ///
+    /// `account` 为 None 时使用默认账号
+    pub fn with_account(&self, account: Option<String>) -> Self {
+        Self {
+            http_client: self.http_client.clone(),
+            auth: self.auth.clone(),
+            signer: self.signer.clone(),
+            account,
+            cache: self.cache.clone(),
+            limiter: self.limiter.clone(),
+            retry: self.retry,
+        }
+    }
+
+    /// 客户端限速器（创作者中心等不经过本客户端的请求也应通过它限速）
+    pub fn limiter(&self) -> &Arc<RateLimiter> {
+        &self.limiter
+    }
+
+    /// 当前绑定的账号（None 表示默认账号）
+    pub fn account(&self) -> Option<&str> {
+        self.account.as_deref()
+    }
+
+    /// 获取当前签名器
+    pub fn signer(&self) -> &Arc<dyn Signer> {
+        &self.signer
+    }
+
+    /// 当前账号的 user_id
+    pub async fn current_user_id(&self) -> XhsResult<String> {
+        Ok(self.credentials().await?.user_id)
+    }
+
     /// 获取认证服务引用
     pub fn auth(&self) -> &Arc<AuthService> {
         &self.auth
     }
 
-    /// 执行 GET 请求（纯算法优先 + 存储回退）
-    /// 
-    /// 优先使用 Python Agent 生成签名，失败时回退到存储的签名
+    /// 执行 GET 请求
     /// 
     /// # Arguments
-    /// * `endpoint_key` - 签名存储的 key（如 "search_trending", "notification_mentions"）
+    /// * `endpoint_key` - 端点标识（如 "search_trending", "notification_mentions"）
     /// 
     /// # Returns
     /// 响应文本内容
-    pub async fn get(&self, endpoint_key: &str) -> Result<String> {
-        let credentials = self.auth.try_get_credentials().await?
-            .ok_or_else(|| anyhow!("Not logged in. Please call /api/auth/login-session first."))?;
-        
-        let cookie_str = credentials.cookie_string();
-        
-        // 优先尝试纯算法签名
-        if let Some(uri) = endpoint_to_uri(endpoint_key) {
-            // 解析 URI，分离 path 和 query params
-            let (path, params) = parse_uri_with_params(uri);
-            let base_url = format!("https://edith.xiaohongshu.com{}", path);
-            
-            match self.get_algo_signature("GET", uri, &cookie_str, None).await {
-                Ok(signature) => {
-                    tracing::info!("[XhsApiClient] GET {} using ALGO (path: {}, params: {:?})", endpoint_key, path, params);
-                    // 使用 .query() 传递参数，而不是直接拼在 URL 中
-                    let response = self.build_get_request_algo(&base_url, &signature, &cookie_str)
-                        .query(&params)
-                        .send()
-                        .await?;
-                    return self.handle_response(response, endpoint_key).await;
-                }
-                Err(algo_err) => {
-                    tracing::warn!("[XhsApiClient] Algo failed for {}: {}, trying stored signature", endpoint_key, algo_err);
-                }
-            }
-        }
-        
-        // 回退到存储的签名
-        let signature = self.get_signature(endpoint_key).await?;
-        let url = signature.request_url.clone()
-            .ok_or_else(|| anyhow!("No request_url found for endpoint: {}", endpoint_key))?;
-        
-        tracing::info!("[XhsApiClient] GET {} using STORED signature", endpoint_key);
+    pub async fn get(&self, endpoint_key: &str) -> XhsResult<String> {
+        let uri = endpoint_to_uri(endpoint_key)
+            .ok_or_else(|| anyhow!("No URI mapping for endpoint: {}", endpoint_key))?;
+        let credentials = self.credentials().await?;
+
         
-        let response = self.build_get_request(&url, &signature, &cookie_str)
-            .send()
-            .await?;
+        // 解析 URI，分离 path 和 query params
+        let (path, params) = parse_uri_with_params(uri);
+        let base_url = format!("https://edith.xiaohongshu.com{}", path);
         
-        self.handle_response(response, endpoint_key).await
+        let key = self.cache_key(endpoint_key, "GET", uri, None, &credentials.user_id);
+        self.execute(endpoint_key, "GET", uri, key, || async {
+            let signature = self.get_algo_signature("GET", uri, &credentials, None).await?;
+            tracing::info!("[XhsApiClient] GET {} (path: {}, params: {:?})", endpoint_key, path, params);
+            // 使用 .query() 传递参数，而不是直接拼在 URL 中
+            let response = self.build_get_request_algo(&base_url, &signature, &credentials)
+                .query(&params)
+                .send()
+                .await?;
+            self.handle_response(response, endpoint_key, &credentials.user_id).await
+        })
+        .await
     }
 
-    /// 执行 GET 请求（纯算法签名优先）
-    /// 
-    /// 优先使用 Python Agent 生成签名，失败时回退到存储的签名
+    /// 执行 GET 请求（直接指定 URI）
     /// 
     /// # Arguments
     /// * `uri` - API 路径（如 "/api/sns/web/v1/user/selfinfo"）
     /// 
     /// # Returns
     /// 响应文本内容
-    pub async fn get_algo(&self, uri: &str) -> Result<String> {
-        let credentials = self.auth.try_get_credentials().await?
-            .ok_or_else(|| anyhow!("Not logged in. Please call /api/auth/login-session first."))?;
-        
-        let cookie_str = credentials.cookie_string();
+    pub async fn get_algo(&self, uri: &str) -> XhsResult<String> {
+        let credentials = self.credentials().await?;
+
         let url = format!("https://edith.xiaohongshu.com{}", uri);
+        let endpoint = path_to_endpoint(parse_uri_with_params(uri).0);
         
-        // 尝试纯算法签名
-        match self.get_algo_signature("GET", uri, &cookie_str, None).await {
-            Ok(signature) => {
-                tracing::info!("[XhsApiClient] GET {} using ALGO signature", uri);
-                let response = self.build_get_request_algo(&url, &signature, &cookie_str)
-                    .send()
-                    .await?;
-                self.handle_response(response, uri).await
-            }
-            Err(algo_err) => {
-                // 算法失败，记录警告并回退
-                tracing::warn!("[XhsApiClient] Algo failed for {}: {}, falling back to stored signature", uri, algo_err);
-                // 这里需要 endpoint_key 来查找存储的签名，但我们没有
-                // 对于纯算法路径，失败即失败
-                Err(algo_err)
+        let key = self.cache_key(endpoint, "GET", uri, None, &credentials.user_id);
+        self.execute(endpoint, "GET", uri, key, || async {
+            // 尝试纯算法签名
+            match self.get_algo_signature("GET", uri, &credentials, None).await {
+                Ok(signature) => {
+                    tracing::info!("[XhsApiClient] GET {} using ALGO signature", uri);
+                    let response = self.build_get_request_algo(&url, &signature, &credentials)
+                        .send()
+                        .await?;
+                    self.handle_response(response, uri, &credentials.user_id).await
+                }
+                Err(algo_err) => {
+                    tracing::warn!("[XhsApiClient] Signing failed for {}: {}", uri, algo_err);
+                    Err(algo_err)
+                }
             }
-        }
+        })
+        .await
     }
 
     /// 执行带动态查询参数的 GET 请求（纯算法签名）
@@ -193,131 +275,71 @@ impl XhsApiClient {
     /// 
     /// # Returns
     /// 响应文本内容
-    pub async fn get_with_query(&self, uri: &str) -> Result<String> {
-        let credentials = self.auth.try_get_credentials().await?
-            .ok_or_else(|| anyhow!("Not logged in. Please call /api/auth/login-session first."))?;
-        
-        let cookie_str = credentials.cookie_string();
+    pub async fn get_with_query(&self, uri: &str) -> XhsResult<String> {
+        let credentials = self.credentials().await?;
+
         
         // 解析 URI，分离 path 和 query params（与 get 方法相同逻辑）
         let (path, params) = parse_uri_with_params(uri);
         let base_url = format!("https://edith.xiaohongshu.com{}", path);
+        let endpoint = path_to_endpoint(path);
         
-        // 尝试纯算法签名
-        match self.get_algo_signature("GET", uri, &cookie_str, None).await {
-            Ok(signature) => {
-                tracing::info!("[XhsApiClient] GET {} using ALGO (path: {}, params: {:?})", uri, path, params);
-                // 使用 .query() 传递参数，保持与 get 方法一致
-                let response = self.build_get_request_algo(&base_url, &signature, &cookie_str)
-                    .query(&params)
-                    .send()
-                    .await?;
-                self.handle_response(response, uri).await
-            }
-            Err(algo_err) => {
-                tracing::warn!("[XhsApiClient] Algo failed for {}: {}", uri, algo_err);
-                Err(algo_err)
-            }
-        }
-    }
-
-    /// 执行带自定义 URL 的 GET 请求（纯算法优先）
-    /// 
-    /// 用于需要动态构造 URL 参数的接口（如 note_page）
-    /// 优先使用 Python Agent 纯算法签名
-    /// 
-    /// # Arguments
-    /// * `endpoint_key` - 端点标识（用于日志和回退）
-    /// * `url` - 完整的请求 URL（含查询参数）
-    pub async fn get_with_url(&self, endpoint_key: &str, url: &str) -> Result<String> {
-        let credentials = self.auth.try_get_credentials().await?
-            .ok_or_else(|| anyhow!("Not logged in. Please call /api/auth/login-session first."))?;
-        
-        let cookie_str = credentials.cookie_string();
-        
-        // 从 URL 中解析 path 和 params
-        if let Some(idx) = url.find("edith.xiaohongshu.com") {
-            let uri_start = url[idx..].find('/').map(|i| idx + i).unwrap_or(url.len());
-            let uri = &url[uri_start..];
-            
-            // 解析 path 和 params
-            // let (path, params) = parse_uri_with_params(uri);
-            
+        let key = self.cache_key(endpoint, "GET", uri, None, &credentials.user_id);
+        self.execute(endpoint, "GET", uri, key, || async {
             // 尝试纯算法签名
-            match self.get_algo_signature("GET", uri, &cookie_str, None).await {
+            match self.get_algo_signature("GET", uri, &credentials, None).await {
                 Ok(signature) => {
-                    // Use URL directly to avoid double encoding of query params by reqwest
-                    tracing::info!("[XhsApiClient] GET {} using ALGO (url: {})", endpoint_key, url);
-                    let response = self.build_get_request_algo(url, &signature, &cookie_str)
+                    tracing::info!("[XhsApiClient] GET {} using ALGO (path: {}, params: {:?})", uri, path, params);
+                    // 使用 .query() 传递参数，保持与 get 方法一致
+                    let response = self.build_get_request_algo(&base_url, &signature, &credentials)
+                        .query(&params)
                         .send()
                         .await?;
-                    return self.handle_response(response, endpoint_key).await;
+                    self.handle_response(response, uri, &credentials.user_id).await
                 }
                 Err(algo_err) => {
-                    tracing::warn!("[XhsApiClient] Algo failed for {}: {}, trying stored signature", endpoint_key, algo_err);
+                    tracing::warn!("[XhsApiClient] Signing failed for {}: {}", uri, algo_err);
+                    Err(algo_err)
                 }
             }
-        }
-        
-        // 回退到存储的签名
-        let signature = self.get_signature(endpoint_key).await?;
-        
-        tracing::info!("[XhsApiClient] GET {} with custom URL using STORED signature", endpoint_key);
-        
-        let response = self.build_get_request(url, &signature, &cookie_str)
-            .send()
-            .await?;
-        
-        self.handle_response(response, endpoint_key).await
+        })
+        .await
     }
 
-    /// 执行 POST 请求（纯算法优先 + 存储回退）
+    /// 执行带自定义 URL 的 GET 请求
     /// 
-    /// 优先使用 Python Agent 生成签名
+    /// 用于需要动态构造 URL 参数的接口（如 note_page）
     /// 
     /// # Arguments
-    /// * `endpoint_key` - 签名存储的 key（如 "home_feed_recommend"）
-    pub async fn post(&self, endpoint_key: &str) -> Result<String> {
-        let credentials = self.auth.try_get_credentials().await?
-            .ok_or_else(|| anyhow!("Not logged in. Please call /api/auth/login-session first."))?;
-        
-        let cookie_str = credentials.cookie_string();
-        
-        // 优先尝试纯算法签名
-        if let Some(uri) = endpoint_to_uri(endpoint_key) {
-            let url = format!("https://edith.xiaohongshu.com{}", uri);
-            
-            // 构建 Home Feed 的默认 payload
-            let payload = self.build_default_payload(endpoint_key);
-            let body = serde_json::to_string(&payload)?;
-            
-            match self.get_algo_signature("POST", uri, &cookie_str, Some(payload)).await {
-                Ok(signature) => {
-                    tracing::info!("[XhsApiClient] POST {} using ALGO", endpoint_key);
-                    let response = self.build_post_request_algo(&url, &signature, &cookie_str, body)
-                        .send()
-                        .await?;
-                    return self.handle_response(response, endpoint_key).await;
-                }
-                Err(algo_err) => {
-                    tracing::warn!("[XhsApiClient] Algo failed for {}: {}, trying stored signature", endpoint_key, algo_err);
-                }
-            }
-        }
-        
-        // 回退到存储的签名
-        let signature = self.get_signature(endpoint_key).await?;
-        let url = signature.request_url.clone()
-            .unwrap_or_else(|| format!("https://edith.xiaohongshu.com/api/sns/web/v1/{}", endpoint_key));
-        let body = signature.post_body.clone().unwrap_or_default();
-        
-        tracing::info!("[XhsApiClient] POST {} using STORED signature", endpoint_key);
-        
-        let response = self.build_post_request(&url, &signature, &cookie_str, body)
-            .send()
-            .await?;
-        
-        self.handle_response(response, endpoint_key).await
+    /// * `endpoint_key` - 端点标识（用于日志）
+    /// * `url` - 完整的请求 URL（含查询参数）
+    pub async fn get_with_url(&self, endpoint_key: &str, url: &str) -> XhsResult<String> {
+        let credentials = self.credentials().await?;
+
+        let uri = uri_from_url(url)
+            .ok_or_else(|| anyhow!("Not an edith.xiaohongshu.com URL: {}", url))?;
+        
+        let key = self.cache_key(endpoint_key, "GET", uri, None, &credentials.user_id);
+        self.execute(endpoint_key, "GET", uri, key, || async {
+            let signature = self.get_algo_signature("GET", uri, &credentials, None).await?;
+            // Use URL directly to avoid double encoding of query params by reqwest
+            tracing::info!("[XhsApiClient] GET {} (url: {})", endpoint_key, url);
+            let response = self.build_get_request_algo(url, &signature, &credentials)
+                .send()
+                .await?;
+            self.handle_response(response, endpoint_key, &credentials.user_id).await
+        })
+        .await
+    }
+
+    /// 执行 POST 请求（使用默认 payload）
+    /// 
+    /// # Arguments
+    /// * `endpoint_key` - 端点标识（如 "home_feed_recommend"）
+    pub async fn post(&self, endpoint_key: &str) -> XhsResult<String> {
+        // 构建 Home Feed 的默认 payload
+        let payload = self.build_default_payload(endpoint_key);
+        self.post_with_payload(endpoint_key, payload).await
     }
 
     /// 构建 Home Feed 请求的默认 Payload
@@ -353,43 +375,31 @@ impl XhsApiClient {
     /// 用于 homefeed 等需要用户控制分页参数的接口
     /// 
     /// # Arguments
-    /// * `endpoint_key` - 签名存储的 key（如 "home_feed_fashion"）
+    /// * `endpoint_key` - 端点标识（如 "home_feed_fashion"）
     /// * `payload` - 用户提供的完整请求体
-    pub async fn post_with_payload(&self, endpoint_key: &str, payload: serde_json::Value) -> Result<String> {
-        let credentials = self.auth.try_get_credentials().await?
-            .ok_or_else(|| anyhow!("Not logged in. Please call /api/auth/login-session first."))?;
-        
-        let cookie_str = credentials.cookie_string();
+    pub async fn post_with_payload(&self, endpoint_key: &str, payload: serde_json::Value) -> XhsResult<String> {
+        let uri = endpoint_to_uri(endpoint_key)
+            .ok_or_else(|| anyhow!("No URI mapping for endpoint: {}", endpoint_key))?;
+        let credentials = self.credentials().await?;
+
+        let url = format!("https://edith.xiaohongshu.com{}", uri);
+        let body = serde_json::to_string(&payload)?;
         
-        // 优先尝试纯算法签名
-        if let Some(uri) = endpoint_to_uri(endpoint_key) {
-            let url = format!("https://edith.xiaohongshu.com{}", uri);
-            let body = serde_json::to_string(&payload)?;
-            
+        let key = self.cache_key(endpoint_key, "POST", uri, Some(&payload), &credentials.user_id);
+        self.execute(endpoint_key, "POST", uri, key, || async {
             // DEBUG: 输出实际发送的 body
             tracing::info!("[XhsApiClient] POST {} body: {}", endpoint_key, body);
             
-            match self.get_algo_signature("POST", uri, &cookie_str, Some(payload)).await {
-                Ok(signature) => {
-                    tracing::info!("[XhsApiClient] POST {} with custom payload using ALGO", endpoint_key);
-                    let response = self.build_post_request_algo(&url, &signature, &cookie_str, body)
-                        .send()
-                        .await?;
-                    return self.handle_response(response, endpoint_key).await;
-                }
-                Err(algo_err) => {
-                    tracing::warn!("[XhsApiClient] Algo failed for {}: {}", endpoint_key, algo_err);
-                    return Err(algo_err);
-                }
-            }
-        }
-        
-        Err(anyhow!("No URI mapping for endpoint: {}", endpoint_key))
+            let signature = self.get_algo_signature("POST", uri, &credentials, Some(payload.clone())).await?;
+            let response = self.build_post_request_algo(&url, &signature, &credentials, body.clone())
+                .send()
+                .await?;
+            self.handle_response(response, endpoint_key, &credentials.user_id).await
+        })
+        .await
     }
 
-    /// 执行 POST 请求（纯算法签名优先）
-    /// 
-    /// 优先使用 Python Agent 生成签名
+    /// 执行 POST 请求（直接指定 URI）
     /// 
     /// # Arguments
     /// * `uri` - API 路径（如 "/api/sns/web/v1/homefeed"）
@@ -397,192 +407,246 @@ impl XhsApiClient {
     /// 
     /// # Returns
     /// 响应文本内容
-    pub async fn post_algo(&self, uri: &str, payload: serde_json::Value) -> Result<String> {
-        let credentials = self.auth.try_get_credentials().await?
-            .ok_or_else(|| anyhow!("Not logged in. Please call /api/auth/login-session first."))?;
-        
-        let cookie_str = credentials.cookie_string();
+    pub async fn post_algo(&self, uri: &str, payload: serde_json::Value) -> XhsResult<String> {
+        let credentials = self.credentials().await?;
+
         let url = format!("https://edith.xiaohongshu.com{}", uri);
         let body = serde_json::to_string(&payload)?;
         
-        // DEBUG: 输出实际发送的 payload
-        tracing::info!("[XhsApiClient] POST {} payload: {}", uri, body);
+        let endpoint = path_to_endpoint(parse_uri_with_params(uri).0);
         
-        // 尝试纯算法签名
-        match self.get_algo_signature("POST", uri, &cookie_str, Some(payload)).await {
-            Ok(signature) => {
-                tracing::info!("[XhsApiClient] POST {} using ALGO signature", uri);
-                let response = self.build_post_request_algo(&url, &signature, &cookie_str, body)
-                    .send()
-                    .await?;
-                self.handle_response(response, uri).await
-            }
-            Err(algo_err) => {
-                tracing::warn!("[XhsApiClient] Algo failed for {}: {}", uri, algo_err);
-                Err(algo_err)
+        let key = self.cache_key(endpoint, "POST", uri, Some(&payload), &credentials.user_id);
+        self.execute(endpoint, "POST", uri, key, || async {
+            // DEBUG: 输出实际发送的 payload
+            tracing::info!("[XhsApiClient] POST {} payload: {}", uri, body);
+            
+            // 尝试纯算法签名
+            match self.get_algo_signature("POST", uri, &credentials, Some(payload.clone())).await {
+                Ok(signature) => {
+                    tracing::info!("[XhsApiClient] POST {} using ALGO signature", uri);
+                    let response = self.build_post_request_algo(&url, &signature, &credentials, body.clone())
+                        .send()
+                        .await?;
+                    self.handle_response(response, uri, &credentials.user_id).await
+                }
+                Err(algo_err) => {
+                    tracing::warn!("[XhsApiClient] Signing failed for {}: {}", uri, algo_err);
+                    Err(algo_err)
+                }
             }
-        }
+        })
+        .await
     }
 
     /// 执行带自定义 body 的 POST 请求
     /// 
-    /// 用于需要动态构造请求体的接口
-    pub async fn post_with_body(&self, endpoint_key: &str, url: &str, body: String) -> Result<String> {
-        let credentials = self.auth.try_get_credentials().await?
-            .ok_or_else(|| anyhow!("Not logged in. Please call /api/auth/login-session first."))?;
-        let signature = self.get_signature(endpoint_key).await?;
-        
-        tracing::info!("[XhsApiClient] POST {} with custom body_len: {}", endpoint_key, body.len());
-        
-        let response = self.build_post_request(url, &signature, &credentials.cookie_string(), body)
-            .send()
-            .await?;
+    /// 用于需要动态构造请求体的接口，body 必须是 JSON（参与签名）
+    pub async fn post_with_body(&self, endpoint_key: &str, url: &str, body: String) -> XhsResult<String> {
+        let credentials = self.credentials().await?;
+
+        let uri = uri_from_url(url)
+            .ok_or_else(|| anyhow!("Not an edith.xiaohongshu.com URL: {}", url))?;
+        let payload: serde_json::Value = crate::error::decode(&body)?;
         
-        self.handle_response(response, endpoint_key).await
+        let key = self.cache_key(endpoint_key, "POST", uri, Some(&payload), &credentials.user_id);
+        self.execute(endpoint_key, "POST", uri, key, || async {
+            tracing::info!("[XhsApiClient] POST {} with custom body_len: {}", endpoint_key, body.len());
+            
+            let signature = self.get_algo_signature("POST", uri, &credentials, Some(payload.clone())).await?;
+            let response = self.build_post_request_algo(url, &signature, &credentials, body.clone())
+                .send()
+                .await?;
+            
+            self.handle_response(response, endpoint_key, &credentials.user_id).await
+        })
+        .await
     }
 
     // ==================== 私有辅助方法 ====================
 
-    /// 获取指定接口的签名（从存储）
-    /// 兜底方法，当纯算法失败时使用
-    async fn get_signature(&self, endpoint_key: &str) -> Result<ApiSignature> {
-        self.auth.get_endpoint_signature(endpoint_key).await?
-            .ok_or_else(|| anyhow!(
-                "No signature found for endpoint: {}. Please login again to capture signatures.", 
-                endpoint_key
-            ))
+    /// 获取当前账号的凭据
+    async fn credentials(&self) -> XhsResult<UserCredentials> {
+        let credentials = self.auth.try_get_credentials_for(self.account.as_deref()).await?;
+        match (&self.account, credentials) {
+            (_, Some(credentials)) => Ok(credentials),
+            (Some(account), None) => Err(XhsError::AccountNotFound(account.clone())),
+            (None, None) => Err(XhsError::NotLoggedIn),
+        }
     }
 
-    /// 获取纯算法签名
-    /// 通过 Python Agent 调用 xhshow 库生成签名
+    /// 缓存键；未启用缓存或端点未配置缓存时返回 None
+    fn cache_key(
+        &self,
+        endpoint: &str,
+        method: &str,
+        uri: &str,
+        payload: Option<&serde_json::Value>,
+        user_id: &str,
+    ) -> Option<String> {
+        self.cache.as_ref()?.ttl(endpoint)?;
+        Some(ResponseCache::key(user_id, method, uri, payload))
+    }
+
+    /// 执行请求：缓存命中时直接返回（不签名、不请求），否则经限速与重试执行 `attempt` 并缓存成功的响应
+    ///
+    /// `attempt` 每次调用都会重新签名并发送一次请求
+    async fn execute<F, Fut>(
+        &self,
+        endpoint: &str,
+        method: &str,
+        uri: &str,
+        key: Option<String>,
+        attempt: F,
+    ) -> XhsResult<String>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = XhsResult<String>>,
+    {
+        let fetch = self.with_retry_policy(endpoint, method, uri, attempt);
+        let (Some(cache), Some(key)) = (&self.cache, key) else {
+            return fetch.await;
+        };
+
+        if !cache::bypass_requested() {
+            if let Some(body) = cache.get(&key).await {
+                tracing::info!("[XhsApiClient] {} served from cache", endpoint);
+                cache::record(CacheStatus::Hit);
+                return Ok(body);
+            }
+        }
+        cache::record(CacheStatus::Miss);
+
+        let body = fetch.await?;
+        if let Some(ttl) = cache.ttl(endpoint).filter(|_| cache::is_cacheable(&body)) {
+            cache.insert(key, body.clone(), ttl).await;
+        }
+        Ok(body)
+    }
+
+    /// 按重试策略执行请求，每次尝试都经过限速器，并记录在 `xhs_attempt` span 中；
+    /// 重新签名次数用尽后仍为 406 时计入限速器的退避与熔断
+    async fn with_retry_policy<F, Fut>(&self, endpoint: &str, method: &str, uri: &str, attempt: F) -> XhsResult<String>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = XhsResult<String>>,
+    {
+        let family = EndpointFamily::from_uri(uri);
+        let max_attempts = self.retry.attempts_for(method, endpoint);
+
+        let result = self.retry
+            .run(endpoint, max_attempts, |number| {
+                let span = tracing::info_span!(
+                    "xhs_attempt",
+                    endpoint,
+                    method,
+                    attempt = number,
+                    max_attempts,
+                    outcome = tracing::field::Empty,
+                );
+                let request = self.limiter.run(family, attempt()).instrument(span.clone());
+                async move {
+                    let result = request.await;
+                    span.record("outcome", result.as_ref().map_or_else(|e| e.kind(), |_| "ok"));
+                    result
+                }
+            })
+            .await;
+        self.limiter.observe_exhausted(family, &result);
+        result
+    }
+
+    /// 获取签名
+    /// 由当前签名器生成（Agent / 原生 / 回放，或它们的链式组合）
     async fn get_algo_signature(
         &self, 
         method: &str, 
         uri: &str, 
-        cookie_str: &str,
+        credentials: &UserCredentials,
         payload: Option<serde_json::Value>,
-    ) -> Result<Signature> {
-        let cookies = parse_cookie_string(cookie_str);
-        self.signature_service
-            .get_signature_from_agent(method, uri, cookies, payload)
-            .await
+    ) -> XhsResult<Signature> {
+        let request = sign_request(method, uri, credentials, payload);
+        tracing::debug!("[XhsApiClient] Signing {} {} with {} signer", method, uri, self.signer.name());
+        let signature = self.signer.sign(&request).await?;
+        self.record_signature(&request, &signature);
+        Ok(signature)
+    }
+
+    /// 按端点记录生成的签名（仅 SQLite 存储，后台写入，不阻塞请求）
+    fn record_signature(&self, request: &SignRequest, signature: &Signature) {
+        if !self.auth.records_signatures() {
+            return;
+        }
+        let record = ApiSignature {
+            endpoint: path_to_endpoint(parse_uri_with_params(&request.uri).0).to_string(),
+            x_s: signature.x_s.clone(),
+            x_t: signature.x_t.clone(),
+            x_s_common: signature.x_s_common.clone(),
+            x_b3_traceid: signature.x_b3_traceid.clone(),
+            x_xray_traceid: signature.x_xray_traceid.clone(),
+            method: Some(request.method.clone()),
+            post_body: request.payload.as_ref().map(|p| p.to_string()),
+            request_url: Some(request.uri.clone()),
+            captured_at: chrono::Utc::now(),
+            is_valid: true,
+        };
+        let auth = self.auth.clone();
+        tokio::spawn(async move {
+            if let Err(e) = auth.save_api_signature(&record).await {
+                tracing::warn!("[XhsApiClient] Failed to record signature for {}: {}", record.endpoint, e);
+            }
+        });
     }
 
     /// 构建 GET 请求（使用纯算法签名）
-    fn build_get_request_algo(&self, url: &str, signature: &Signature, cookie: &str) -> reqwest::RequestBuilder {
+    fn build_get_request_algo(&self, url: &str, signature: &Signature, credentials: &UserCredentials) -> reqwest::RequestBuilder {
         self.http_client.get_client()
             .get(url)
-            .header("accept", "application/json, text/plain, */*")
-            .header("accept-language", "zh-CN,zh;q=0.9")
+            .headers(credentials.profile().headers(Site::Web))
             .header("cache-control", "no-cache")
             .header("pragma", "no-cache")
             .header("priority", "u=1, i")
-            .header("sec-ch-ua", r#""Google Chrome";v="143", "Chromium";v="143", "Not A(Brand";v="24""#)
-            .header("sec-ch-ua-mobile", "?0")
-            .header("sec-ch-ua-platform", r#""Windows""#)
             .header("sec-fetch-dest", "empty")
             .header("sec-fetch-mode", "cors")
             .header("sec-fetch-site", "same-site")
-            .header("user-agent", USER_AGENT)
-            .header("origin", ORIGIN)
-            .header("referer", REFERER)
             .header("x-s", &signature.x_s)
             .header("x-t", &signature.x_t)
             .header("x-s-common", &signature.x_s_common)
             .header("x-b3-traceid", &signature.x_b3_traceid)
             .header("x-xray-traceid", &signature.x_xray_traceid)
-            .header("cookie", cookie)
+            .header("cookie", credentials.cookie_string())
     }
 
     /// 构建 POST 请求（使用纯算法签名）
-    fn build_post_request_algo(&self, url: &str, signature: &Signature, cookie: &str, body: String) -> reqwest::RequestBuilder {
+    fn build_post_request_algo(&self, url: &str, signature: &Signature, credentials: &UserCredentials, body: String) -> reqwest::RequestBuilder {
         self.http_client.get_client()
             .post(url)
-            .header("accept", "application/json, text/plain, */*")
-            .header("accept-language", "zh-CN,zh;q=0.9")
+            .headers(credentials.profile().headers(Site::Web))
             .header("cache-control", "no-cache")  // 修复：添加缺失的 header
             .header("content-type", "application/json;charset=UTF-8")
             .header("pragma", "no-cache")  // 修复：添加缺失的 header
             .header("priority", "u=1, i")
-            .header("sec-ch-ua", r#""Google Chrome";v="143", "Chromium";v="143", "Not A(Brand";v="24""#)
-            .header("sec-ch-ua-mobile", "?0")
-            .header("sec-ch-ua-platform", r#""Windows""#)
             .header("sec-fetch-dest", "empty")
             .header("sec-fetch-mode", "cors")
             .header("sec-fetch-site", "same-site")
-            .header("user-agent", USER_AGENT)
-            .header("origin", ORIGIN)
-            .header("referer", REFERER)
             .header("x-s", &signature.x_s)
             .header("x-t", &signature.x_t)
             .header("x-s-common", &signature.x_s_common)
             .header("x-b3-traceid", &signature.x_b3_traceid)
             .header("x-xray-traceid", &signature.x_xray_traceid)
-            .header("cookie", cookie)
+            .header("cookie", credentials.cookie_string())
             .body(body)
     }
 
-    /// 构建 GET 请求（含所有 headers）
-    fn build_get_request(&self, url: &str, signature: &ApiSignature, cookie: &str) -> reqwest::RequestBuilder {
-        self.http_client.get_client()
-            .get(url)
-            // Standard browser headers
-            .header("accept", "application/json, text/plain, */*")
-            .header("accept-language", "zh-CN,zh;q=0.9")
-            .header("cache-control", "no-cache")
-            .header("pragma", "no-cache")
-            .header("priority", "u=1, i")
-            // Security headers
-            .header("sec-ch-ua", r#""Google Chrome";v="143", "Chromium";v="143", "Not A(Brand";v="24""#)
-            .header("sec-ch-ua-mobile", "?0")
-            .header("sec-ch-ua-platform", r#""Windows""#)
-            .header("sec-fetch-dest", "empty")
-            .header("sec-fetch-mode", "cors")
-            .header("sec-fetch-site", "same-site")
-            .header("user-agent", USER_AGENT)
-            // XHS specific headers
-            .header("origin", ORIGIN)
-            .header("referer", REFERER)
-            .header("x-s", &signature.x_s)
-            .header("x-t", &signature.x_t)
-            .header("x-s-common", &signature.x_s_common)
-            .header("x-b3-traceid", &signature.x_b3_traceid)
-            .header("x-xray-traceid", &signature.x_xray_traceid)
-            .header("cookie", cookie)
-    }
-
-    /// 构建 POST 请求（含所有 headers）
-    fn build_post_request(&self, url: &str, signature: &ApiSignature, cookie: &str, body: String) -> reqwest::RequestBuilder {
-        self.http_client.get_client()
-            .post(url)
-            // Standard browser headers
-            .header("accept", "application/json, text/plain, */*")
-            .header("accept-language", "zh-CN,zh;q=0.9")
-            .header("content-type", "application/json;charset=UTF-8")
-            .header("priority", "u=1, i")
-            // Security headers
-            .header("sec-ch-ua", r#""Google Chrome";v="143", "Chromium";v="143", "Not A(Brand";v="24""#)
-            .header("sec-ch-ua-mobile", "?0")
-            .header("sec-ch-ua-platform", r#""Windows""#)
-            .header("sec-fetch-dest", "empty")
-            .header("sec-fetch-mode", "cors")
-            .header("sec-fetch-site", "same-site")
-            .header("user-agent", USER_AGENT)
-            // XHS specific headers
-            .header("origin", ORIGIN)
-            .header("referer", REFERER)
-            .header("x-s", &signature.x_s)
-            .header("x-t", &signature.x_t)
-            .header("x-s-common", &signature.x_s_common)
-            .header("x-b3-traceid", &signature.x_b3_traceid)
-            .header("x-xray-traceid", &signature.x_xray_traceid)
-            .header("xy-direction", "98")
-            .header("cookie", cookie)
-            .body(body)
-    }
+    /// 处理响应（Cookie 合并 + 日志 + 错误状态码处理）
+    ///
+    /// 响应中的 `Set-Cookie`（刷新的 web_session、acw_tc 等）合并回 `user_id` 的凭据并持久化，
+    /// 后续请求使用更新后的 Cookie。
+    ///
+    /// XHS 返回未登录 / 登录过期（HTTP 401 或业务码）时，将 `user_id` 的会话标记为失效，
+    /// 并返回 [`XhsError::SessionExpired`]，避免继续使用失效的 web_session 请求
+    async fn handle_response(&self, response: reqwest::Response, endpoint_key: &str, user_id: &str) -> XhsResult<String> {
+        merge_response_cookies(&self.auth, user_id, &response, endpoint_key).await;
 
-    /// 处理响应（日志 + 错误状态码处理）
-    async fn handle_response(&self, response: reqwest::Response, endpoint_key: &str) -> Result<String> {
         let status = response.status();
         let text = response.text().await?;
         
@@ -595,30 +659,179 @@ impl XhsApiClient {
                     "[XhsApiClient] {} received 406 - signature may be invalid (cookies are still valid)",
                     endpoint_key
                 );
+                return Err(XhsError::SignatureRejected(text));
             }
             461 => {
                 tracing::warn!(
                     "[XhsApiClient] {} received 461 - XHS rate limit or risk control triggered",
                     endpoint_key
                 );
-                return Err(anyhow!(
-                    "XHS 风控触发 (461): 请稍后重试或更换关键词。Response: {}",
-                    text
-                ));
+                return Err(XhsError::RiskControl461(text));
+            }
+            401 => {
+                return Err(self.expire_session(user_id, endpoint_key, 401, text).await);
             }
             status_code if status_code >= 400 => {
                 tracing::warn!(
                     "[XhsApiClient] {} received {} - request failed",
                     endpoint_key, status_code
                 );
-                return Err(anyhow!(
-                    "XHS API 错误 ({}): {}",
-                    status_code, text
-                ));
+                return Err(XhsError::Upstream {
+                    code: status_code as i32,
+                    msg: text,
+                });
             }
             _ => {}
         }
         
+        // 业务码：HTTP 200 但提示未登录 / 登录过期
+        if let Ok(BusinessCode { code: Some(code), msg }) = serde_json::from_str::<BusinessCode>(&text) {
+            if is_login_required_code(code) {
+                return Err(self.expire_session(user_id, endpoint_key, code, msg.unwrap_or(text)).await);
+            }
+        }
+        
         Ok(text)
     }
+
+    /// 将会话标记为失效，返回对应错误
+    async fn expire_session(&self, user_id: &str, endpoint_key: &str, code: i32, msg: String) -> XhsError {
+        tracing::warn!(
+            "[XhsApiClient] {} reported login required ({}) - invalidating session {}",
+            endpoint_key, code, user_id
+        );
+        if let Err(e) = self.auth.invalidate_account(user_id).await {
+            tracing::warn!("[XhsApiClient] Failed to invalidate session {}: {}", user_id, e);
+        }
+        XhsError::SessionExpired { account: user_id.to_string(), code, msg }
+    }
+}
+
+/// 将响应中的 `Set-Cookie` 合并到 `auth` 中 `user_id` 的凭据（主站与创作者中心共用）
+///
+/// 只有登录相关的 Cookie 变化才会持久化（见 [`AuthService::merge_set_cookies`]），
+/// 失败只记录日志，不影响本次响应
+pub async fn merge_response_cookies(auth: &AuthService, user_id: &str, response: &reqwest::Response, endpoint_key: &str) {
+    let set_cookies = set_cookie_headers(response.headers());
+    if set_cookies.is_empty() {
+        return;
+    }
+    if let Err(e) = auth.merge_set_cookies(user_id, &set_cookies).await {
+        tracing::warn!("[XhsApiClient] {} failed to save refreshed cookies of {}: {}", endpoint_key, user_id, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::ClientProfile;
+    use crate::signature::NativeSigner;
+    use axum::{extract::Path, http::StatusCode, routing::get, Json, Router};
+    use std::collections::HashMap;
+
+    #[test]
+    fn sign_request_uses_the_account_profile_of_the_headers() {
+        let mut credentials = UserCredentials::new("u1".to_string(), HashMap::from([("a1".to_string(), "a".to_string())]), None);
+        credentials.profile = Some(ClientProfile {
+            user_agent: "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36".to_string(),
+            ..ClientProfile::default()
+        });
+
+        let headers = credentials.profile().headers(Site::Web);
+        let request = sign_request("get", "/api/sns/web/v2/user/me", &credentials, None);
+        let profile = &request.profile;
+
+        assert_eq!(request.method, "GET");
+        assert_eq!(headers["user-agent"], profile.user_agent.as_str());
+        assert_eq!(headers["sec-ch-ua-platform"], format!("\"{}\"", profile.platform()).as_str());
+        assert_eq!(headers["sec-ch-ua"], profile.sec_ch_ua.as_deref().unwrap());
+
+        // Agent 收到的 JSON 中显式带有平台
+        let body = serde_json::to_value(&request).unwrap();
+        assert_eq!(body["profile"]["platform"], "macOS");
+        assert_eq!(body["profile"]["user_agent"], credentials.profile().user_agent.as_str());
+    }
+
+    /// Local XHS stub: `/status/{code}` answers with that HTTP status,
+    /// `/code/{code}` answers 200 with that business code
+    async fn stub() -> String {
+        let app = Router::new()
+            .route(
+                "/status/:code",
+                get(|Path(code): Path<u16>| async move { (StatusCode::from_u16(code).unwrap(), "blocked") }),
+            )
+            .route(
+                "/code/:code",
+                get(|Path(code): Path<i32>| async move {
+                    Json(serde_json::json!({ "code": code, "success": code == 0, "msg": format!("msg {}", code), "data": {} }))
+                }),
+            );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        base
+    }
+
+    /// Fresh client with a valid session for `u1`
+    async fn client() -> (XhsApiClient, std::path::PathBuf) {
+        let dir = std::env::temp_dir().join(format!("xhs-common-{}", uuid::Uuid::new_v4()));
+        let auth = Arc::new(AuthService::new(dir.clone()).await.unwrap());
+        let cookies = HashMap::from([("a1".to_string(), "a".to_string()), ("web_session".to_string(), "s".to_string())]);
+        auth.save_credentials(&UserCredentials::new("u1".to_string(), cookies, None)).await.unwrap();
+        (XhsApiClient::new(XhsClient::new().unwrap(), auth, Arc::new(NativeSigner::new())), dir)
+    }
+
+    async fn handle(base: &str, path: &str) -> (XhsResult<String>, bool) {
+        let (api, dir) = client().await;
+        let response = reqwest::get(format!("{}{}", base, path)).await.unwrap();
+        let result = api.handle_response(response, "user_me", "u1").await;
+        let valid = api.auth.try_get_credentials_for(Some("u1")).await.unwrap().is_some();
+        let _ = std::fs::remove_dir_all(&dir);
+        (result, valid)
+    }
+
+    #[tokio::test]
+    async fn login_required_responses_expire_the_session() {
+        let base = stub().await;
+
+        for (path, expected_code) in [("/status/401", 401), ("/code/-100", -100), ("/code/-101", -101)] {
+            let (result, valid) = handle(&base, path).await;
+            match result {
+                Err(XhsError::SessionExpired { account, code, .. }) => {
+                    assert_eq!(account, "u1", "{}", path);
+                    assert_eq!(code, expected_code, "{}", path);
+                }
+                other => panic!("{}: expected SessionExpired, got {:?}", path, other),
+            }
+            assert!(!valid, "{}: session must be invalidated", path);
+        }
+
+        let (result, _) = handle(&base, "/code/-100").await;
+        assert!(matches!(result, Err(XhsError::SessionExpired { msg, .. }) if msg == "msg -100"));
+    }
+
+    #[tokio::test]
+    async fn other_errors_keep_the_session_valid() {
+        let base = stub().await;
+
+        let (result, valid) = handle(&base, "/status/406").await;
+        assert!(matches!(result, Err(XhsError::SignatureRejected(_))));
+        assert!(valid);
+
+        let (result, valid) = handle(&base, "/status/461").await;
+        assert!(matches!(result, Err(XhsError::RiskControl461(_))));
+        assert!(valid);
+
+        let (result, valid) = handle(&base, "/status/500").await;
+        assert!(matches!(result, Err(XhsError::Upstream { code: 500, .. })));
+        assert!(valid);
+
+        let (result, valid) = handle(&base, "/code/300012").await;
+        assert!(result.unwrap().contains("300012"));
+        assert!(valid);
+
+        let (result, valid) = handle(&base, "/code/0").await;
+        assert!(result.is_ok());
+        assert!(valid);
+    }
 }
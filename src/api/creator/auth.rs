@@ -3,15 +3,16 @@
 //! Handles the QR code login process specifically for the Creator Center (creator.xiaohongshu.com).
 //! Similar to the user login flow but operating in the 'ugc' context.
 
-use anyhow::{anyhow, Result};
+use crate::error::{decode, XhsError, XhsResult};
 use reqwest::header::{HeaderValue, CONTENT_TYPE};
 use serde::Deserialize;
 use std::collections::HashMap;
-use crate::config::get_agent_url;
+use crate::config::{get_agent_url, get_client_profile};
 use crate::api::login::{
     QrCodeCreateResponse,
     QrCodeCreateData,
     AgentGuestCookiesResponse,
+    response_cookies,
 };
 
 // ============================================================================
@@ -30,7 +31,7 @@ use crate::api::creator::utils::{sign_request, build_creator_headers, cookies_to
 /// Fetch guest cookies for Creator Center options
 ///
 /// Calls Agent with `target=creator` to initialize cookies on creator.xiaohongshu.com
-pub async fn fetch_creator_guest_cookies() -> Result<HashMap<String, String>> {
+pub async fn fetch_creator_guest_cookies() -> XhsResult<HashMap<String, String>> {
     let client = reqwest::Client::new();
     // Use the new target parameter we added to agent_server.py
     let url = format!("{}/guest-cookies?target=creator", get_agent_url());
@@ -42,31 +43,31 @@ pub async fn fetch_creator_guest_cookies() -> Result<HashMap<String, String>> {
         .timeout(std::time::Duration::from_secs(45)) // Little extra buffer
         .send()
         .await
-        .map_err(|e| anyhow!("Failed to connect to Agent: {}", e))?;
+        .map_err(|e| XhsError::AgentUnavailable(format!("Failed to connect to Agent: {}", e)))?;
     
     let result: AgentGuestCookiesResponse = response
         .json()
         .await
-        .map_err(|e| anyhow!("Failed to parse Agent response: {}", e))?;
+        .map_err(|e| XhsError::AgentUnavailable(format!("Failed to parse Agent response: {}", e)))?;
     
     if !result.success {
-        return Err(anyhow!("Agent error: {}", result.error.unwrap_or_default()));
+        return Err(XhsError::AgentUnavailable(format!("Agent error: {}", result.error.unwrap_or_default())));
     }
     
-    result.cookies.ok_or_else(|| anyhow!("No cookies returned"))
+    result.cookies.ok_or_else(|| XhsError::AgentUnavailable("No cookies returned".to_string()))
 }
 
 /// Create QR code for Creator Center Login
-pub async fn create_creator_qrcode(cookies: &HashMap<String, String>) -> Result<QrCodeCreateResponse> {
+pub async fn create_creator_qrcode(cookies: &HashMap<String, String>) -> XhsResult<QrCodeCreateResponse> {
     let uri = "/api/cas/customer/web/qr-code";
     let payload = serde_json::json!({"service": "https://creator.xiaohongshu.com"});
     
     // Get signature
     let (x_s, x_t, x_s_common) = 
-        sign_request(cookies, "POST", uri, Some(payload.clone())).await?;
+        sign_request(cookies, get_client_profile(), "POST", uri, Some(payload.clone())).await?;
     
     // Build request
-    let mut headers = build_creator_headers();
+    let mut headers = build_creator_headers(get_client_profile());
     headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json;charset=UTF-8"));
     headers.insert("x-s", HeaderValue::from_str(&x_s)?);
     headers.insert("x-t", HeaderValue::from_str(&x_t)?);
@@ -91,7 +92,10 @@ pub async fn create_creator_qrcode(cookies: &HashMap<String, String>) -> Result<
     tracing::debug!("Creator QR Response [{}]: {}", status, text);
     
     if status.as_u16() >= 400 {
-        return Err(anyhow!("API Error ({}): {}", status, text));
+        return Err(XhsError::Upstream {
+            code: status.as_u16() as i32,
+            msg: text,
+        });
     }
     
     #[derive(Debug, Deserialize)]
@@ -107,8 +111,7 @@ pub async fn create_creator_qrcode(cookies: &HashMap<String, String>) -> Result<
         id: String, // Matched from actual API response
     }
     
-    let raw: RawCreatorQrResponse = serde_json::from_str(&text)
-        .map_err(|e| anyhow!("Parse error: {} - Body: {}", e, text))?;
+    let raw: RawCreatorQrResponse = decode(&text)?;
         
     let data = raw.data.map(|d| QrCodeCreateData {
         url: d.url,
@@ -131,7 +134,7 @@ pub async fn create_creator_qrcode(cookies: &HashMap<String, String>) -> Result<
 pub async fn check_creator_qrcode_status(
     qr_id: &str,
     cookies: &HashMap<String, String>
-) -> Result<(serde_json::Value, Option<HashMap<String, String>>)> {
+) -> XhsResult<(serde_json::Value, Option<HashMap<String, String>>)> {
     let uri = "/api/cas/customer/web/qr-code";
     
     let service = "https://creator.xiaohongshu.com";
@@ -141,9 +144,9 @@ pub async fn check_creator_qrcode_status(
     
     // Get signature (GET request, no payload)
     let (x_s, x_t, x_s_common) = 
-        sign_request(cookies, "GET", &full_uri, None).await?;
+        sign_request(cookies, get_client_profile(), "GET", &full_uri, None).await?;
         
-    let mut headers = build_creator_headers();
+    let mut headers = build_creator_headers(get_client_profile());
     headers.insert("x-s", HeaderValue::from_str(&x_s)?);
     headers.insert("x-t", HeaderValue::from_str(&x_t)?);
     headers.insert("x-s-common", HeaderValue::from_str(&x_s_common)?);
@@ -163,30 +166,19 @@ pub async fn check_creator_qrcode_status(
         .await?;
         
     // Extract new cookies from Set-Cookie headers
-    let mut new_cookies: HashMap<String, String> = HashMap::new();
-    for (name, value) in response.headers().iter() {
-        if name.as_str() == "set-cookie" {
-            if let Ok(v) = value.to_str() {
-                // Parse "name=value; ..." format
-                if let Some(main) = v.split(';').next() {
-                    let mut parts = main.splitn(2, '=');
-                    if let (Some(k), Some(val)) = (parts.next(), parts.next()) {
-                        new_cookies.insert(k.trim().to_string(), val.trim().to_string());
-                    }
-                }
-            }
-        }
-    }
+    let new_cookies = response_cookies(&response);
     
     let status = response.status();
     let text = response.text().await?;
     
     if status.as_u16() >= 400 {
-        return Err(anyhow!("API Error ({}): {}", status, text));
+        return Err(XhsError::Upstream {
+            code: status.as_u16() as i32,
+            msg: text,
+        });
     }
     
-    let json: serde_json::Value = serde_json::from_str(&text)
-        .map_err(|e| anyhow!("Parse error: {} - Body: {}", e, text))?;
+    let json: serde_json::Value = decode(&text)?;
 
     // Check data.status
     // 1: Success
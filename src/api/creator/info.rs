@@ -1,10 +1,11 @@
 //! Creator Center User Info APIs
-use anyhow::{anyhow, Result};
-use std::collections::HashMap;
+use crate::error::{decode, XhsError, XhsResult};
 use reqwest::header::HeaderValue;
 
 use crate::api::creator::utils::{sign_request, build_creator_headers, cookies_to_string};
 use crate::api::creator::models::{CreatorUserInfo, CreatorHomeInfo};
+use crate::api::common::merge_response_cookies;
+use crate::auth::{AuthService, UserCredentials};
 
 // ============================================================================
 // Constants
@@ -21,13 +22,16 @@ const CREATOR_HOME_INFO_URL: &str = "https://creator.xiaohongshu.com/api/galaxy/
 // ============================================================================
 
 /// Get Creator User Info (role, permissions, etc.)
-pub async fn get_creator_user_info(cookies: &HashMap<String, String>) -> Result<CreatorUserInfo> {
+///
+/// `Set-Cookie` from the response is merged back into the account's credentials in `auth`
+pub async fn get_creator_user_info(auth: &AuthService, creds: &UserCredentials) -> XhsResult<CreatorUserInfo> {
+    let (cookies, profile) = (&creds.cookies, creds.profile());
     
     // Get signature (GET request, no payload)
     let (x_s, x_t, x_s_common) = 
-        sign_request(cookies, "GET", CREATOR_USER_INFO_URI, None).await?;
+        sign_request(cookies, profile, "GET", CREATOR_USER_INFO_URI, None).await?;
         
-    let mut headers = build_creator_headers();
+    let mut headers = build_creator_headers(profile);
     headers.insert("x-s", HeaderValue::from_str(&x_s)?);
     headers.insert("x-t", HeaderValue::from_str(&x_t)?);
     headers.insert("x-s-common", HeaderValue::from_str(&x_s_common)?);
@@ -43,12 +47,16 @@ pub async fn get_creator_user_info(cookies: &HashMap<String, String>) -> Result<
         .get(CREATOR_USER_INFO_URL)
         .send()
         .await?;
+    merge_response_cookies(auth, &creds.user_id, &response, CREATOR_USER_INFO_URI).await;
         
     let status = response.status();
     let text = response.text().await?;
     
     if status.as_u16() >= 400 {
-        return Err(anyhow!("API Error ({}): {}", status, text));
+        return Err(XhsError::Upstream {
+            code: status.as_u16() as i32,
+            msg: text,
+        });
     }
     
     // Parse generic wrapper: { code, data: ... }
@@ -59,27 +67,29 @@ pub async fn get_creator_user_info(cookies: &HashMap<String, String>) -> Result<
         data: Option<CreatorUserInfo>,
     }
     
-    let wrapper: ResponseWrapper = serde_json::from_str(&text)
-        .map_err(|e| anyhow!("Parse error: {} - Body: {}", e, text))?;
+    let wrapper: ResponseWrapper = decode(&text)?;
         
     if wrapper.code != 0 {
-        return Err(anyhow!("API Failed (code {}): {}", 
-            wrapper.code, 
-            wrapper.msg.unwrap_or_default()
-        ));
+        return Err(XhsError::Upstream {
+            code: wrapper.code,
+            msg: wrapper.msg.unwrap_or_default(),
+        });
     }
     
-    wrapper.data.ok_or_else(|| anyhow!("No data returned"))
+    wrapper.data.ok_or_else(|| XhsError::decode("No data returned", &text))
 }
 
 /// Get Creator Home Info (fans, likes, etc.)
-pub async fn get_creator_home_info(cookies: &HashMap<String, String>) -> Result<CreatorHomeInfo> {
+///
+/// `Set-Cookie` from the response is merged back into the account's credentials in `auth`
+pub async fn get_creator_home_info(auth: &AuthService, creds: &UserCredentials) -> XhsResult<CreatorHomeInfo> {
+    let (cookies, profile) = (&creds.cookies, creds.profile());
     
     // Get signature (GET request, no payload)
     let (x_s, x_t, x_s_common) = 
-        sign_request(cookies, "GET", CREATOR_HOME_INFO_URI, None).await?;
+        sign_request(cookies, profile, "GET", CREATOR_HOME_INFO_URI, None).await?;
         
-    let mut headers = build_creator_headers();
+    let mut headers = build_creator_headers(profile);
     headers.insert("x-s", HeaderValue::from_str(&x_s)?);
     headers.insert("x-t", HeaderValue::from_str(&x_t)?);
     headers.insert("x-s-common", HeaderValue::from_str(&x_s_common)?);
@@ -95,12 +105,16 @@ pub async fn get_creator_home_info(cookies: &HashMap<String, String>) -> Result<
         .get(CREATOR_HOME_INFO_URL)
         .send()
         .await?;
+    merge_response_cookies(auth, &creds.user_id, &response, CREATOR_HOME_INFO_URI).await;
         
     let status = response.status();
     let text = response.text().await?;
     
     if status.as_u16() >= 400 {
-        return Err(anyhow!("API Error ({}): {}", status, text));
+        return Err(XhsError::Upstream {
+            code: status.as_u16() as i32,
+            msg: text,
+        });
     }
     
     // Parse generic wrapper
@@ -111,15 +125,14 @@ pub async fn get_creator_home_info(cookies: &HashMap<String, String>) -> Result<
         data: Option<CreatorHomeInfo>,
     }
     
-    let wrapper: ResponseWrapper = serde_json::from_str(&text)
-        .map_err(|e| anyhow!("Parse error: {} - Body: {}", e, text))?;
+    let wrapper: ResponseWrapper = decode(&text)?;
         
     if wrapper.code != 0 {
-        return Err(anyhow!("API Failed (code {}): {}", 
-            wrapper.code, 
-            wrapper.msg.unwrap_or_default()
-        ));
+        return Err(XhsError::Upstream {
+            code: wrapper.code,
+            msg: wrapper.msg.unwrap_or_default(),
+        });
     }
     
-    wrapper.data.ok_or_else(|| anyhow!("No data returned"))
+    wrapper.data.ok_or_else(|| XhsError::decode("No data returned", &text))
 }
@@ -1,69 +1,42 @@
-use anyhow::{anyhow, Result};
-use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, ORIGIN, REFERER, USER_AGENT};
+use crate::error::XhsResult;
+use reqwest::header::{HeaderMap, HeaderValue};
 use std::collections::HashMap;
-use crate::config::get_agent_url;
-use crate::api::login::{AgentSignRequest, AgentSignResponse};
-
-// ============================================================================
-// Constants
-// ============================================================================
-
-pub const CREATOR_ORIGIN: &str = "https://creator.xiaohongshu.com";
-pub const CREATOR_REFERER: &str = "https://creator.xiaohongshu.com/";
-pub const XHS_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/143.0.0.0 Safari/537.36";
+use crate::client::{ClientProfile, Site};
+use crate::signature::{default_signer, SignRequest};
 
 // ============================================================================
 // Public Functions
 // ============================================================================
 
-/// Sign request using Agent
+/// Sign request using the default signer (native or Agent)
 pub async fn sign_request(
     cookies: &HashMap<String, String>,
+    profile: &ClientProfile,
     method: &str,
     uri: &str,
     payload: Option<serde_json::Value>,
-) -> Result<(String, String, String)> {
-    let client = reqwest::Client::new();
-    let url = format!("{}/sign", get_agent_url());
-    
-    let request = AgentSignRequest {
+) -> XhsResult<(String, String, String)> {
+    let request = SignRequest {
         method: method.to_string(),
         uri: uri.to_string(),
         cookies: cookies.clone(),
+        params: None,
         payload,
+        profile: profile.clone(),
     };
     
-    let response = client
-        .post(&url)
-        .json(&request)
-        .timeout(std::time::Duration::from_secs(5))
-        .send()
-        .await
-        .map_err(|e| anyhow!("Failed to connect to Agent: {}", e))?;
-    
-    let result: AgentSignResponse = response
-        .json()
-        .await
-        .map_err(|e| anyhow!("Failed to parse signature response: {}", e))?;
-    
-    if !result.success {
-        return Err(anyhow!("Sign error: {}", result.error.unwrap_or_default()));
-    }
+    let signature = default_signer().sign(&request).await?;
     
     Ok((
-        result.x_s.unwrap_or_default(),
-        result.x_t.unwrap_or_default(),
-        result.x_s_common.unwrap_or_default(),
+        signature.x_s,
+        signature.x_t,
+        signature.x_s_common,
     ))
 }
 
 /// Build common headers for Creator API
-pub fn build_creator_headers() -> HeaderMap {
-    let mut headers = HeaderMap::new();
-    headers.insert(ACCEPT, HeaderValue::from_static("application/json, text/plain, */*"));
-    headers.insert(ORIGIN, HeaderValue::from_static(CREATOR_ORIGIN));
-    headers.insert(REFERER, HeaderValue::from_static(CREATOR_REFERER));
-    headers.insert(USER_AGENT, HeaderValue::from_static(XHS_USER_AGENT));
+pub fn build_creator_headers(profile: &ClientProfile) -> HeaderMap {
+    let mut headers = profile.headers(Site::Creator);
     // CRITICAL: Creator Center / UGC context
     headers.insert("xsecappid", HeaderValue::from_static("ugc"));
     headers
@@ -6,6 +6,8 @@ use axum::{
 use std::sync::Arc;
 use crate::{
     api::XhsApiClient,
+    error::{decode, XhsResult},
+    handlers::session::AccountSelector,
     models::feed::{HomefeedRequest, HomefeedResponse},
     server::AppState,
 };
@@ -21,6 +23,7 @@ use crate::{
     summary = "主页发现-频道",
     description = "获取指定频道的内容流。支持用户自定义分页参数。\n\n分页规则请参阅 doc/homefeed_pagination.md\n\n可用频道:\n- recommend: 推荐\n- fashion: 穿搭\n- food: 美食\n- cosmetics: 彩妆\n- movie_and_tv: 影视\n- career: 职场\n- love: 情感\n- household_product: 家居\n- gaming: 游戏\n- travel: 旅行\n- fitness: 健身",
     params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
         ("category" = String, Path, description = "频道名称: recommend/fashion/food/cosmetics/movie_and_tv/career/love/household_product/gaming/travel/fitness")
     ),
     request_body = HomefeedRequest,
@@ -32,23 +35,31 @@ use crate::{
 )]
 pub async fn get_category_feed(
     State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
     Path(category): Path<String>,
     Json(mut req): Json<HomefeedRequest>,
 ) -> impl axum::response::IntoResponse {
     // Map category to correct format
     req.category = map_category(&category);
     
-    match get_feed_internal(&state.api, &category, req).await {
+    match get_feed_internal(&state.api.with_account(account), &category, req).await {
         Ok(data) => Json(data).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
+/// Fetch one page of a category feed (category is the path name, e.g. "fashion")
+///
+/// 连续获取可使用 [`crate::api::Paginator::homefeed`]
+pub async fn fetch_category_feed(
+    api: &XhsApiClient,
+    category: &str,
+    mut req: HomefeedRequest,
+) -> XhsResult<HomefeedResponse> {
+    req.category = map_category(category);
+    get_feed_internal(api, category, req).await
+}
+
 /// Map path category to XHS category format
 fn map_category(category: &str) -> String {
     if category == "recommend" {
@@ -62,7 +73,7 @@ async fn get_feed_internal(
     api: &XhsApiClient,
     category: &str,
     req: HomefeedRequest,
-) -> anyhow::Result<HomefeedResponse> {
+) -> XhsResult<HomefeedResponse> {
     // Construct signature key: home_feed_fashion, home_feed_food, etc.
     let signature_key = if category == "recommend" {
         "home_feed_recommend".to_string()
@@ -75,6 +86,6 @@ async fn get_feed_internal(
     
     // Use post_with_payload to sign and send with user-provided payload
     let text = api.post_with_payload(&signature_key, payload).await?;
-    let feed_resp: HomefeedResponse = serde_json::from_str(&text)?;
+    let feed_resp: HomefeedResponse = decode(&text)?;
     Ok(feed_resp)
 }
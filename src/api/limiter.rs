@@ -0,0 +1,491 @@
+//! 客户端限速与风控退避 (Rate Limiter & Backoff Scheduler)
+//!
+//! 按端点族（搜索、首页、笔记、通知、用户、创作者）限制请求速率，避免批量任务触发风控：
+//! - 令牌桶：每个端点族独立的速率与突发容量，令牌不足时等待而不是报错
+//! - 退避：收到 461（风控）后，该端点族按指数退避（带随机抖动）暂停请求。
+//!   406（签名被拒）先由重试策略重新签名，单次 406 不计入；重新签名次数用尽后仍为 406 时计入一次
+//! - 熔断：连续触发达到阈值后进入冷却期，冷却期内直接返回 [`XhsError::RateLimited`]；
+//!   冷却结束后只放行一个试探请求，成功即恢复，再次触发风控则重新熔断
+//!
+//! 配置见 [`crate::config::RateLimitConfig`]，当前状态见 `/api/status/limits`。
+
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+use crate::config::get_rate_limit_config;
+use crate::error::{XhsError, XhsResult};
+
+/// 半开状态下试探请求的最长占用时间（请求被取消、未记录结果时释放）
+const PROBE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// 端点族
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum EndpointFamily {
+    /// 搜索 (search/*)
+    Search,
+    /// 首页推荐 (homefeed)
+    Feed,
+    /// 笔记详情与评论 (feed, comment/*)
+    Note,
+    /// 通知 (you/*, unread_count)
+    Notification,
+    /// 用户主页、收藏、点赞、专辑
+    User,
+    /// 创作者中心 (galaxy/*)
+    Creator,
+    /// 其他
+    Other,
+}
+
+impl EndpointFamily {
+    /// 全部端点族
+    pub const ALL: [Self; 7] = [
+        Self::Search,
+        Self::Feed,
+        Self::Note,
+        Self::Notification,
+        Self::User,
+        Self::Creator,
+        Self::Other,
+    ];
+
+    /// 配置中使用的名称
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Search => "search",
+            Self::Feed => "feed",
+            Self::Note => "note",
+            Self::Notification => "notification",
+            Self::User => "user",
+            Self::Creator => "creator",
+            Self::Other => "other",
+        }
+    }
+
+    /// 按名称解析（大小写不敏感）
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = name.trim().to_ascii_lowercase();
+        Self::ALL.into_iter().find(|f| f.name() == name)
+    }
+
+    /// 按 API 路径归类
+    pub fn from_uri(uri: &str) -> Self {
+        let path = uri.split('?').next().unwrap_or(uri);
+        let path = path.strip_prefix("/api/sns/web").unwrap_or(path);
+        if path.starts_with("/v1/search/") {
+            Self::Search
+        } else if path.starts_with("/v1/homefeed") {
+            Self::Feed
+        } else if path == "/v1/feed" || path.starts_with("/v2/comment/") {
+            Self::Note
+        } else if path.starts_with("/v1/you/") || path == "/unread_count" {
+            Self::Notification
+        } else if path.starts_with("/api/galaxy/") {
+            Self::Creator
+        } else if path.contains("user")
+            || path.starts_with("/v1/board/")
+            || path.starts_with("/v2/note/collect/")
+            || path.starts_with("/v1/note/like/")
+        {
+            Self::User
+        } else {
+            Self::Other
+        }
+    }
+}
+
+/// 令牌桶速率
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rate {
+    /// 每秒补充的令牌数，0 表示不限速
+    pub per_second: f64,
+    /// 突发容量
+    pub burst: u32,
+}
+
+/// 退避与熔断策略
+#[derive(Debug, Clone, Copy)]
+pub struct BackoffPolicy {
+    /// 首次退避时长
+    pub base: Duration,
+    /// 退避上限
+    pub max: Duration,
+    /// 连续触发多少次后熔断
+    pub threshold: u32,
+    /// 熔断冷却时长
+    pub cooldown: Duration,
+}
+
+/// 客户端限速器
+pub struct RateLimiter {
+    families: HashMap<EndpointFamily, Mutex<FamilyState>>,
+    policy: BackoffPolicy,
+}
+
+/// 单个端点族的状态
+struct FamilyState {
+    rate: Rate,
+    tokens: f64,
+    updated: Instant,
+    /// 连续触发风控的次数（成功后清零）
+    consecutive_failures: u32,
+    backoff_until: Option<Instant>,
+    open_until: Option<Instant>,
+    /// 半开状态下正在进行的试探请求的开始时间
+    probe_started: Option<Instant>,
+    throttled_total: u64,
+    last_throttled_at: Option<DateTime<Utc>>,
+}
+
+impl FamilyState {
+    fn new(rate: Rate) -> Self {
+        Self {
+            rate,
+            tokens: rate.burst as f64,
+            updated: Instant::now(),
+            consecutive_failures: 0,
+            backoff_until: None,
+            open_until: None,
+            probe_started: None,
+            throttled_total: 0,
+            last_throttled_at: None,
+        }
+    }
+
+    /// 预留一个令牌（不早于 `not_before`），返回从 `now` 起需要等待的时长
+    fn reserve(&mut self, now: Instant, not_before: Instant) -> Duration {
+        if self.rate.per_second <= 0.0 {
+            return not_before.saturating_duration_since(now);
+        }
+        let at = not_before.max(self.updated);
+        let refill = at.saturating_duration_since(self.updated).as_secs_f64() * self.rate.per_second;
+        self.tokens = (self.tokens + refill).min(self.rate.burst.max(1) as f64);
+        self.updated = at;
+        self.tokens -= 1.0;
+
+        let deficit = if self.tokens < 0.0 { -self.tokens / self.rate.per_second } else { 0.0 };
+        at.saturating_duration_since(now) + Duration::from_secs_f64(deficit)
+    }
+
+    /// 当前可用令牌数（不修改状态）
+    fn available(&self, now: Instant) -> f64 {
+        if self.rate.per_second <= 0.0 {
+            return self.rate.burst as f64;
+        }
+        let refill = now.saturating_duration_since(self.updated).as_secs_f64() * self.rate.per_second;
+        (self.tokens + refill).min(self.rate.burst.max(1) as f64)
+    }
+}
+
+impl RateLimiter {
+    /// 创建限速器，`rates` 中未列出的端点族不限速
+    pub fn new(rates: HashMap<EndpointFamily, Rate>, policy: BackoffPolicy) -> Self {
+        let families = EndpointFamily::ALL
+            .into_iter()
+            .map(|family| {
+                let rate = rates.get(&family).copied().unwrap_or(Rate { per_second: 0.0, burst: 1 });
+                (family, Mutex::new(FamilyState::new(rate)))
+            })
+            .collect();
+        Self { families, policy }
+    }
+
+    /// 按 `XHS_RATE_LIMIT` / `XHS_BACKOFF_*` / `XHS_BREAKER_*` 配置创建
+    pub fn from_config() -> Self {
+        let config = get_rate_limit_config();
+        let policy = config.policy();
+        info!("[RateLimiter] Rates: {:?}, backoff: {:?}", config.rates, policy);
+        Self::new(config.rates(), policy)
+    }
+
+    /// 等待请求许可：熔断中返回错误，退避中或令牌不足时等待
+    ///
+    /// 冷却结束（半开）后只放行一个试探请求，其余请求在试探完成前仍返回错误
+    pub async fn acquire(&self, family: EndpointFamily) -> XhsResult<()> {
+        let wait = {
+            let mut state = self.state(family);
+            let now = Instant::now();
+            if let Some(until) = state.open_until {
+                if until > now {
+                    return Err(XhsError::RateLimited {
+                        family: family.name().to_string(),
+                        retry_after: until.saturating_duration_since(now).as_secs().max(1),
+                    });
+                }
+                if state.probe_started.is_some_and(|started| now.saturating_duration_since(started) < PROBE_TIMEOUT) {
+                    return Err(XhsError::RateLimited { family: family.name().to_string(), retry_after: 1 });
+                }
+                info!("[RateLimiter] {} half-open, sending a probe request", family.name());
+                state.probe_started = Some(now);
+            }
+            let not_before = state.backoff_until.filter(|until| *until > now).unwrap_or(now);
+            state.reserve(now, not_before)
+        };
+
+        if !wait.is_zero() {
+            info!("[RateLimiter] {} waiting {:.1}s", family.name(), wait.as_secs_f64());
+            tokio::time::sleep(wait).await;
+        }
+        Ok(())
+    }
+
+    /// 记录请求结果：461 触发退避与熔断，成功则恢复
+    ///
+    /// 其他错误（包括单次 406 签名被拒）不影响退避与熔断，只释放半开状态的试探名额
+    pub fn observe<T>(&self, family: EndpointFamily, result: &XhsResult<T>) {
+        match result {
+            Ok(_) => self.on_success(family),
+            Err(e) if is_throttled(e) => self.on_throttled(family),
+            Err(_) => self.state(family).probe_started = None,
+        }
+    }
+
+    /// 记录重试策略的最终结果：重新签名次数用尽后仍被拒绝（406）时计入退避与熔断
+    ///
+    /// 每次尝试的 461 已由 [`RateLimiter::observe`] 记录，这里只处理 406
+    pub fn observe_exhausted<T>(&self, family: EndpointFamily, result: &XhsResult<T>) {
+        if matches!(result, Err(XhsError::SignatureRejected(_))) {
+            warn!("[RateLimiter] {} still rejected (406) after re-signing", family.name());
+            self.on_throttled(family);
+        }
+    }
+
+    /// 在限速下执行请求（许可 → 请求 → 记录结果）
+    pub async fn run<T>(&self, family: EndpointFamily, request: impl Future<Output = XhsResult<T>>) -> XhsResult<T> {
+        self.acquire(family).await?;
+        let result = request.await;
+        self.observe(family, &result);
+        result
+    }
+
+    /// 各端点族的当前状态
+    pub fn snapshot(&self) -> Vec<LimitStatus> {
+        let now = Instant::now();
+        EndpointFamily::ALL
+            .into_iter()
+            .map(|family| {
+                let state = self.state(family);
+                let remaining = |until: Option<Instant>| {
+                    until.map(|u| u.saturating_duration_since(now).as_secs_f64()).filter(|secs| *secs > 0.0)
+                };
+                let circuit = match state.open_until {
+                    Some(until) if until > now => CircuitState::Open,
+                    Some(_) => CircuitState::HalfOpen,
+                    None => CircuitState::Closed,
+                };
+                LimitStatus {
+                    family,
+                    rate_per_second: state.rate.per_second,
+                    burst: state.rate.burst,
+                    available_tokens: state.available(now),
+                    consecutive_failures: state.consecutive_failures,
+                    backoff_remaining_secs: remaining(state.backoff_until),
+                    circuit,
+                    circuit_remaining_secs: remaining(state.open_until),
+                    throttled_total: state.throttled_total,
+                    last_throttled_at: state.last_throttled_at,
+                }
+            })
+            .collect()
+    }
+
+    fn on_success(&self, family: EndpointFamily) {
+        let mut state = self.state(family);
+        if state.consecutive_failures > 0 || state.open_until.is_some() {
+            info!("[RateLimiter] {} recovered", family.name());
+        }
+        state.consecutive_failures = 0;
+        state.backoff_until = None;
+        state.open_until = None;
+        state.probe_started = None;
+    }
+
+    fn on_throttled(&self, family: EndpointFamily) {
+        let policy = self.policy;
+        let mut state = self.state(family);
+        let now = Instant::now();
+        state.consecutive_failures += 1;
+        state.throttled_total += 1;
+        state.probe_started = None;
+        state.last_throttled_at = Some(Utc::now());
+
+        // 指数退避，随机取 [delay/2, delay] 避免多个任务同时恢复
+        let exponent = state.consecutive_failures.saturating_sub(1).min(16);
+        let delay = policy.base.saturating_mul(1 << exponent).min(policy.max);
+        let delay = delay.mul_f64(rand::thread_rng().gen_range(0.5..=1.0));
+        state.backoff_until = Some(now + delay);
+
+        if state.consecutive_failures >= policy.threshold {
+            state.open_until = Some(now + policy.cooldown);
+            warn!(
+                "[RateLimiter] {} throttled {} times in a row, circuit open for {}s",
+                family.name(),
+                state.consecutive_failures,
+                policy.cooldown.as_secs()
+            );
+        } else {
+            warn!(
+                "[RateLimiter] {} throttled ({} in a row), backing off {:.1}s",
+                family.name(),
+                state.consecutive_failures,
+                delay.as_secs_f64()
+            );
+        }
+    }
+
+    fn state(&self, family: EndpointFamily) -> std::sync::MutexGuard<'_, FamilyState> {
+        self.families[&family].lock().unwrap()
+    }
+}
+
+/// 单次请求是否为风控限流（461）
+///
+/// 406 先重新签名，重试用尽后由 [`RateLimiter::observe_exhausted`] 计入
+fn is_throttled(err: &XhsError) -> bool {
+    match err {
+        XhsError::RiskControl461(_) => true,
+        XhsError::Upstream { code, .. } => *code == 461,
+        _ => false,
+    }
+}
+
+/// 熔断状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CircuitState {
+    /// 正常
+    Closed,
+    /// 熔断中，请求直接失败
+    Open,
+    /// 冷却结束，等待试探请求
+    HalfOpen,
+}
+
+/// 端点族限速状态
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct LimitStatus {
+    /// 端点族
+    pub family: EndpointFamily,
+    /// 每秒请求数 (0 表示不限速)
+    pub rate_per_second: f64,
+    /// 突发容量
+    pub burst: u32,
+    /// 当前可用令牌
+    pub available_tokens: f64,
+    /// 连续触发风控次数
+    pub consecutive_failures: u32,
+    /// 退避剩余时间 (秒)
+    pub backoff_remaining_secs: Option<f64>,
+    /// 熔断状态
+    pub circuit: CircuitState,
+    /// 熔断剩余时间 (秒)
+    pub circuit_remaining_secs: Option<f64>,
+    /// 累计触发风控次数
+    pub throttled_total: u64,
+    /// 最近一次触发风控的时间
+    #[schema(value_type = Option<String>, format = DateTime)]
+    pub last_throttled_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(cooldown: Duration) -> RateLimiter {
+        let policy = BackoffPolicy {
+            base: Duration::from_millis(1),
+            max: Duration::from_millis(2),
+            threshold: 3,
+            cooldown,
+        };
+        RateLimiter::new(HashMap::new(), policy)
+    }
+
+    fn status(limiter: &RateLimiter, family: EndpointFamily) -> LimitStatus {
+        limiter.snapshot().into_iter().find(|s| s.family == family).unwrap()
+    }
+
+    #[tokio::test]
+    async fn signature_rejected_is_not_throttling() {
+        let limiter = limiter(Duration::from_secs(900));
+        for _ in 0..5 {
+            limiter.observe::<()>(EndpointFamily::Search, &Err(XhsError::SignatureRejected(String::new())));
+            limiter.observe::<()>(EndpointFamily::Search, &Err(XhsError::Upstream { code: 406, msg: String::new() }));
+        }
+
+        let status = status(&limiter, EndpointFamily::Search);
+        assert_eq!(status.consecutive_failures, 0);
+        assert_eq!(status.throttled_total, 0);
+        assert_eq!(status.circuit, CircuitState::Closed);
+        assert!(limiter.acquire(EndpointFamily::Search).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn exhausted_406_counts_toward_the_breaker() {
+        let limiter = limiter(Duration::from_secs(900));
+        limiter.observe_exhausted::<()>(EndpointFamily::Search, &Ok(()));
+        limiter.observe_exhausted::<()>(EndpointFamily::Search, &Err(XhsError::Upstream { code: 500, msg: String::new() }));
+        assert_eq!(status(&limiter, EndpointFamily::Search).throttled_total, 0);
+
+        for _ in 0..3 {
+            limiter.observe_exhausted::<()>(EndpointFamily::Search, &Err(XhsError::SignatureRejected(String::new())));
+        }
+        let status = status(&limiter, EndpointFamily::Search);
+        assert_eq!(status.throttled_total, 3);
+        assert_eq!(status.circuit, CircuitState::Open);
+        assert!(limiter.acquire(EndpointFamily::Note).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn repeated_461_opens_only_that_family() {
+        let limiter = limiter(Duration::from_secs(900));
+        for _ in 0..3 {
+            limiter.observe::<()>(EndpointFamily::Search, &Err(XhsError::RiskControl461(String::new())));
+        }
+
+        assert_eq!(status(&limiter, EndpointFamily::Search).circuit, CircuitState::Open);
+        assert!(matches!(limiter.acquire(EndpointFamily::Search).await, Err(XhsError::RateLimited { .. })));
+        assert!(limiter.acquire(EndpointFamily::Note).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn half_open_lets_exactly_one_probe_through() {
+        let limiter = limiter(Duration::from_millis(5));
+        for _ in 0..3 {
+            limiter.observe::<()>(EndpointFamily::Feed, &Err(XhsError::RiskControl461(String::new())));
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(status(&limiter, EndpointFamily::Feed).circuit, CircuitState::HalfOpen);
+
+        assert!(limiter.acquire(EndpointFamily::Feed).await.is_ok());
+        assert!(limiter.acquire(EndpointFamily::Feed).await.is_err());
+
+        // A successful probe closes the circuit
+        limiter.observe(EndpointFamily::Feed, &Ok(()));
+        assert_eq!(status(&limiter, EndpointFamily::Feed).circuit, CircuitState::Closed);
+        assert!(limiter.acquire(EndpointFamily::Feed).await.is_ok());
+        assert!(limiter.acquire(EndpointFamily::Feed).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn throttled_probe_reopens_the_circuit() {
+        let limiter = limiter(Duration::from_millis(5));
+        for _ in 0..3 {
+            limiter.observe::<()>(EndpointFamily::Feed, &Err(XhsError::RiskControl461(String::new())));
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+
+        assert!(limiter.acquire(EndpointFamily::Feed).await.is_ok());
+        limiter.observe::<()>(EndpointFamily::Feed, &Err(XhsError::RiskControl461(String::new())));
+        assert_eq!(status(&limiter, EndpointFamily::Feed).circuit, CircuitState::Open);
+    }
+}
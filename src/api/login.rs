@@ -4,26 +4,25 @@
 //! 1. Fetch guest cookies from Python Agent (Playwright)
 //! 2. Create QR code using official API
 //! 3. Poll QR code status until login success
-//! 4. Store user credentials in MongoDB
+//! 4. Store user credentials in the session registry (configured `XHS_STORAGE` backend)
 //!
 //! Design Principles:
 //! - Single Responsibility: Each function does one thing
 //! - KISS: Simple, straightforward implementation
 
-use anyhow::{anyhow, Result};
-use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, CONTENT_TYPE, ORIGIN, REFERER, USER_AGENT};
+use crate::error::{decode, XhsError, XhsResult};
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use crate::config::get_agent_url;
+use crate::auth::credentials::merge_set_cookies;
+use crate::client::{set_cookie_headers, Site};
+use crate::config::{get_agent_url, get_client_profile};
+use crate::signature::{default_signer, SignRequest};
 
 // ============================================================================
 // Constants
 // ============================================================================
 
-const XHS_ORIGIN: &str = "https://www.xiaohongshu.com";
-const XHS_REFERER: &str = "https://www.xiaohongshu.com/";
-const XHS_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/143.0.0.0 Safari/537.36";
-
 const QRCODE_CREATE_URL: &str = "https://edith.xiaohongshu.com/api/sns/web/v1/login/qrcode/create";
 const QRCODE_STATUS_URL: &str = "https://edith.xiaohongshu.com/api/sns/web/v1/login/qrcode/status";
 
@@ -31,29 +30,6 @@ const QRCODE_STATUS_URL: &str = "https://edith.xiaohongshu.com/api/sns/web/v1/lo
 // Agent Request/Response Models
 // ============================================================================
 
-/// Request to Python Agent for signature generation
-#[derive(Debug, Serialize)]
-pub struct AgentSignRequest {
-    pub method: String,
-    pub uri: String,
-    pub cookies: HashMap<String, String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub payload: Option<serde_json::Value>,
-}
-
-/// Response from Python Agent signature endpoint
-/// Response from Python Agent signature endpoint
-#[derive(Debug, Deserialize)]
-pub struct AgentSignResponse {
-    pub success: bool,
-    pub x_s: Option<String>,
-    pub x_t: Option<String>,
-    pub x_s_common: Option<String>,
-    pub x_b3_traceid: Option<String>,
-    pub x_xray_traceid: Option<String>,
-    pub error: Option<String>,
-}
-
 /// Response from Python Agent guest-cookies endpoint
 #[derive(Debug, Deserialize)]
 pub struct AgentGuestCookiesResponse {
@@ -142,7 +118,7 @@ pub struct PollStatusResponse {
 /// Fetch guest cookies from Python Agent (uses Playwright internally)
 ///
 /// Returns a HashMap of cookies needed for QR code login
-pub async fn fetch_guest_cookies() -> Result<HashMap<String, String>> {
+pub async fn fetch_guest_cookies() -> XhsResult<HashMap<String, String>> {
     let client = reqwest::Client::new();
     let url = format!("{}/guest-cookies", get_agent_url());
     
@@ -153,70 +129,49 @@ pub async fn fetch_guest_cookies() -> Result<HashMap<String, String>> {
         .timeout(std::time::Duration::from_secs(30))  // Playwright needs time
         .send()
         .await
-        .map_err(|e| anyhow!("Failed to connect to Agent: {}", e))?;
+        .map_err(|e| XhsError::AgentUnavailable(format!("Failed to connect to Agent: {}", e)))?;
     
     let result: AgentGuestCookiesResponse = response
         .json()
         .await
-        .map_err(|e| anyhow!("Failed to parse Agent response: {}", e))?;
+        .map_err(|e| XhsError::AgentUnavailable(format!("Failed to parse Agent response: {}", e)))?;
     
     if !result.success {
-        return Err(anyhow!("Agent error: {}", result.error.unwrap_or_default()));
+        return Err(XhsError::AgentUnavailable(format!("Agent error: {}", result.error.unwrap_or_default())));
     }
     
-    result.cookies.ok_or_else(|| anyhow!("No cookies returned"))
+    result.cookies.ok_or_else(|| XhsError::AgentUnavailable("No cookies returned".to_string()))
 }
 
-/// Get signature from Python Agent
+/// Sign request using the default signer (native or Agent)
 async fn sign_request(
     cookies: &HashMap<String, String>,
     method: &str,
     uri: &str,
     payload: Option<serde_json::Value>,
-) -> Result<(String, String, String, String)> {
-    let client = reqwest::Client::new();
-    let url = format!("{}/sign", get_agent_url());
-    
-    let request = AgentSignRequest {
+) -> XhsResult<(String, String, String, String)> {
+    let request = SignRequest {
         method: method.to_string(),
         uri: uri.to_string(),
         cookies: cookies.clone(),
+        params: None,
         payload,
+        profile: get_client_profile().clone(),
     };
     
-    let response = client
-        .post(&url)
-        .json(&request)
-        .timeout(std::time::Duration::from_secs(5))
-        .send()
-        .await
-        .map_err(|e| anyhow!("Failed to connect to Agent: {}", e))?;
-    
-    let result: AgentSignResponse = response
-        .json()
-        .await
-        .map_err(|e| anyhow!("Failed to parse signature response: {}", e))?;
-    
-    if !result.success {
-        return Err(anyhow!("Sign error: {}", result.error.unwrap_or_default()));
-    }
+    let signature = default_signer().sign(&request).await?;
     
     Ok((
-        result.x_s.unwrap_or_default(),
-        result.x_t.unwrap_or_default(),
-        result.x_s_common.unwrap_or_default(),
-        result.x_b3_traceid.unwrap_or_default(),
+        signature.x_s,
+        signature.x_t,
+        signature.x_s_common,
+        signature.x_b3_traceid,
     ))
 }
 
-/// Build common headers for XHS API requests
+/// Build common headers for XHS API requests (default profile, no account yet)
 fn build_common_headers() -> HeaderMap {
-    let mut headers = HeaderMap::new();
-    headers.insert(ACCEPT, HeaderValue::from_static("application/json, text/plain, */*"));
-    headers.insert(ORIGIN, HeaderValue::from_static(XHS_ORIGIN));
-    headers.insert(REFERER, HeaderValue::from_static(XHS_REFERER));
-    headers.insert(USER_AGENT, HeaderValue::from_static(XHS_USER_AGENT));
-    headers
+    get_client_profile().headers(Site::Web)
 }
 
 /// Convert cookies HashMap to cookie string
@@ -228,8 +183,17 @@ fn cookies_to_string(cookies: &HashMap<String, String>) -> String {
         .join("; ")
 }
 
+/// Cookies set by a response (deleted / expired cookies are skipped)
+pub(crate) fn response_cookies(response: &reqwest::Response) -> HashMap<String, String> {
+    let mut cookies = HashMap::new();
+    merge_set_cookies(&mut cookies, set_cookie_headers(response.headers()).iter().map(String::as_str));
+    cookies
+}
+
 /// Create QR code using official API
-pub async fn create_qrcode(cookies: &HashMap<String, String>) -> Result<QrCodeCreateResponse> {
+///
+/// `Set-Cookie` from the response is merged into the guest `cookies`
+pub async fn create_qrcode(cookies: &mut HashMap<String, String>) -> XhsResult<QrCodeCreateResponse> {
     let uri = "/api/sns/web/v1/login/qrcode/create";
     let payload = serde_json::json!({"qr_type": 1});
     
@@ -257,6 +221,7 @@ pub async fn create_qrcode(cookies: &HashMap<String, String>) -> Result<QrCodeCr
         .json(&payload)
         .send()
         .await?;
+    merge_set_cookies(cookies, set_cookie_headers(response.headers()).iter().map(String::as_str));
     
     let status = response.status();
     let text = response.text().await?;
@@ -264,10 +229,10 @@ pub async fn create_qrcode(cookies: &HashMap<String, String>) -> Result<QrCodeCr
     tracing::debug!("QR Create Response [{}]: {}", status, text);
     
     if status.as_u16() == 406 {
-        return Err(anyhow!("Signature rejected (406): cookies may be invalid"));
+        return Err(XhsError::SignatureRejected("cookies may be invalid".to_string()));
     }
     
-    serde_json::from_str(&text).map_err(|e| anyhow!("Parse error: {} - Body: {}", e, text))
+    decode(&text)
 }
 
 /// Check QR code status using official API
@@ -277,7 +242,7 @@ pub async fn check_qrcode_status(
     cookies: &HashMap<String, String>,
     qr_id: &str,
     code: &str,
-) -> Result<(QrCodeStatusResponse, Option<HashMap<String, String>>)> {
+) -> XhsResult<(QrCodeStatusResponse, Option<HashMap<String, String>>)> {
     let uri = format!("/api/sns/web/v1/login/qrcode/status?qr_id={}&code={}", qr_id, code);
     let url = format!("{}?qr_id={}&code={}", QRCODE_STATUS_URL, qr_id, code);
     
@@ -300,23 +265,10 @@ pub async fn check_qrcode_status(
     let response = client.get(&url).send().await?;
     
     // Extract new cookies from Set-Cookie headers
-    let mut new_cookies: HashMap<String, String> = HashMap::new();
-    for (name, value) in response.headers().iter() {
-        if name.as_str() == "set-cookie" {
-            if let Ok(v) = value.to_str() {
-                // Parse "name=value; ..." format
-                if let Some(main) = v.split(';').next() {
-                    let mut parts = main.splitn(2, '=');
-                    if let (Some(k), Some(val)) = (parts.next(), parts.next()) {
-                        new_cookies.insert(k.trim().to_string(), val.trim().to_string());
-                    }
-                }
-            }
-        }
-    }
-    
+    let new_cookies = response_cookies(&response);
     
-    let status_response: QrCodeStatusResponse = response.json().await?;
+    let text = response.text().await?;
+    let status_response: QrCodeStatusResponse = decode(&text)?;
     
     // 如果登录成功，执行阻塞式 Cookie 同步
     let cookies_to_return = if let Some(data) = &status_response.data {
@@ -368,7 +320,7 @@ pub async fn check_qrcode_status(
 /// 
 /// - `web_session`: The base session cookie
 /// - `target`: Optional target page ("explore" [default], "creator")
-pub async fn sync_login_cookies(cookies: &HashMap<String, String>, target: Option<&str>) -> Result<HashMap<String, String>> {
+pub async fn sync_login_cookies(cookies: &HashMap<String, String>, target: Option<&str>) -> XhsResult<HashMap<String, String>> {
     let client = reqwest::Client::new();
     let url = format!("{}/sync-login-cookies", get_agent_url());
     
@@ -387,24 +339,23 @@ pub async fn sync_login_cookies(cookies: &HashMap<String, String>, target: Optio
         .timeout(std::time::Duration::from_secs(90))
         .send()
         .await
-        .map_err(|e| anyhow!("Failed to connect to Agent sync: {}", e))?;
+        .map_err(|e| XhsError::AgentUnavailable(format!("Failed to connect to Agent sync: {}", e)))?;
         
         
     let status = response.status();
     let text = response.text().await
-        .map_err(|e| anyhow!("Failed to get Agent sync response body: {}", e))?;
+        .map_err(|e| XhsError::AgentUnavailable(format!("Failed to get Agent sync response body: {}", e)))?;
         
     if !status.is_success() {
         tracing::error!("Agent sync returned error status: {} - Body: {}", status, text);
-        return Err(anyhow!("Agent sync failed with status {}: {}", status, text));
+        return Err(XhsError::AgentUnavailable(format!("Agent sync failed with status {}: {}", status, text)));
     }
 
-    let result: AgentGuestCookiesResponse = serde_json::from_str(&text)
-        .map_err(|e| anyhow!("Failed to parse Agent sync response: {} - Body: {}", e, text))?;
+    let result: AgentGuestCookiesResponse = decode(&text)?;
         
     if !result.success {
-        return Err(anyhow!("Agent sync error: {}", result.error.unwrap_or_default()));
+        return Err(XhsError::AgentUnavailable(format!("Agent sync error: {}", result.error.unwrap_or_default())));
     }
     
-    result.cookies.ok_or_else(|| anyhow!("No cookies returned from sync"))
+    result.cookies.ok_or_else(|| XhsError::AgentUnavailable("No cookies returned from sync".to_string()))
 }
@@ -2,10 +2,14 @@
 //!
 //! Downloads media files (video/image) to local storage
 
-use anyhow::{Result, anyhow};
+use anyhow::anyhow;
+use crate::client::Site;
+use reqwest::header::{HeaderValue, ACCEPT};
+use crate::config::{get_client_profile, get_download_config};
+use crate::error::XhsResult;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
 
@@ -16,7 +20,7 @@ pub struct DownloadRequest {
     /// 支持 xhscdn.com 域名的视频和图片
     pub url: String,
     /// 保存路径 (必填)
-    /// 例如: "./downloads/video.mp4"
+    /// 例如: "./downloads/video.mp4"；配置了下载根目录时相对于根目录解析，且不能超出根目录
     pub save_path: String,
 }
 
@@ -50,7 +54,7 @@ const ALLOWED_DOMAINS: &[&str] = &[
 /// 下载媒体文件到本地
 ///
 /// 支持视频和图片的下载
-pub async fn download_media(req: DownloadRequest) -> Result<DownloadResponse> {
+pub async fn download_media(req: DownloadRequest) -> XhsResult<DownloadResponse> {
     // 验证 URL 域名白名单
     if !is_url_allowed(&req.url) {
         return Ok(DownloadResponse {
@@ -60,8 +64,12 @@ pub async fn download_media(req: DownloadRequest) -> Result<DownloadResponse> {
         });
     }
     
+    let save_path = match resolve_save_path(&req.save_path) {
+        Ok(path) => path,
+        Err(msg) => return Ok(DownloadResponse { success: false, msg: Some(msg), data: None }),
+    };
+
     // 确保保存目录存在
-    let save_path = Path::new(&req.save_path);
     if let Some(parent) = save_path.parent() {
         if !parent.exists() {
             fs::create_dir_all(parent).await
@@ -75,16 +83,13 @@ pub async fn download_media(req: DownloadRequest) -> Result<DownloadResponse> {
         .build()?;
     
     // 发送下载请求
+    let mut headers = get_client_profile().headers(Site::Web);
+    headers.insert(ACCEPT, HeaderValue::from_static("*/*"));
     let response = client
         .get(&req.url)
-        .header("Accept", "*/*")
-        .header("Accept-Language", "zh-CN,zh;q=0.9")
-        .header("Origin", "https://www.xiaohongshu.com")
-        .header("Referer", "https://www.xiaohongshu.com/")
-        .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/143.0.0.0 Safari/537.36")
+        .headers(headers)
         .send()
-        .await
-        .map_err(|e| anyhow!("Failed to download: {}", e))?;
+        .await?;
     
     // 检查响应状态
     if !response.status().is_success() {
@@ -104,13 +109,12 @@ pub async fn download_media(req: DownloadRequest) -> Result<DownloadResponse> {
         .to_string();
     
     // 获取文件内容
-    let bytes = response.bytes().await
-        .map_err(|e| anyhow!("Failed to read response body: {}", e))?;
+    let bytes = response.bytes().await?;
     
     let file_size = bytes.len() as u64;
     
     // 写入文件
-    let mut file = fs::File::create(&req.save_path).await
+    let mut file = fs::File::create(&save_path).await
         .map_err(|e| anyhow!("Failed to create file: {}", e))?;
     
     file.write_all(&bytes).await
@@ -121,20 +125,45 @@ pub async fn download_media(req: DownloadRequest) -> Result<DownloadResponse> {
     
     tracing::info!(
         "[MediaDownload] Downloaded {} -> {} ({} bytes)", 
-        req.url, req.save_path, file_size
+        req.url, save_path.display(), file_size
     );
     
     Ok(DownloadResponse {
         success: true,
         msg: None,
         data: Some(DownloadData {
-            saved_path: req.save_path,
+            saved_path: save_path.display().to_string(),
             file_size,
             content_type,
         }),
     })
 }
 
+/// 解析保存路径
+///
+/// 未配置下载根目录 (`download.root` / XHS_DOWNLOAD_ROOT) 时原样使用；
+/// 配置后相对路径拼接到根目录下，拒绝 `..` 以及根目录之外的绝对路径
+fn resolve_save_path(save_path: &str) -> Result<PathBuf, String> {
+    let path = Path::new(save_path);
+    let Some(root) = &get_download_config().root else {
+        return Ok(path.to_path_buf());
+    };
+
+    let relative = if path.is_absolute() {
+        path.strip_prefix(root)
+            .map_err(|_| format!("save_path must be inside the download root {}", root.display()))?
+    } else {
+        path
+    };
+    if relative.components().any(|c| !matches!(c, Component::Normal(_) | Component::CurDir)) {
+        return Err(format!("save_path must not leave the download root {}", root.display()));
+    }
+    if relative.file_name().is_none() {
+        return Err("save_path must name a file".to_string());
+    }
+    Ok(root.join(relative))
+}
+
 /// 检查 URL 是否在白名单中
 fn is_url_allowed(url: &str) -> bool {
     for domain in ALLOWED_DOMAINS {
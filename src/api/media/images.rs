@@ -2,8 +2,10 @@
 //!
 //! Extracts image download URLs from note details
 
+use crate::api::note::detail::{fetch_note_detail, NoteDetailRequest};
 use crate::api::XhsApiClient;
-use anyhow::{Result, anyhow};
+use crate::error::XhsResult;
+use crate::models::note::NoteDetail;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -63,127 +65,70 @@ pub struct ImageItem {
 ///
 /// 从笔记详情中提取所有图片的下载 URL
 /// 返回有水印和无水印两个版本
-pub async fn get_image_urls(api: &XhsApiClient, req: ImagesRequest) -> Result<ImagesResponse> {
-    let path = "/api/sns/web/v1/feed";
-    
-    // 构造请求体
-    let payload = serde_json::json!({
-        "source_note_id": req.note_id,
-        "image_formats": ["jpg", "webp", "avif"],
-        "xsec_source": "pc_feed",
-        "xsec_token": req.xsec_token,
-        "extra": {"need_body_topic": "1"}
-    });
-    
-    let text = api.post_algo(path, payload).await?;
-    let raw: serde_json::Value = serde_json::from_str(&text)?;
-    
+pub async fn get_image_urls(api: &XhsApiClient, req: ImagesRequest) -> XhsResult<ImagesResponse> {
+    let resp = fetch_note_detail(api, NoteDetailRequest::new(&req.note_id, &req.xsec_token)).await?;
+
     // 检查响应状态
-    if raw.get("success").and_then(|v| v.as_bool()) != Some(true) {
-        let msg = raw.get("msg").and_then(|v| v.as_str()).unwrap_or("Unknown error");
+    if !resp.success {
         return Ok(ImagesResponse {
             success: false,
-            msg: Some(msg.to_string()),
+            msg: Some(resp.msg.unwrap_or_else(|| "Unknown error".to_string())),
             data: None,
         });
     }
-    
-    // 提取笔记卡片
-    let note_card = raw
-        .pointer("/data/items/0/note_card")
-        .ok_or_else(|| anyhow!("No note_card found in response"))?;
-    
+
+    let note = resp.into_note()?;
+
     // 检查笔记类型 (normal = 图文笔记)
-    let note_type = note_card.get("type").and_then(|v| v.as_str()).unwrap_or("");
-    if note_type == "video" {
+    if note.is_video() {
         return Ok(ImagesResponse {
             success: false,
             msg: Some("This note is a video, not an image note. Use /api/note/video instead.".to_string()),
             data: None,
         });
     }
-    
-    // 提取基本信息
-    let title = note_card.get("title").and_then(|v| v.as_str()).unwrap_or("").to_string();
-    let author = note_card
-        .pointer("/user/nickname")
-        .and_then(|v| v.as_str())
-        .unwrap_or("")
-        .to_string();
-    let desc = note_card.get("desc").and_then(|v| v.as_str()).map(|s| s.to_string());
-    
-    // 提取图片列表
-    let mut images = Vec::new();
-    
-    if let Some(image_list) = note_card.get("image_list").and_then(|v| v.as_array()) {
-        for (idx, img) in image_list.iter().enumerate() {
-            if let Some(item) = parse_image_item(img, idx + 1) {
-                images.push(item);
-            }
-        }
-    }
-    
-    if images.is_empty() {
+
+    let data = ImagesData::from(&note);
+    if data.images.is_empty() {
         return Ok(ImagesResponse {
             success: false,
             msg: Some("No images found in this note".to_string()),
             data: None,
         });
     }
-    
+
     Ok(ImagesResponse {
         success: true,
         msg: None,
-        data: Some(ImagesData {
-            note_id: req.note_id,
-            title,
-            author,
-            desc,
-            image_count: images.len(),
-            images,
-        }),
+        data: Some(data),
     })
 }
 
-/// 解析单张图片
-fn parse_image_item(img: &serde_json::Value, index: usize) -> Option<ImageItem> {
-    let width = img.get("width")?.as_i64()? as i32;
-    let height = img.get("height")?.as_i64()? as i32;
-    
-    // 优先从 url_pre / url_default 获取
-    let url_original = img.get("url_pre")
-        .and_then(|v| v.as_str())
-        .filter(|s| !s.is_empty())
-        .or_else(|| {
-            // 回退到 info_list 中的 WB_PRV
-            img.get("info_list")
-                .and_then(|v| v.as_array())
-                .and_then(|arr| arr.iter().find(|item| {
-                    item.get("image_scene").and_then(|s| s.as_str()) == Some("WB_PRV")
-                }))
-                .and_then(|item| item.get("url").and_then(|v| v.as_str()))
-        })?
-        .to_string();
-    
-    let url_watermark = img.get("url_default")
-        .and_then(|v| v.as_str())
-        .filter(|s| !s.is_empty())
-        .or_else(|| {
-            // 回退到 info_list 中的 WB_DFT
-            img.get("info_list")
-                .and_then(|v| v.as_array())
-                .and_then(|arr| arr.iter().find(|item| {
-                    item.get("image_scene").and_then(|s| s.as_str()) == Some("WB_DFT")
-                }))
-                .and_then(|item| item.get("url").and_then(|v| v.as_str()))
-        })?
-        .to_string();
-    
-    Some(ImageItem {
-        index,
-        width,
-        height,
-        url_watermark,
-        url_original,
-    })
+impl From<&NoteDetail> for ImagesData {
+    /// 从笔记详情提取图片地址（缺少任一版本 URL 的图片会被跳过）
+    fn from(note: &NoteDetail) -> Self {
+        let images: Vec<ImageItem> = note
+            .image_list
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, img)| {
+                Some(ImageItem {
+                    index: idx + 1,
+                    width: img.width,
+                    height: img.height,
+                    url_watermark: img.url_watermark()?,
+                    url_original: img.url_original()?,
+                })
+            })
+            .collect();
+
+        ImagesData {
+            note_id: note.note_id.clone(),
+            title: note.title.clone(),
+            author: note.author(),
+            desc: Some(note.desc.clone()).filter(|d| !d.is_empty()),
+            image_count: images.len(),
+            images,
+        }
+    }
 }
@@ -2,8 +2,10 @@
 //!
 //! Extracts video download URLs from note details
 
+use crate::api::note::detail::{fetch_note_detail, NoteDetailRequest};
 use crate::api::XhsApiClient;
-use anyhow::{Result, anyhow};
+use crate::error::XhsResult;
+use crate::models::note::{NoteDetail, VideoStream};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -67,133 +69,90 @@ pub struct VideoItem {
 /// 获取视频下载地址
 ///
 /// 从笔记详情中提取所有画质的视频下载 URL
-pub async fn get_video_urls(api: &XhsApiClient, req: VideoRequest) -> Result<VideoResponse> {
-    let path = "/api/sns/web/v1/feed";
-    
-    // 构造请求体
-    let payload = serde_json::json!({
-        "source_note_id": req.note_id,
-        "image_formats": ["jpg", "webp", "avif"],
-        "xsec_source": "pc_feed",
-        "xsec_token": req.xsec_token,
-        "extra": {"need_body_topic": "1"}
-    });
-    
-    let text = api.post_algo(path, payload).await?;
-    let raw: serde_json::Value = serde_json::from_str(&text)?;
-    
+pub async fn get_video_urls(api: &XhsApiClient, req: VideoRequest) -> XhsResult<VideoResponse> {
+    let resp = fetch_note_detail(api, NoteDetailRequest::new(&req.note_id, &req.xsec_token)).await?;
+
     // 检查响应状态
-    if raw.get("success").and_then(|v| v.as_bool()) != Some(true) {
-        let msg = raw.get("msg").and_then(|v| v.as_str()).unwrap_or("Unknown error");
+    if !resp.success {
         return Ok(VideoResponse {
             success: false,
-            msg: Some(msg.to_string()),
+            msg: Some(resp.msg.unwrap_or_else(|| "Unknown error".to_string())),
             data: None,
         });
     }
-    
-    // 提取笔记卡片
-    let note_card = raw
-        .pointer("/data/items/0/note_card")
-        .ok_or_else(|| anyhow!("No note_card found in response"))?;
-    
+
+    let note = resp.into_note()?;
+
     // 检查是否为视频类型
-    let note_type = note_card.get("type").and_then(|v| v.as_str()).unwrap_or("");
-    if note_type != "video" {
+    if !note.is_video() {
         return Ok(VideoResponse {
             success: false,
             msg: Some("This note is not a video".to_string()),
             data: None,
         });
     }
-    
-    // 提取基本信息
-    let title = note_card.get("title").and_then(|v| v.as_str()).unwrap_or("").to_string();
-    let author = note_card
-        .pointer("/user/nickname")
-        .and_then(|v| v.as_str())
-        .unwrap_or("")
-        .to_string();
-    
-    // 提取视频时长
-    let duration = note_card
-        .pointer("/video/capa/duration")
-        .and_then(|v| v.as_i64())
-        .unwrap_or(0) * 1000; // 转为毫秒
-    
-    // 提取封面
-    let cover = note_card
-        .pointer("/image_list/0/url_default")
-        .and_then(|v| v.as_str())
-        .map(|s| s.to_string());
-    
-    // 提取视频流
-    let mut videos = Vec::new();
-    
-    // 解析 h265 流
-    if let Some(h265_streams) = note_card.pointer("/video/media/stream/h265").and_then(|v| v.as_array()) {
-        for stream in h265_streams {
-            if let Some(item) = parse_video_stream(stream, "hevc") {
-                videos.push(item);
-            }
-        }
-    }
-    
-    // 解析 h264 流
-    if let Some(h264_streams) = note_card.pointer("/video/media/stream/h264").and_then(|v| v.as_array()) {
-        for stream in h264_streams {
-            if let Some(item) = parse_video_stream(stream, "h264") {
-                videos.push(item);
-            }
-        }
-    }
-    
-    // 按文件大小降序排列 (最高画质在前)
-    videos.sort_by(|a, b| b.size.cmp(&a.size));
-    
+
     Ok(VideoResponse {
         success: true,
         msg: None,
-        data: Some(VideoData {
-            note_id: req.note_id,
-            title,
-            author,
+        data: Some(VideoData::from(&note)),
+    })
+}
+
+impl From<&NoteDetail> for VideoData {
+    /// 从笔记详情提取视频流（按文件大小降序，最高画质在前）
+    fn from(note: &NoteDetail) -> Self {
+        let video = note.video.as_ref();
+
+        // 视频时长 (capa 中为秒)
+        let duration = video
+            .and_then(|v| v.capa.as_ref())
+            .and_then(|c| c.duration)
+            .unwrap_or(0) as i64 * 1000;
+
+        let mut videos: Vec<VideoItem> = video
+            .and_then(|v| v.media.as_ref())
+            .map(|media| {
+                let streams = &media.stream;
+                streams.h265.iter().map(|s| (s, "hevc"))
+                    .chain(streams.h264.iter().map(|s| (s, "h264")))
+                    .filter_map(|(stream, codec)| parse_video_stream(stream, codec))
+                    .collect()
+            })
+            .unwrap_or_default();
+        videos.sort_by_key(|v| std::cmp::Reverse(v.size));
+
+        VideoData {
+            note_id: note.note_id.clone(),
+            title: note.title.clone(),
+            author: note.author(),
             duration,
             videos,
-            cover,
-        }),
-    })
+            cover: note.image_list.first().and_then(|img| img.url_default.clone()),
+        }
+    }
 }
 
 /// 解析单个视频流
-fn parse_video_stream(stream: &serde_json::Value, codec: &str) -> Option<VideoItem> {
-    let master_url = stream.get("master_url")?.as_str()?;
-    let width = stream.get("width")?.as_i64()? as i32;
-    let height = stream.get("height")?.as_i64()? as i32;
-    let size = stream.get("size")?.as_i64()?;
-    
+fn parse_video_stream(stream: &VideoStream, codec: &str) -> Option<VideoItem> {
+    if stream.master_url.is_empty() {
+        return None;
+    }
+
     // 构建画质标识
-    let quality = format!("{}_{}", codec, 
-        if height >= 1080 { "1080p" } 
-        else if height >= 720 { "720p" } 
+    let quality = format!("{}_{}", codec,
+        if stream.height >= 1080 { "1080p" }
+        else if stream.height >= 720 { "720p" }
         else { "480p" }
     );
-    
-    // 获取备用 URL
-    let backup_url = stream
-        .get("backup_urls")
-        .and_then(|v| v.as_array())
-        .and_then(|arr| arr.first())
-        .and_then(|v| v.as_str())
-        .map(|s| s.to_string());
-    
+
     Some(VideoItem {
         quality,
-        url: master_url.to_string(),
-        backup_url,
-        width,
-        height,
-        size,
+        url: stream.master_url.clone(),
+        backup_url: stream.backup_urls.first().cloned(),
+        width: stream.width,
+        height: stream.height,
+        size: stream.size,
         codec: codec.to_string(),
     })
 }
@@ -1,11 +1,16 @@
+pub mod cache;
 pub mod common;
 pub mod feed;
+pub mod limiter;
 pub mod login;
 pub mod media;
 pub mod note;
 pub mod notification;
+pub mod paginator;
+pub mod retry;
 pub mod search;
 pub mod user;
 pub mod creator;
 
 pub use common::XhsApiClient;
+pub use paginator::{Page, Paginator};
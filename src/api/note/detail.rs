@@ -10,7 +10,10 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 use utoipa::ToSchema;
+use crate::error::{decode, XhsResult};
+pub use crate::models::note::NoteDetailResponse;
 use crate::server::AppState;
+use crate::handlers::session::AccountSelector;
 
 /// 笔记详情请求参数
 #[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
@@ -38,15 +41,17 @@ fn default_xsec_source() -> String {
     "pc_feed".to_string()
 }
 
-/// 笔记详情响应 (简化)
-#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
-pub struct NoteDetailResponse {
-    pub code: i32,
-    pub success: bool,
-    #[serde(default)]
-    pub msg: Option<String>,
-    #[serde(default)]
-    pub data: Option<serde_json::Value>,
+impl NoteDetailRequest {
+    /// 使用默认参数构造请求（与网页端点开笔记时一致）
+    pub fn new(note_id: &str, xsec_token: &str) -> Self {
+        Self {
+            source_note_id: note_id.to_string(),
+            image_formats: default_image_formats(),
+            extra: Some(serde_json::json!({"need_body_topic": "1"})),
+            xsec_source: default_xsec_source(),
+            xsec_token: xsec_token.to_string(),
+        }
+    }
 }
 
 /// 获取笔记详情
@@ -64,6 +69,9 @@ pub struct NoteDetailResponse {
     summary = "笔记详情",
     description = "获取笔记完整内容（标题、正文、图片、标签、互动数据）。",
     request_body = NoteDetailRequest,
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号")
+    ),
     responses(
         (status = 200, description = "笔记详情", body = NoteDetailResponse),
         (status = 500, description = "请求失败")
@@ -71,23 +79,23 @@ pub struct NoteDetailResponse {
 )]
 pub async fn get_note_detail(
     State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
     Json(req): Json<NoteDetailRequest>,
 ) -> impl IntoResponse {
-    match get_note_detail_internal(&state.api, req).await {
+    match fetch_note_detail(&state.api.with_account(account), req).await {
         Ok(data) => Json(data).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
-async fn get_note_detail_internal(
+/// 获取笔记详情
+///
+/// 详情、视频地址 (`api::media::video`) 与图片地址 (`api::media::images`) 共用此请求，
+/// 通过 [`NoteDetailResponse::into_note`] 取出 [`crate::models::note::NoteDetail`]
+pub async fn fetch_note_detail(
     api: &crate::api::XhsApiClient,
     req: NoteDetailRequest,
-) -> anyhow::Result<NoteDetailResponse> {
+) -> XhsResult<NoteDetailResponse> {
     let path = "/api/sns/web/v1/feed";
     
     // 构造请求体
@@ -104,6 +112,5 @@ async fn get_note_detail_internal(
     }
     
     let text = api.post_algo(path, payload).await?;
-    let response: NoteDetailResponse = serde_json::from_str(&text)?;
-    Ok(response)
+    decode(&text)
 }
@@ -1,2 +1,4 @@
 pub mod page;
 pub mod detail;
+pub mod sub_comments;
+pub mod resolve;
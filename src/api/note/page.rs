@@ -5,7 +5,10 @@ use axum::{
 };
 use serde::Deserialize;
 use std::sync::Arc;
+use crate::error::{decode, XhsResult};
+use crate::models::comment::CommentPageResponse;
 use crate::server::AppState;
+use crate::handlers::session::AccountSelector;
 
 /// 笔记评论页请求参数
 #[derive(Deserialize, utoipa::IntoParams)]
@@ -44,43 +47,52 @@ fn default_image_formats() -> String {
     tag = "Note",
     summary = "笔记评论列表",
     description = "获取指定笔记的评论内容（分页）。如需获取笔记正文，请使用 /api/note/detail 接口。",
-    params(NotePageParams),
+    params(NotePageParams, ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号")),
     responses(
-        (status = 200, description = "评论列表（原始JSON）"),
+        (status = 200, description = "评论列表", body = CommentPageResponse),
         (status = 500, description = "请求失败")
     )
 )]
 pub async fn get_note_page(
     State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
     Query(params): Query<NotePageParams>,
 ) -> impl IntoResponse {
-    match get_note_page_internal(&state.api, params).await {
+    match get_note_page_internal(&state.api.with_account(account), params).await {
         Ok(data) => Json(data).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
 async fn get_note_page_internal(
     api: &crate::api::XhsApiClient,
     params: NotePageParams,
-) -> anyhow::Result<serde_json::Value> {
+) -> XhsResult<CommentPageResponse> {
+    get_comments(api, &params.note_id, &params.cursor, &params.top_comment_id, &params.image_formats, &params.xsec_token).await
+}
+
+/// 获取一页笔记评论
+///
+/// 分页规则请参阅 doc/comment_pagination.md，连续获取可使用 [`crate::api::Paginator::comments`]
+pub async fn get_comments(
+    api: &crate::api::XhsApiClient,
+    note_id: &str,
+    cursor: &str,
+    top_comment_id: &str,
+    image_formats: &str,
+    xsec_token: &str,
+) -> XhsResult<CommentPageResponse> {
     // 构造完整 URL（note_page 是 GET 请求，参数在 URL 中）
     let url = format!(
         "https://edith.xiaohongshu.com/api/sns/web/v2/comment/page?note_id={}&cursor={}&top_comment_id={}&image_formats={}&xsec_token={}",
-        params.note_id,
-        params.cursor,
-        params.top_comment_id,
-        params.image_formats,
-        urlencoding::encode(&params.xsec_token)
+        note_id,
+        cursor,
+        top_comment_id,
+        image_formats,
+        urlencoding::encode(xsec_token)
     );
     
     // 使用公共模块发送请求
     let text = api.get_with_url("note_page", &url).await?;
-    let response: serde_json::Value = serde_json::from_str(&text)?;
-    Ok(response)
+    decode(&text)
 }
@@ -0,0 +1,310 @@
+//! Note URL Resolver
+//!
+//! Parses note links / share text into `note_id` + `xsec_token`:
+//! - `https://www.xiaohongshu.com/explore/<note_id>?xsec_token=...`
+//! - `https://www.xiaohongshu.com/discovery/item/<note_id>?xsec_token=...`
+//! - `https://www.xiaohongshu.com/user/profile/<user_id>/<note_id>?xsec_token=...`
+//! - `http://xhslink.com/a/<code>` short links (redirects are followed)
+//! - App share text containing any of the above
+
+use axum::{response::IntoResponse, Json};
+use reqwest::header::{LOCATION, USER_AGENT};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use url::Url;
+use utoipa::ToSchema;
+use crate::config::get_client_profile;
+use crate::error::{XhsError, XhsResult};
+
+/// 短链域名
+pub const SHORT_LINK_HOSTS: &[&str] = &["xhslink.com", "www.xhslink.com"];
+
+/// 短链最多跟随的跳转次数
+const MAX_REDIRECTS: usize = 5;
+
+/// 解析笔记链接请求
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct ResolveNoteRequest {
+    /// 笔记链接、xhslink.com 短链，或 App 复制的分享文本
+    pub url: String,
+}
+
+/// 解析笔记链接响应
+#[derive(Debug, Clone, Deserialize, Serialize, ToSchema)]
+pub struct ResolveNoteResponse {
+    pub success: bool,
+    #[serde(default)]
+    pub msg: Option<String>,
+    #[serde(default)]
+    pub data: Option<ResolvedNote>,
+}
+
+/// 解析结果，可直接用于 `/api/note/detail`
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, ToSchema)]
+#[schema(example = json!({
+    "note_id": "6965aba6000000000e03c2a2",
+    "xsec_token": "ABkT8PPgDgmMj8QDzHMrqEe-bozccvXIirQq865XG_HGA=",
+    "xsec_source": "pc_share"
+}))]
+pub struct ResolvedNote {
+    /// 笔记 ID
+    pub note_id: String,
+    /// 安全Token
+    pub xsec_token: String,
+    /// xsec_source (链接中缺省时为 pc_feed)
+    pub xsec_source: String,
+}
+
+/// 解析笔记链接
+///
+/// 支持笔记页链接、`xhslink.com` 短链和 App 分享文本，返回 `note_id` / `xsec_token` / `xsec_source`
+#[utoipa::path(
+    post,
+    path = "/api/note/resolve",
+    tag = "Note",
+    summary = "解析笔记链接",
+    description = "从笔记链接、xhslink.com 短链或分享文本中解析 note_id 与 xsec_token，结果可直接用于 /api/note/detail。",
+    request_body = ResolveNoteRequest,
+    responses(
+        (status = 200, description = "解析结果", body = ResolveNoteResponse),
+        (status = 400, description = "无法识别的链接")
+    )
+)]
+pub async fn resolve_note_handler(Json(req): Json<ResolveNoteRequest>) -> impl IntoResponse {
+    match resolve_note_url(&req.url).await {
+        Ok(note) => Json(ResolveNoteResponse { success: true, msg: None, data: Some(note) }).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// 解析笔记链接（短链域名为 [`SHORT_LINK_HOSTS`]）
+pub async fn resolve_note_url(input: &str) -> XhsResult<ResolvedNote> {
+    resolve_note_url_with(input, SHORT_LINK_HOSTS).await
+}
+
+/// 解析笔记链接，`short_link_hosts` 中的域名会跟随跳转后再解析
+pub async fn resolve_note_url_with(input: &str, short_link_hosts: &[&str]) -> XhsResult<ResolvedNote> {
+    let url = extract_url(input)
+        .ok_or_else(|| XhsError::InvalidInput(format!("No URL found in: {}", input)))?;
+
+    if let Some(note) = parse_note_url(&url)? {
+        return Ok(note);
+    }
+
+    let host = url.host_str().unwrap_or_default();
+    if short_link_hosts.iter().any(|h| host.eq_ignore_ascii_case(h)) {
+        return follow_short_link(url, short_link_hosts).await;
+    }
+
+    Err(XhsError::InvalidInput(format!("Not a note URL: {}", url)))
+}
+
+/// 逐跳跟随短链，直到跳转地址可以解析为笔记链接
+///
+/// 只请求短链域名与 `*.xiaohongshu.com`，跳转到其他域名时停止，避免服务端请求任意地址
+async fn follow_short_link(mut url: Url, short_link_hosts: &[&str]) -> XhsResult<ResolvedNote> {
+    let client = reqwest::Client::builder()
+        .redirect(reqwest::redirect::Policy::none())
+        .timeout(Duration::from_secs(10))
+        .build()?;
+
+    for _ in 0..MAX_REDIRECTS {
+        tracing::info!("[NoteResolver] Following {}", url);
+        let response = client.get(url.clone()).header(USER_AGENT, get_client_profile().user_agent_header()).send().await?;
+
+        let Some(location) = response.headers().get(LOCATION).and_then(|v| v.to_str().ok()) else {
+            return Err(XhsError::InvalidInput(format!(
+                "Short link did not redirect to a note ({}): {}",
+                response.status(),
+                url
+            )));
+        };
+        url = url
+            .join(location)
+            .map_err(|e| XhsError::InvalidInput(format!("Invalid redirect location {}: {}", location, e)))?;
+
+        if let Some(note) = parse_note_url(&url)? {
+            return Ok(note);
+        }
+        if !is_followable(&url, short_link_hosts) {
+            return Err(XhsError::InvalidInput(format!("Short link redirected to an unexpected host: {}", url)));
+        }
+    }
+
+    Err(XhsError::InvalidInput(format!("Too many redirects, last URL: {}", url)))
+}
+
+/// 跳转地址是否可以继续请求：短链域名或 xiaohongshu.com 及其子域名
+fn is_followable(url: &Url, short_link_hosts: &[&str]) -> bool {
+    let host = url.host_str().unwrap_or_default().to_ascii_lowercase();
+    matches!(url.scheme(), "http" | "https")
+        && (short_link_hosts.iter().any(|h| host.eq_ignore_ascii_case(h))
+            || host == "xiaohongshu.com"
+            || host.ends_with(".xiaohongshu.com"))
+}
+
+/// 从输入中提取第一个链接（分享文本中链接前后通常带有中文说明）
+fn extract_url(input: &str) -> Option<Url> {
+    let input = input.trim();
+    let start = ["http://", "https://"]
+        .iter()
+        .filter_map(|p| input.find(p))
+        .min()
+        .or_else(|| ["xiaohongshu.com/", "xhslink.com/"].iter().filter_map(|h| input.find(h)).min())?;
+    let candidate: String = input[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_graphic())
+        .collect();
+
+    let candidate = if candidate.starts_with("http") {
+        candidate
+    } else {
+        format!("https://{}", candidate)
+    };
+    Url::parse(&candidate).ok()
+}
+
+/// 解析笔记页链接
+///
+/// 不是笔记链接时返回 `None`；是笔记链接但缺少 `xsec_token` 时返回错误。
+/// 未登录访问会被重定向到登录页，原链接保存在 `redirectPath` 中
+fn parse_note_url(url: &Url) -> XhsResult<Option<ResolvedNote>> {
+    let host = url.host_str().unwrap_or_default();
+    if host != "xiaohongshu.com" && !host.ends_with(".xiaohongshu.com") {
+        return Ok(None);
+    }
+
+    let query = |key: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.into_owned())
+            .filter(|v| !v.is_empty())
+    };
+
+    if let Some(redirect) = query("redirectPath").and_then(|r| url.join(&r).ok()) {
+        return parse_note_url(&redirect);
+    }
+
+    let segments: Vec<&str> = url.path_segments().map(|s| s.filter(|s| !s.is_empty()).collect()).unwrap_or_default();
+    let note_id = match segments.as_slice() {
+        ["explore", id] | ["discovery", "item", id] | ["user", "profile", _, id] => *id,
+        _ => return Ok(None),
+    };
+    if !is_note_id(note_id) {
+        return Ok(None);
+    }
+
+    let xsec_token = query("xsec_token")
+        .ok_or_else(|| XhsError::InvalidInput(format!("Note URL has no xsec_token: {}", url)))?;
+
+    Ok(Some(ResolvedNote {
+        note_id: note_id.to_string(),
+        xsec_token,
+        xsec_source: query("xsec_source").unwrap_or_else(|| "pc_feed".to_string()),
+    }))
+}
+
+/// 笔记 ID 为 24 位十六进制字符串
+fn is_note_id(id: &str) -> bool {
+    id.len() == 24 && id.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::header, response::Redirect, routing::get, Router};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    const NOTE_ID: &str = "6965aba6000000000e03c2a2";
+
+    fn note(xsec_source: &str) -> ResolvedNote {
+        ResolvedNote { note_id: NOTE_ID.to_string(), xsec_token: "TOKEN=".to_string(), xsec_source: xsec_source.to_string() }
+    }
+
+    fn parse(input: &str) -> XhsResult<Option<ResolvedNote>> {
+        parse_note_url(&extract_url(input).unwrap())
+    }
+
+    #[test]
+    fn extracts_first_url_from_share_text() {
+        let text = "42 小红书用户发布了一篇笔记 http://xhslink.com/a/AbCd 复制本条信息，打开App查看 https://www.xiaohongshu.com/x";
+        assert_eq!(extract_url(text).unwrap().as_str(), "http://xhslink.com/a/AbCd");
+
+        let text = "看看 https://www.xiaohongshu.com/explore/a 和 http://xhslink.com/a/b";
+        assert_eq!(extract_url(text).unwrap().path(), "/explore/a");
+
+        assert_eq!(extract_url("xhslink.com/a/AbCd，复制").unwrap().as_str(), "https://xhslink.com/a/AbCd");
+        assert!(extract_url("没有链接").is_none());
+    }
+
+    #[test]
+    fn parses_note_urls() {
+        let url = format!("https://www.xiaohongshu.com/explore/{}?xsec_token=TOKEN%3D&xsec_source=pc_share", NOTE_ID);
+        assert_eq!(parse(&url).unwrap(), Some(note("pc_share")));
+
+        let url = format!("https://www.xiaohongshu.com/discovery/item/{}?xsec_token=TOKEN%3D", NOTE_ID);
+        assert_eq!(parse(&url).unwrap(), Some(note("pc_feed")));
+
+        let url = format!("https://www.xiaohongshu.com/user/profile/5a1b/{}?xsec_token=TOKEN%3D", NOTE_ID);
+        assert_eq!(parse(&url).unwrap(), Some(note("pc_feed")));
+    }
+
+    #[test]
+    fn follows_redirect_path_of_login_page() {
+        let redirect = format!("/explore/{}?xsec_token=TOKEN%3D&xsec_source=pc_share", NOTE_ID);
+        let url = Url::parse_with_params("https://www.xiaohongshu.com/login", [("redirectPath", redirect)]).unwrap();
+        assert_eq!(parse(url.as_str()).unwrap(), Some(note("pc_share")));
+    }
+
+    #[test]
+    fn rejects_note_url_without_xsec_token() {
+        let url = format!("https://www.xiaohongshu.com/explore/{}", NOTE_ID);
+        assert!(matches!(parse(&url), Err(XhsError::InvalidInput(_))));
+        let url = format!("https://www.xiaohongshu.com/explore/{}?xsec_token=", NOTE_ID);
+        assert!(matches!(parse(&url), Err(XhsError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn ignores_non_note_urls() {
+        assert_eq!(parse("https://www.xiaohongshu.com/explore").unwrap(), None);
+        assert_eq!(parse("https://www.xiaohongshu.com/explore/not-a-note?xsec_token=t").unwrap(), None);
+        assert_eq!(parse(&format!("https://example.com/explore/{}?xsec_token=t", NOTE_ID)).unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn follows_short_link_redirects() {
+        let target = format!("https://www.xiaohongshu.com/explore/{}?xsec_token=TOKEN%3D&xsec_source=app_share", NOTE_ID);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        // Same server under a host that is not a short-link host
+        let internal = format!("http://localhost:{}/internal", addr.port());
+        let hits = Arc::new(AtomicUsize::new(0));
+        let internal_hits = hits.clone();
+        let app = Router::new()
+            .route("/a/code", get(|| async { Redirect::to("/b/code") }))
+            .route("/b/code", get(move || async move { ([(header::LOCATION, target)], axum::http::StatusCode::FOUND) }))
+            .route("/dead", get(|| async { "no redirect" }))
+            .route("/evil/code", get(move || async move { Redirect::to(&internal) }))
+            .route("/internal", get(move || async move {
+                internal_hits.fetch_add(1, Ordering::SeqCst);
+                "internal"
+            }));
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let share = format!("快来看看这篇笔记 http://{}/a/code 复制后打开App", addr);
+        let resolved = resolve_note_url_with(&share, &["127.0.0.1"]).await.unwrap();
+        assert_eq!(resolved, note("app_share"));
+
+        let dead = format!("http://{}/dead", addr);
+        assert!(matches!(resolve_note_url_with(&dead, &["127.0.0.1"]).await, Err(XhsError::InvalidInput(_))));
+
+        // 不在短链域名列表中的链接不会被请求
+        assert!(matches!(resolve_note_url_with(&share, SHORT_LINK_HOSTS).await, Err(XhsError::InvalidInput(_))));
+
+        // 跳转到其他域名时停止，不会请求该地址
+        let evil = format!("http://{}/evil/code", addr);
+        assert!(matches!(resolve_note_url_with(&evil, &["127.0.0.1"]).await, Err(XhsError::InvalidInput(_))));
+        assert_eq!(hits.load(Ordering::SeqCst), 0);
+    }
+}
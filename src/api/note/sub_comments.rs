@@ -0,0 +1,241 @@
+//! Sub-comment (Reply Thread) API
+//!
+//! 一级评论只随带前几条回复，其余回复需通过 `sub_comment_cursor` 分页展开。
+
+use axum::{
+    extract::{Query, State},
+    response::IntoResponse,
+    Json,
+};
+use futures::{Stream, StreamExt};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use crate::api::{Paginator, XhsApiClient};
+use crate::error::{decode, XhsResult};
+use crate::models::comment::{Comment, SubCommentPageResponse};
+use crate::server::AppState;
+use crate::handlers::session::AccountSelector;
+
+/// 回复列表请求参数
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct SubCommentsParams {
+    /// 笔记 ID (必填)
+    pub note_id: String,
+    /// 一级评论 ID (必填)
+    pub root_comment_id: String,
+    /// 分页游标 (首次使用一级评论的 sub_comment_cursor)
+    #[serde(default)]
+    pub cursor: String,
+    /// 每页数量 (默认: 10)
+    #[serde(default = "default_num")]
+    pub num: u32,
+    /// 图片格式 (默认: jpg,webp,avif)
+    #[serde(default = "default_image_formats")]
+    pub image_formats: String,
+    /// xsec_token (必填)
+    pub xsec_token: String,
+}
+
+fn default_num() -> u32 {
+    10
+}
+
+fn default_image_formats() -> String {
+    "jpg,webp,avif".to_string()
+}
+
+/// 评论回复列表
+///
+/// 展开一级评论下被折叠的回复（`sub_comment_has_more == true` 时）。
+///
+/// 参数说明：
+/// - `root_comment_id`: 一级评论ID
+/// - `cursor`: 首次请求使用一级评论返回的 `sub_comment_cursor`，后续使用上次返回的 `cursor`
+#[utoipa::path(
+    get,
+    path = "/api/note/sub_comments",
+    tag = "Note",
+    summary = "评论回复列表",
+    description = "获取一级评论下的回复（分页）。",
+    params(SubCommentsParams, ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号")),
+    responses(
+        (status = 200, description = "回复列表", body = SubCommentPageResponse),
+        (status = 500, description = "请求失败")
+    )
+)]
+pub async fn get_sub_comments_handler(
+    State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
+    Query(params): Query<SubCommentsParams>,
+) -> impl IntoResponse {
+    let api = state.api.with_account(account);
+    match get_sub_comments(
+        &api,
+        &params.note_id,
+        &params.root_comment_id,
+        &params.cursor,
+        params.num,
+        &params.image_formats,
+        &params.xsec_token,
+    )
+    .await
+    {
+        Ok(data) => Json(data).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// 获取一页回复
+///
+/// 连续获取可使用 [`crate::api::Paginator::sub_comments`]
+pub async fn get_sub_comments(
+    api: &XhsApiClient,
+    note_id: &str,
+    root_comment_id: &str,
+    cursor: &str,
+    num: u32,
+    image_formats: &str,
+    xsec_token: &str,
+) -> XhsResult<SubCommentPageResponse> {
+    let url = format!(
+        "https://edith.xiaohongshu.com/api/sns/web/v2/comment/sub/page?note_id={}&root_comment_id={}&num={}&cursor={}&image_formats={}&top_comment_id=&xsec_token={}",
+        urlencoding::encode(note_id),
+        urlencoding::encode(root_comment_id),
+        num,
+        urlencoding::encode(cursor),
+        image_formats,
+        urlencoding::encode(xsec_token)
+    );
+
+    let text = api.get_with_url("note_sub_comments", &url).await?;
+    decode(&text)
+}
+
+// ============================================================================
+// Full Thread
+// ============================================================================
+
+/// 展开一条一级评论的全部回复（最多 `max_replies` 条，包含随评论返回的回复）
+///
+/// 全部取完时 `sub_comment_has_more` 置为 false；达到上限时保持原值
+pub async fn expand_replies(
+    api: &XhsApiClient,
+    comment: &mut Comment,
+    note_id: &str,
+    xsec_token: &str,
+    max_replies: usize,
+    delay: Duration,
+) -> XhsResult<()> {
+    let Some(cursor) = comment.sub_comment_cursor.clone().filter(|c| !c.is_empty()) else {
+        return Ok(());
+    };
+    if !comment.sub_comment_has_more || comment.sub_comments.len() >= max_replies {
+        return Ok(());
+    }
+
+    let wanted = max_replies - comment.sub_comments.len();
+    let replies = Paginator::sub_comments(api, note_id, &comment.id, xsec_token, &cursor)
+        .max_items(wanted)
+        .delay(delay)
+        .into_stream();
+    let mut replies = std::pin::pin!(replies);
+
+    let mut fetched = 0;
+    while let Some(reply) = replies.next().await {
+        // 回复列表可能与随评论返回的前几条重叠
+        let reply = reply?;
+        fetched += 1;
+        if !comment.sub_comments.iter().any(|c| c.id == reply.id) {
+            comment.sub_comments.push(reply);
+        }
+    }
+
+    if fetched < wanted {
+        comment.sub_comment_has_more = false;
+    }
+    Ok(())
+}
+
+/// 完整评论串：逐条返回一级评论，每条评论的回复已展开（最多 `max_replies` 条）
+///
+/// `comments` 通常为 [`Paginator::comments`] 的数据流，`delay` 同时作用于回复翻页。
+/// 遇到第一个错误（评论翻页或展开回复失败）时返回该错误并结束，不再继续请求
+pub fn full_threads<S>(
+    api: &XhsApiClient,
+    comments: S,
+    note_id: &str,
+    xsec_token: &str,
+    max_replies: usize,
+    delay: Duration,
+) -> impl Stream<Item = XhsResult<Comment>> + Send
+where
+    S: Stream<Item = XhsResult<Comment>> + Send,
+{
+    let api = api.clone();
+    let note_id = note_id.to_string();
+    let xsec_token = xsec_token.to_string();
+
+    let threads = comments.then(move |comment| {
+        let api = api.clone();
+        let note_id = note_id.clone();
+        let xsec_token = xsec_token.clone();
+        async move {
+            let mut comment = comment?;
+            expand_replies(&api, &mut comment, &note_id, &xsec_token, max_replies, delay).await?;
+            Ok(comment)
+        }
+    });
+    stop_after_error(threads)
+}
+
+/// 返回第一个错误后结束数据流（不再拉取后续数据）
+fn stop_after_error<T, S>(stream: S) -> impl Stream<Item = XhsResult<T>> + Send
+where
+    T: Send,
+    S: Stream<Item = XhsResult<T>> + Send,
+{
+    futures::stream::unfold((Box::pin(stream), false), |(mut stream, failed)| async move {
+        if failed {
+            return None;
+        }
+        let item = stream.next().await?;
+        let failed = item.is_err();
+        Some((item, (stream, failed)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::AuthService;
+    use crate::client::XhsClient;
+    use crate::error::XhsError;
+    use crate::signature::NativeSigner;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn comment(id: &str) -> Comment {
+        serde_json::from_value(serde_json::json!({ "id": id, "sub_comment_has_more": false })).unwrap()
+    }
+
+    #[tokio::test]
+    async fn full_threads_stops_after_first_error() {
+        let dir = std::env::temp_dir().join(format!("xhs-threads-{}", uuid::Uuid::new_v4()));
+        let auth = Arc::new(AuthService::new(dir.clone()).await.unwrap());
+        let api = XhsApiClient::new(XhsClient::new().unwrap(), auth, Arc::new(NativeSigner::new()));
+
+        let polled = AtomicUsize::new(0);
+        let items = vec![Ok(comment("c1")), Err(XhsError::RiskControl461(String::new())), Ok(comment("c2"))];
+        let comments = futures::stream::iter(items).inspect(|_| {
+            polled.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let threads: Vec<_> = full_threads(&api, comments, "n1", "token", 10, Duration::ZERO).collect().await;
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(threads.len(), 2);
+        assert_eq!(threads[0].as_ref().unwrap().id, "c1");
+        assert!(matches!(threads[1], Err(XhsError::RiskControl461(_))));
+        assert_eq!(polled.load(Ordering::SeqCst), 2, "comments after the error must not be requested");
+    }
+}
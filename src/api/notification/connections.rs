@@ -1,5 +1,6 @@
 use crate::api::XhsApiClient;
-use anyhow::Result;
+use crate::error::{decode, XhsResult};
+use crate::models::notification::NotificationMessage;
 use serde::{Deserialize, Serialize};
 
 /// Connections request parameters (新增关注 请求参数)
@@ -40,7 +41,7 @@ pub struct ConnectionsResponse {
 #[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct ConnectionsData {
     /// 通知消息列表
-    pub message_list: Vec<serde_json::Value>,
+    pub message_list: Vec<NotificationMessage>,
     /// 下一页游标 (数值型)
     #[serde(default)]
     pub cursor: Option<i64>,
@@ -55,7 +56,7 @@ pub struct ConnectionsData {
 /// 通知页-新增关注 (默认参数)
 /// 
 /// 获取新增关注通知列表，使用默认分页参数
-pub async fn get_connections(api: &XhsApiClient) -> Result<ConnectionsResponse> {
+pub async fn get_connections(api: &XhsApiClient) -> XhsResult<ConnectionsResponse> {
     get_connections_with_params(api, ConnectionsParams::default()).await
 }
 
@@ -66,13 +67,13 @@ pub async fn get_connections(api: &XhsApiClient) -> Result<ConnectionsResponse>
 /// # Arguments
 /// * `api` - API 客户端
 /// * `params` - 分页参数 (num, cursor)
-pub async fn get_connections_with_params(api: &XhsApiClient, params: ConnectionsParams) -> Result<ConnectionsResponse> {
+pub async fn get_connections_with_params(api: &XhsApiClient, params: ConnectionsParams) -> XhsResult<ConnectionsResponse> {
     // 构建 URI
     let cursor = params.cursor.unwrap_or_default();
     let uri = format!("/api/sns/web/v1/you/connections?num={}&cursor={}", params.num, cursor);
     
     let text = api.get_with_query(&uri).await?;
-    let result = serde_json::from_str::<ConnectionsResponse>(&text)?;
+    let result = decode::<ConnectionsResponse>(&text)?;
     Ok(result)
 }
 
@@ -1,5 +1,6 @@
 use crate::api::XhsApiClient;
-use anyhow::Result;
+use crate::error::{decode, XhsResult};
+use crate::models::notification::NotificationMessage;
 use serde::{Deserialize, Serialize};
 
 /// Likes request parameters (赞和收藏 请求参数)
@@ -40,7 +41,7 @@ pub struct LikesResponse {
 #[derive(Debug, Serialize, Deserialize, utoipa::ToSchema)]
 pub struct LikesData {
     /// 通知消息列表
-    pub message_list: Vec<serde_json::Value>,
+    pub message_list: Vec<NotificationMessage>,
     /// 是否有更多数据
     #[serde(default)]
     pub has_more: bool,
@@ -55,7 +56,7 @@ pub struct LikesData {
 /// 通知页-赞和收藏 (默认参数)
 /// 
 /// 获取赞和收藏通知列表，使用默认分页参数
-pub async fn get_likes(api: &XhsApiClient) -> Result<LikesResponse> {
+pub async fn get_likes(api: &XhsApiClient) -> XhsResult<LikesResponse> {
     get_likes_with_params(api, LikesParams::default()).await
 }
 
@@ -66,12 +67,12 @@ pub async fn get_likes(api: &XhsApiClient) -> Result<LikesResponse> {
 /// # Arguments
 /// * `api` - API 客户端
 /// * `params` - 分页参数 (num, cursor)
-pub async fn get_likes_with_params(api: &XhsApiClient, params: LikesParams) -> Result<LikesResponse> {
+pub async fn get_likes_with_params(api: &XhsApiClient, params: LikesParams) -> XhsResult<LikesResponse> {
     // 构建 URI
     let cursor = params.cursor.unwrap_or_default();
     let uri = format!("/api/sns/web/v1/you/likes?num={}&cursor={}", params.num, cursor);
     
     let text = api.get_with_query(&uri).await?;
-    let result = serde_json::from_str::<LikesResponse>(&text)?;
+    let result = decode::<LikesResponse>(&text)?;
     Ok(result)
 }
@@ -1,5 +1,6 @@
 use crate::api::XhsApiClient;
-use anyhow::Result;
+use crate::error::{decode, XhsResult};
+use crate::models::notification::NotificationMessage;
 use serde::{Deserialize, Serialize};
 
 /// Mentions request parameters (评论和@ 请求参数)
@@ -49,13 +50,13 @@ pub struct MentionsData {
     #[serde(default)]
     pub has_more: bool,
     /// 通知消息列表
-    pub message_list: Vec<serde_json::Value>,
+    pub message_list: Vec<NotificationMessage>,
 }
 
 /// 通知页-评论和@ (默认参数)
 /// 
 /// 获取评论和@通知列表，使用默认分页参数
-pub async fn get_mentions(api: &XhsApiClient) -> Result<MentionsResponse> {
+pub async fn get_mentions(api: &XhsApiClient) -> XhsResult<MentionsResponse> {
     get_mentions_with_params(api, MentionsParams::default()).await
 }
 
@@ -66,13 +67,13 @@ pub async fn get_mentions(api: &XhsApiClient) -> Result<MentionsResponse> {
 /// # Arguments
 /// * `api` - API 客户端
 /// * `params` - 分页参数 (num, cursor)
-pub async fn get_mentions_with_params(api: &XhsApiClient, params: MentionsParams) -> Result<MentionsResponse> {
+pub async fn get_mentions_with_params(api: &XhsApiClient, params: MentionsParams) -> XhsResult<MentionsResponse> {
     // 构建 URI
     let cursor = params.cursor.unwrap_or_default();
     let uri = format!("/api/sns/web/v1/you/mentions?num={}&cursor={}", params.num, cursor);
     
     let text = api.get_with_query(&uri).await?;
-    let result = serde_json::from_str::<MentionsResponse>(&text)?;
+    let result = decode::<MentionsResponse>(&text)?;
     Ok(result)
 }
 
@@ -1,10 +1,12 @@
 // Notification API module
-// Handles mentions, connections and likes endpoints
+// Handles mentions, connections, likes and unread count endpoints
 
 pub mod mentions;
 pub mod connections;
 pub mod likes;
+pub mod unread;
 
 pub use mentions::get_mentions;
 pub use connections::get_connections;
 pub use likes::get_likes;
+pub use unread::get_unread_count;
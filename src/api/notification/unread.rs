@@ -0,0 +1,94 @@
+use crate::api::XhsApiClient;
+use crate::error::{decode, XhsResult};
+use crate::models::notification::NotificationType;
+use serde::{Deserialize, Serialize};
+
+/// Unread count response (未读通知数)
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct UnreadCountResponse {
+    #[serde(default)]
+    pub code: Option<i32>,
+    pub success: bool,
+    #[serde(default)]
+    pub msg: String,
+    #[serde(default)]
+    pub data: Option<UnreadCount>,
+}
+
+/// 各类通知的未读数
+#[derive(Debug, Clone, Default, Serialize, Deserialize, utoipa::ToSchema)]
+#[schema(example = json!({
+    "unread_count": 12,
+    "likes": 8,
+    "mentions": 3,
+    "connections": 1
+}))]
+pub struct UnreadCount {
+    /// 未读总数
+    #[serde(default)]
+    pub unread_count: i64,
+    /// 赞和收藏
+    #[serde(default)]
+    pub likes: i64,
+    /// 评论和@
+    #[serde(default)]
+    pub mentions: i64,
+    /// 新增关注
+    #[serde(default)]
+    pub connections: i64,
+}
+
+impl UnreadCount {
+    /// 指定类型的未读数
+    pub fn get(&self, notification_type: NotificationType) -> i64 {
+        match notification_type {
+            NotificationType::Likes => self.likes,
+            NotificationType::Mentions => self.mentions,
+            NotificationType::Connections => self.connections,
+        }
+    }
+
+    /// 是否有未读通知（有则需要同步）
+    pub fn has_unread(&self) -> bool {
+        self.unread_count > 0 || self.likes > 0 || self.mentions > 0 || self.connections > 0
+    }
+}
+
+/// 通知页-未读数
+///
+/// 获取赞和收藏、评论和@、新增关注的未读数，不拉取通知列表
+pub async fn get_unread_count(api: &XhsApiClient) -> XhsResult<UnreadCountResponse> {
+    let text = api.get("notification_unread_count").await?;
+    decode::<UnreadCountResponse>(&text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_unread_count() {
+        let response: UnreadCountResponse =
+            decode(include_str!("../../../tests/fixtures/notifications/unread_count.json")).unwrap();
+        assert_eq!(response.code, Some(0));
+        assert!(response.success);
+
+        let count = response.data.unwrap();
+        assert_eq!(count.unread_count, 12);
+        assert_eq!(count.get(NotificationType::Likes), 8);
+        assert_eq!(count.get(NotificationType::Mentions), 3);
+        assert_eq!(count.get(NotificationType::Connections), 1);
+        assert!(count.has_unread());
+    }
+
+    #[test]
+    fn missing_counts_default_to_zero() {
+        let response: UnreadCountResponse = decode(r#"{"success": true, "data": {"mentions": 2}}"#).unwrap();
+        let count = response.data.unwrap();
+        assert_eq!(count.get(NotificationType::Likes), 0);
+        assert_eq!(count.get(NotificationType::Mentions), 2);
+        assert!(count.has_unread());
+
+        assert!(!UnreadCount::default().has_unread());
+    }
+}
@@ -0,0 +1,521 @@
+//! 游标分页模块 (Cursor Pagination)
+//!
+//! 各分页接口的游标规则不同（见 `doc/*_pagination.md`）：
+//! - 评论 / 评论回复 / 用户笔记 / 收藏 / 点赞 / 专辑笔记: `cursor` 字符串，`has_more == false` 结束
+//! - 通知 (mentions / connections / likes): `strCursor`（或数值 `cursor`），`has_more == false` 结束
+//! - 主页发现: `cursor_score` + `note_index` + `refresh_type`，返回空列表结束
+//! - 搜索笔记 / 搜索用户: `page` 页码（保持同一 `search_id`），`has_more == false` 结束
+//! - 收藏专辑: `page` 页码，`has_more == false` 结束
+//!
+//! [`Paginator`] 统一封装这些规则，并以 `Stream<Item = XhsResult<T>>` 逐条返回结果：
+//!
+//! ```ignore
+//! use futures::StreamExt;
+//!
+//! let mut stream = Paginator::mentions(&api).max_items(100).into_stream();
+//! while let Some(message) = stream.next().await {
+//!     let message = message?;
+//! }
+//! ```
+//!
+//! 请求出错时流返回该错误后结束。
+
+use futures::future::BoxFuture;
+use futures::stream::{self, Stream, StreamExt};
+use std::time::Duration;
+
+use crate::api::{feed, note, notification, search, user, XhsApiClient};
+use crate::error::{XhsError, XhsResult};
+use crate::models::comment::{Comment, SubComment};
+use crate::models::feed::{HomefeedItem, HomefeedRequest};
+use crate::models::notification::NotificationMessage;
+use crate::models::search::{SearchNotesRequest, SearchUserItem, SearchUserRequest};
+use crate::models::user::{Board, UserNote, UserNotesResponse};
+
+/// 一页结果
+pub struct Page<T, C> {
+    /// 本页数据
+    pub items: Vec<T>,
+    /// 下一页游标，None 表示没有更多数据
+    pub next: Option<C>,
+}
+
+type FetchFn<T, C> = Box<dyn FnMut(C) -> BoxFuture<'static, XhsResult<Page<T, C>>> + Send>;
+
+/// 游标分页器
+///
+/// `T` 为单条数据类型，`C` 为游标类型（由具体接口决定）
+pub struct Paginator<T, C> {
+    cursor: C,
+    fetch: FetchFn<T, C>,
+    max_items: Option<usize>,
+    max_pages: Option<usize>,
+    delay: Duration,
+}
+
+impl<T: Send + 'static, C: Clone + Send + 'static> Paginator<T, C> {
+    /// 从首个游标和单页请求函数创建分页器
+    pub fn new<F>(cursor: C, fetch: F) -> Self
+    where
+        F: FnMut(C) -> BoxFuture<'static, XhsResult<Page<T, C>>> + Send + 'static,
+    {
+        Self {
+            cursor,
+            fetch: Box::new(fetch),
+            max_items: None,
+            max_pages: None,
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// 最多返回的数据条数
+    pub fn max_items(mut self, max_items: usize) -> Self {
+        self.max_items = Some(max_items);
+        self
+    }
+
+    /// 最多请求的页数
+    pub fn max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = Some(max_pages);
+        self
+    }
+
+    /// 两次翻页之间的间隔（文档建议 ≥ 1 秒以避免风控）
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// 转换为逐条返回的数据流
+    pub fn into_stream(self) -> impl Stream<Item = XhsResult<T>> + Send {
+        let max_items = self.max_items.unwrap_or(usize::MAX);
+        self.into_pages()
+            .flat_map(|page| match page {
+                Ok(page) => stream::iter(page.items.into_iter().map(Ok).collect::<Vec<_>>()),
+                Err(e) => stream::iter(vec![Err(e)]),
+            })
+            .take(max_items)
+    }
+
+    /// 转换为逐页返回的数据流（不受 `max_items` 限制）
+    ///
+    /// 最后一页的 `next` 不为 None 表示因 `max_pages` 提前结束、还有更多数据
+    pub fn into_pages(self) -> impl Stream<Item = XhsResult<Page<T, C>>> + Send {
+        let state = StreamState {
+            cursor: Some(self.cursor),
+            fetch: self.fetch,
+            pages: 0,
+            max_pages: self.max_pages,
+            delay: self.delay,
+        };
+
+        stream::unfold(state, |mut state| async move {
+            if state.max_pages.is_some_and(|max| state.pages >= max) {
+                return None;
+            }
+
+            // 没有下一页游标时结束
+            let cursor = state.cursor.take()?;
+            if state.pages > 0 && !state.delay.is_zero() {
+                tokio::time::sleep(state.delay).await;
+            }
+
+            match (state.fetch)(cursor).await {
+                Ok(page) => {
+                    state.pages += 1;
+                    state.cursor = page.next.clone();
+                    Some((Ok(page), state))
+                }
+                // 返回错误后结束（cursor 已被取走）
+                Err(e) => Some((Err(e), state)),
+            }
+        })
+    }
+}
+
+struct StreamState<T, C> {
+    cursor: Option<C>,
+    fetch: FetchFn<T, C>,
+    pages: usize,
+    max_pages: Option<usize>,
+    delay: Duration,
+}
+
+// ============================================================================
+// Endpoint Paginators
+// ============================================================================
+
+impl Paginator<Comment, String> {
+    /// 笔记评论（`cursor` 游标）
+    pub fn comments(api: &XhsApiClient, note_id: &str, xsec_token: &str) -> Self {
+        let api = api.clone();
+        let note_id = note_id.to_string();
+        let xsec_token = xsec_token.to_string();
+
+        Self::new(String::new(), move |cursor: String| {
+            let api = api.clone();
+            let note_id = note_id.clone();
+            let xsec_token = xsec_token.clone();
+            Box::pin(async move {
+                let resp = note::page::get_comments(&api, &note_id, &cursor, "", "jpg,webp,avif", &xsec_token).await?;
+                check(resp.success, resp.code, resp.msg)?;
+
+                let Some(data) = resp.data else {
+                    return Ok(Page { items: Vec::new(), next: None });
+                };
+                let next = (data.has_more && !data.cursor.is_empty()).then_some(data.cursor);
+                Ok(Page { items: data.comments, next })
+            })
+        })
+    }
+}
+
+impl Paginator<SubComment, String> {
+    /// 评论回复（`cursor` 游标，首个游标为一级评论的 `sub_comment_cursor`）
+    pub fn sub_comments(api: &XhsApiClient, note_id: &str, root_comment_id: &str, xsec_token: &str, cursor: &str) -> Self {
+        let api = api.clone();
+        let note_id = note_id.to_string();
+        let root_comment_id = root_comment_id.to_string();
+        let xsec_token = xsec_token.to_string();
+
+        Self::new(cursor.to_string(), move |cursor: String| {
+            let api = api.clone();
+            let note_id = note_id.clone();
+            let root_comment_id = root_comment_id.clone();
+            let xsec_token = xsec_token.clone();
+            Box::pin(async move {
+                let resp = note::sub_comments::get_sub_comments(&api, &note_id, &root_comment_id, &cursor, 10, "jpg,webp,avif", &xsec_token).await?;
+                check(resp.success, resp.code, resp.msg)?;
+
+                let Some(data) = resp.data else {
+                    return Ok(Page { items: Vec::new(), next: None });
+                };
+                let next = (data.has_more && !data.cursor.is_empty()).then_some(data.cursor);
+                Ok(Page { items: data.comments, next })
+            })
+        })
+    }
+}
+
+impl Paginator<UserNote, String> {
+    /// 用户笔记列表（`cursor` 游标）
+    pub fn user_posted(api: &XhsApiClient, user_id: &str, xsec_token: &str) -> Self {
+        let user_id = user_id.to_string();
+        let xsec_token = xsec_token.to_string();
+        Self::user_notes(api, move |api, cursor| {
+            let user_id = user_id.clone();
+            let xsec_token = xsec_token.clone();
+            Box::pin(async move { user::get_user_posted_notes(&api, &user_id, &cursor, 30, &xsec_token).await })
+        })
+    }
+
+    /// 我的收藏（`cursor` 游标）
+    pub fn collected(api: &XhsApiClient) -> Self {
+        Self::user_notes(api, |api, cursor| {
+            Box::pin(async move { user::get_collected_notes(&api, &cursor, 30).await })
+        })
+    }
+
+    /// 我的点赞（`cursor` 游标）
+    pub fn liked(api: &XhsApiClient) -> Self {
+        Self::user_notes(api, |api, cursor| {
+            Box::pin(async move { user::get_liked_notes(&api, &cursor, 30).await })
+        })
+    }
+
+    /// 专辑内的笔记（`cursor` 游标）
+    pub fn board_notes(api: &XhsApiClient, board_id: &str) -> Self {
+        let board_id = board_id.to_string();
+        Self::user_notes(api, move |api, cursor| {
+            let board_id = board_id.clone();
+            Box::pin(async move { user::get_board_notes(&api, &board_id, &cursor, 30).await })
+        })
+    }
+
+    /// 返回 [`UserNotesResponse`] 的接口共用同一套 `cursor` / `has_more` 规则
+    fn user_notes<F>(api: &XhsApiClient, mut request: F) -> Self
+    where
+        F: FnMut(XhsApiClient, String) -> BoxFuture<'static, XhsResult<UserNotesResponse>> + Send + 'static,
+    {
+        let api = api.clone();
+        Self::new(String::new(), move |cursor: String| {
+            let resp = request(api.clone(), cursor);
+            Box::pin(async move {
+                let resp = resp.await?;
+                check(resp.success, resp.code, resp.msg)?;
+
+                let Some(data) = resp.data else {
+                    return Ok(Page { items: Vec::new(), next: None });
+                };
+                let next = (data.has_more && !data.cursor.is_empty()).then_some(data.cursor);
+                Ok(Page { items: data.notes, next })
+            })
+        })
+    }
+}
+
+impl Paginator<Board, u32> {
+    /// 我的收藏专辑（`page` 页码）
+    pub fn boards(api: &XhsApiClient) -> Self {
+        let api = api.clone();
+        Self::new(1, move |page: u32| {
+            let api = api.clone();
+            Box::pin(async move {
+                let resp = user::get_boards(&api, page, 15).await?;
+                check(resp.success, resp.code, resp.msg)?;
+
+                let Some(data) = resp.data else {
+                    return Ok(Page { items: Vec::new(), next: None });
+                };
+                let next = (data.has_more && !data.boards.is_empty()).then_some(page + 1);
+                Ok(Page { items: data.boards, next })
+            })
+        })
+    }
+}
+
+impl Paginator<NotificationMessage, String> {
+    /// 通知-评论和@（`strCursor` 游标）
+    pub fn mentions(api: &XhsApiClient) -> Self {
+        let api = api.clone();
+        Self::new(String::new(), move |cursor: String| {
+            let api = api.clone();
+            Box::pin(async move {
+                let params = notification::mentions::MentionsParams { cursor: Some(cursor), ..Default::default() };
+                let resp = notification::mentions::get_mentions_with_params(&api, params).await?;
+                check(resp.success, resp.code.unwrap_or(-1), Some(resp.msg))?;
+
+                Ok(match resp.data {
+                    Some(data) => notification_page(data.message_list, data.has_more, data.str_cursor, data.cursor),
+                    None => Page { items: Vec::new(), next: None },
+                })
+            })
+        })
+    }
+
+    /// 通知-新增关注（`strCursor` 游标）
+    pub fn connections(api: &XhsApiClient) -> Self {
+        let api = api.clone();
+        Self::new(String::new(), move |cursor: String| {
+            let api = api.clone();
+            Box::pin(async move {
+                let params = notification::connections::ConnectionsParams { cursor: Some(cursor), ..Default::default() };
+                let resp = notification::connections::get_connections_with_params(&api, params).await?;
+                check(resp.success, -1, Some(resp.msg))?;
+
+                Ok(match resp.data {
+                    Some(data) => notification_page(data.message_list, data.has_more, data.str_cursor, data.cursor),
+                    None => Page { items: Vec::new(), next: None },
+                })
+            })
+        })
+    }
+
+    /// 通知-赞和收藏（`strCursor` 游标）
+    pub fn likes(api: &XhsApiClient) -> Self {
+        let api = api.clone();
+        Self::new(String::new(), move |cursor: String| {
+            let api = api.clone();
+            Box::pin(async move {
+                let params = notification::likes::LikesParams { cursor: Some(cursor), ..Default::default() };
+                let resp = notification::likes::get_likes_with_params(&api, params).await?;
+                check(resp.success, -1, Some(resp.msg))?;
+
+                Ok(match resp.data {
+                    Some(data) => notification_page(data.message_list, data.has_more, data.str_cursor, Some(data.cursor)),
+                    None => Page { items: Vec::new(), next: None },
+                })
+            })
+        })
+    }
+}
+
+impl Paginator<HomefeedItem, HomefeedRequest> {
+    /// 主页发现频道（`cursor_score` + `note_index` 游标）
+    ///
+    /// `category` 为频道名称（如 "recommend", "fashion"），Feed 没有 `has_more`，返回空列表时结束
+    pub fn homefeed(api: &XhsApiClient, category: &str) -> Self {
+        let api = api.clone();
+        let category = category.to_string();
+        let first = HomefeedRequest { note_index: 35, refresh_type: 1, ..Default::default() };
+
+        Self::new(first, move |req: HomefeedRequest| {
+            let api = api.clone();
+            let category = category.clone();
+            Box::pin(async move {
+                let resp = feed::category::fetch_category_feed(&api, &category, req.clone()).await?;
+                check(resp.success, resp.code, resp.msg)?;
+
+                let Some(data) = resp.data else {
+                    return Ok(Page { items: Vec::new(), next: None });
+                };
+                let cursor_score = data.cursor_score.unwrap_or_default();
+                if data.items.is_empty() || cursor_score.is_empty() {
+                    return Ok(Page { items: data.items, next: None });
+                }
+
+                // 首次: 0 + 返回数量 + 1，之后: 上次 note_index + 返回数量 + 1
+                let base = if req.refresh_type == 1 { 0 } else { req.note_index };
+                let next = HomefeedRequest {
+                    cursor_score,
+                    note_index: base + data.items.len() as i32 + 1,
+                    refresh_type: 3,
+                    ..req
+                };
+                Ok(Page { items: data.items, next: Some(next) })
+            })
+        })
+    }
+}
+
+impl Paginator<HomefeedItem, SearchNotesRequest> {
+    /// 搜索笔记（`page` 页码，同一 `search_id`）
+    pub fn search_notes(api: &XhsApiClient, mut req: SearchNotesRequest) -> Self {
+        let api = api.clone();
+        if req.search_id.as_deref().unwrap_or_default().is_empty() {
+            req.search_id = Some(search::generate_search_id());
+        }
+
+        Self::new(req, move |req: SearchNotesRequest| {
+            let api = api.clone();
+            Box::pin(async move {
+                let resp = search::search_notes(&api, req.clone()).await?;
+                check(resp.success, resp.code, resp.msg)?;
+
+                let Some(data) = resp.data else {
+                    return Ok(Page { items: Vec::new(), next: None });
+                };
+                let next = (data.has_more && !data.items.is_empty())
+                    .then(|| SearchNotesRequest { page: req.page + 1, ..req });
+                Ok(Page { items: data.items, next })
+            })
+        })
+    }
+}
+
+impl Paginator<SearchUserItem, SearchUserRequest> {
+    /// 搜索用户（`page` 页码，同一 `search_id`）
+    pub fn search_users(api: &XhsApiClient, mut req: SearchUserRequest) -> Self {
+        let api = api.clone();
+        if req.search_id.as_deref().unwrap_or_default().is_empty() {
+            req.search_id = Some(search::generate_search_id());
+        }
+
+        Self::new(req, move |req: SearchUserRequest| {
+            let api = api.clone();
+            Box::pin(async move {
+                let resp = search::search_user(&api, req.clone()).await?;
+                check(resp.success, resp.code, resp.msg)?;
+
+                let Some(data) = resp.data else {
+                    return Ok(Page { items: Vec::new(), next: None });
+                };
+                let next = (data.has_more && !data.users.is_empty())
+                    .then(|| SearchUserRequest { page: req.page + 1, request_id: None, ..req });
+                Ok(Page { items: data.users, next })
+            })
+        })
+    }
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// 业务失败（success == false）转换为错误
+fn check(success: bool, code: i32, msg: Option<String>) -> XhsResult<()> {
+    if success {
+        Ok(())
+    } else {
+        Err(XhsError::Upstream { code, msg: msg.unwrap_or_default() })
+    }
+}
+
+/// 通知接口的下一页游标：优先 `strCursor`，其次数值 `cursor`
+fn notification_page(
+    items: Vec<NotificationMessage>,
+    has_more: bool,
+    str_cursor: Option<String>,
+    cursor: Option<i64>,
+) -> Page<NotificationMessage, String> {
+    let next = str_cursor
+        .filter(|c| !c.is_empty())
+        .or_else(|| cursor.filter(|c| *c != 0).map(|c| c.to_string()));
+    Page { items, next: next.filter(|_| has_more) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// 模拟接口：每页 `per_page` 条，第 `last` 页 has_more == false；记录收到的游标
+    fn stub(per_page: u32, last: u32, cursors: Arc<Mutex<Vec<u32>>>) -> Paginator<u32, u32> {
+        Paginator::new(1, move |page: u32| {
+            cursors.lock().unwrap().push(page);
+            Box::pin(async move {
+                let items = ((page - 1) * per_page..page * per_page).collect();
+                let has_more = page < last;
+                Ok(Page { items, next: has_more.then_some(page + 1) })
+            })
+        })
+    }
+
+    #[tokio::test]
+    async fn advances_cursor_until_has_more_is_false() {
+        let cursors = Arc::new(Mutex::new(Vec::new()));
+        let items: Vec<u32> = stub(2, 3, cursors.clone()).into_stream().map(Result::unwrap).collect().await;
+
+        assert_eq!(items, [0, 1, 2, 3, 4, 5]);
+        assert_eq!(*cursors.lock().unwrap(), [1, 2, 3]);
+    }
+
+    #[tokio::test]
+    async fn stops_at_max_pages_and_reports_remaining_cursor() {
+        let cursors = Arc::new(Mutex::new(Vec::new()));
+        let pages: Vec<_> = stub(2, 10, cursors.clone()).max_pages(2).into_pages().map(Result::unwrap).collect().await;
+
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[1].next, Some(3));
+        assert_eq!(*cursors.lock().unwrap(), [1, 2]);
+    }
+
+    #[tokio::test]
+    async fn max_items_does_not_fetch_more_pages_than_needed() {
+        let cursors = Arc::new(Mutex::new(Vec::new()));
+        let items: Vec<u32> = stub(2, 10, cursors.clone()).max_items(3).into_stream().map(Result::unwrap).collect().await;
+
+        assert_eq!(items, [0, 1, 2]);
+        assert_eq!(*cursors.lock().unwrap(), [1, 2]);
+    }
+
+    #[tokio::test]
+    async fn error_ends_the_stream() {
+        let calls = Arc::new(Mutex::new(0));
+        let counter = calls.clone();
+        let paginator = Paginator::new(1, move |page: u32| {
+            *counter.lock().unwrap() += 1;
+            Box::pin(async move {
+                match page {
+                    1 => Ok(Page { items: vec![1], next: Some(2) }),
+                    _ => Err(XhsError::RiskControl461(String::new())),
+                }
+            })
+        });
+
+        let items: Vec<XhsResult<u32>> = paginator.into_stream().collect().await;
+        assert_eq!(items.len(), 2);
+        assert!(matches!(items[1], Err(XhsError::RiskControl461(_))));
+        assert_eq!(*calls.lock().unwrap(), 2);
+    }
+
+    #[test]
+    fn notification_cursor_prefers_str_cursor() {
+        let page = notification_page(Vec::new(), true, Some("s1".to_string()), Some(42));
+        assert_eq!(page.next.as_deref(), Some("s1"));
+        let page = notification_page(Vec::new(), true, Some(String::new()), Some(42));
+        assert_eq!(page.next.as_deref(), Some("42"));
+        let page = notification_page(Vec::new(), false, Some("s1".to_string()), Some(42));
+        assert_eq!(page.next, None);
+    }
+}
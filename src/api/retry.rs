@@ -0,0 +1,218 @@
+//! 重试策略 (Retry Policy)
+//!
+//! 单次传输超时、偶发的 5xx，或签名过期导致的 406 不应让整个调用失败：
+//! - 只重试幂等的只读请求（GET，以及搜索、笔记详情、首页推荐等只读 POST）
+//! - 每次重试都会重新签名（新的 x-t / x-s）
+//! - 重试间隔按指数增长并带随机抖动
+//! - 461 风控不重试，由 [`crate::api::limiter`] 负责退避；406 不计入限速器的退避与熔断
+//!
+//! 配置见 [`crate::config::RetryConfig`]。
+
+use rand::Rng;
+use std::future::Future;
+use std::time::Duration;
+
+use crate::config::get_retry_config;
+use crate::error::{XhsError, XhsResult};
+
+/// 只读的 POST 端点（可安全重试）
+const READ_ONLY_POSTS: &[&str] = &["note_detail", "search_notes", "search_onebox", "search_user"];
+
+/// 重试策略
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// 最多尝试次数（含首次），1 表示不重试
+    pub attempts: u32,
+    /// 首次重试间隔
+    pub base: Duration,
+    /// 重试间隔上限
+    pub max: Duration,
+}
+
+impl RetryPolicy {
+    /// 按 `XHS_RETRY_*` 配置创建
+    pub fn from_config() -> Self {
+        get_retry_config().policy()
+    }
+
+    /// 第 `attempt` 次失败后的等待时间，随机取 [delay/2, delay]
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(16);
+        let delay = self.base.saturating_mul(1 << exponent).min(self.max);
+        delay.mul_f64(rand::thread_rng().gen_range(0.5..=1.0))
+    }
+
+    /// 执行 `attempt` 直到成功、遇到不可重试的错误或用完 `max_attempts` 次
+    ///
+    /// `attempt` 的参数为第几次尝试（从 1 开始），每次调用都应重新签名；
+    /// 406 只触发重新签名后重试，不计入限速器的风控退避
+    pub async fn run<T, F, Fut>(&self, label: &str, max_attempts: u32, mut attempt: F) -> XhsResult<T>
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = XhsResult<T>>,
+    {
+        let mut number = 1;
+        loop {
+            match attempt(number).await {
+                Err(e) if number < max_attempts && is_retryable(&e) => {
+                    let delay = self.delay(number);
+                    tracing::warn!(
+                        "[XhsApiClient] {} attempt {}/{} failed ({}), re-signing and retrying in {:.1}s",
+                        label,
+                        number,
+                        max_attempts,
+                        e.kind(),
+                        delay.as_secs_f64()
+                    );
+                    tokio::time::sleep(delay).await;
+                    number += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// 请求最多尝试次数：非幂等请求只尝试一次
+    pub fn attempts_for(&self, method: &str, endpoint: &str) -> u32 {
+        if is_idempotent(method, endpoint) {
+            self.attempts.max(1)
+        } else {
+            1
+        }
+    }
+}
+
+/// 是否为幂等的只读请求
+pub fn is_idempotent(method: &str, endpoint: &str) -> bool {
+    method.eq_ignore_ascii_case("GET") || READ_ONLY_POSTS.contains(&endpoint) || endpoint.starts_with("home_feed_")
+}
+
+/// 是否为可重试的临时错误（传输错误、5xx、签名被拒）
+pub fn is_retryable(err: &XhsError) -> bool {
+    match err {
+        XhsError::Transport(_) | XhsError::SignatureRejected(_) => true,
+        XhsError::Upstream { code, .. } => matches!(code, 500 | 502 | 503 | 504),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn policy() -> RetryPolicy {
+        RetryPolicy { attempts: 3, base: Duration::from_millis(1), max: Duration::from_millis(4) }
+    }
+
+    /// 前 `failures` 次返回 `err`，之后成功
+    async fn run_failing(max_attempts: u32, failures: u32, err: fn() -> XhsError) -> (XhsResult<u32>, u32) {
+        let calls = AtomicU32::new(0);
+        let result = policy()
+            .run("test", max_attempts, |number| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async move { if number <= failures { Err(err()) } else { Ok(number) } }
+            })
+            .await;
+        (result, calls.load(Ordering::SeqCst))
+    }
+
+    #[test]
+    fn retryable_errors() {
+        assert!(is_retryable(&XhsError::SignatureRejected(String::new())));
+        assert!(is_retryable(&XhsError::Upstream { code: 502, msg: String::new() }));
+        assert!(!is_retryable(&XhsError::Upstream { code: 404, msg: String::new() }));
+        assert!(!is_retryable(&XhsError::RiskControl461(String::new())));
+        assert!(!is_retryable(&XhsError::NotLoggedIn));
+    }
+
+    #[test]
+    fn only_idempotent_requests_are_retried() {
+        let policy = policy();
+        assert_eq!(policy.attempts_for("GET", "user_me"), 3);
+        assert_eq!(policy.attempts_for("POST", "search_notes"), 3);
+        assert_eq!(policy.attempts_for("POST", "home_feed_recommend"), 3);
+        assert_eq!(policy.attempts_for("POST", "comment_post"), 1);
+    }
+
+    #[test]
+    fn delay_grows_exponentially_with_jitter_and_cap() {
+        let policy = RetryPolicy { attempts: 5, base: Duration::from_millis(100), max: Duration::from_millis(300) };
+        for _ in 0..20 {
+            let first = policy.delay(1);
+            assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+            let second = policy.delay(2);
+            assert!(second >= Duration::from_millis(100) && second <= Duration::from_millis(200));
+            let capped = policy.delay(10);
+            assert!(capped >= Duration::from_millis(150) && capped <= Duration::from_millis(300));
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_until_success() {
+        let (result, calls) = run_failing(3, 2, || XhsError::SignatureRejected(String::new())).await;
+        assert_eq!(result.unwrap(), 3);
+        assert_eq!(calls, 3);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_attempts() {
+        let (result, calls) = run_failing(3, 5, || XhsError::Upstream { code: 503, msg: String::new() }).await;
+        assert!(matches!(result, Err(XhsError::Upstream { code: 503, .. })));
+        assert_eq!(calls, 3);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_non_retryable_errors() {
+        let (result, calls) = run_failing(3, 1, || XhsError::RiskControl461(String::new())).await;
+        assert!(matches!(result, Err(XhsError::RiskControl461(_))));
+        assert_eq!(calls, 1);
+    }
+
+    #[tokio::test]
+    async fn single_attempt_is_not_retried() {
+        let (result, calls) = run_failing(1, 1, || XhsError::SignatureRejected(String::new())).await;
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[tokio::test]
+    async fn only_exhausted_406_trips_the_limiter() {
+        use crate::api::limiter::{BackoffPolicy, EndpointFamily, RateLimiter};
+        use std::sync::atomic::AtomicBool;
+
+        let limiter = RateLimiter::new(
+            Default::default(),
+            BackoffPolicy { base: Duration::from_millis(1), max: Duration::from_millis(1), threshold: 2, cooldown: Duration::from_secs(900) },
+        );
+        // Same composition as XhsApiClient::with_retry_policy
+        let run = |recovers: bool| {
+            let rejected = AtomicBool::new(false);
+            let limiter = &limiter;
+            async move {
+                let result: XhsResult<()> = policy()
+                    .run("test", 3, |_| {
+                        let recovered = recovers && rejected.swap(true, Ordering::SeqCst);
+                        limiter.run(EndpointFamily::Search, async move {
+                            if recovered { Ok(()) } else { Err(XhsError::SignatureRejected(String::new())) }
+                        })
+                    })
+                    .await;
+                limiter.observe_exhausted(EndpointFamily::Search, &result);
+                result
+            }
+        };
+
+        // A 406 fixed by re-signing is not counted
+        for _ in 0..3 {
+            assert!(run(true).await.is_ok());
+        }
+        assert!(limiter.acquire(EndpointFamily::Search).await.is_ok());
+
+        // Each exhausted run counts once, not once per attempt
+        assert!(run(false).await.is_err());
+        assert!(limiter.acquire(EndpointFamily::Search).await.is_ok());
+        assert!(run(false).await.is_err());
+        assert!(matches!(limiter.acquire(EndpointFamily::Search).await, Err(XhsError::RateLimited { .. })));
+    }
+}
@@ -1,4 +1,4 @@
-use anyhow::Result;
+use crate::error::{decode, XhsResult};
 use crate::api::XhsApiClient;
 use crate::models::search::*;
 use rand::{Rng, distributions::Alphanumeric};
@@ -7,22 +7,22 @@ use std::time::{SystemTime, UNIX_EPOCH};
 /// 猜你想搜
 /// 
 /// 获取小红书首页搜索框的热门搜索推荐词
-pub async fn query_trending(api: &XhsApiClient) -> Result<QueryTrendingResponse> {
+pub async fn query_trending(api: &XhsApiClient) -> XhsResult<QueryTrendingResponse> {
     let text = api.get("search_trending").await?;
-    let result = serde_json::from_str::<QueryTrendingResponse>(&text)?;
+    let result = decode::<QueryTrendingResponse>(&text)?;
     Ok(result)
 }
 
 /// 搜索推荐 (联想词)
 /// 
 /// 根据关键词获取搜索建议
-pub async fn recommend_search(api: &XhsApiClient, keyword: &str) -> Result<SearchRecommendResponse> {
+pub async fn recommend_search(api: &XhsApiClient, keyword: &str) -> XhsResult<SearchRecommendResponse> {
     let encoded_keyword = urlencoding::encode(keyword);
     let url = format!("https://edith.xiaohongshu.com/api/sns/web/v1/search/recommend?keyword={}", encoded_keyword);
     
     // 使用 get_with_url 处理动态参数并进行纯算法签名
     let text = api.get_with_url("search_recommend", &url).await?;
-    let result = serde_json::from_str::<SearchRecommendResponse>(&text)?;
+    let result = decode::<SearchRecommendResponse>(&text)?;
     Ok(result)
 }
 
@@ -64,7 +64,7 @@ fn generate_request_id() -> String {
 }
 
 /// 搜索笔记列表
-pub async fn search_notes(api: &XhsApiClient, mut req: SearchNotesRequest) -> Result<SearchNotesResponse> {
+pub async fn search_notes(api: &XhsApiClient, mut req: SearchNotesRequest) -> XhsResult<SearchNotesResponse> {
     // 自动补全 search_id (格式: xxx@xxx)
     if req.search_id.is_none() || req.search_id.as_ref().is_some_and(|s| s.is_empty()) {
         req.search_id = Some(generate_search_id());
@@ -92,7 +92,7 @@ pub async fn search_notes(api: &XhsApiClient, mut req: SearchNotesRequest) -> Re
     
     // 使用 post_algo 进行签名和发送
     let text = api.post_algo(path, payload).await?;
-    let mut result = serde_json::from_str::<SearchNotesResponse>(&text)?;
+    let mut result = decode::<SearchNotesResponse>(&text)?;
     
     // 注入 search_id 到响应中，供客户端用于后续请求 (如 onebox)
     if let Some(ref mut data) = result.data {
@@ -105,7 +105,7 @@ pub async fn search_notes(api: &XhsApiClient, mut req: SearchNotesRequest) -> Re
 /// 搜索 OneBox (聚合结果)
 /// 
 /// 注意：onebox 应使用与 search/notes 相同的 search_id 来关联搜索会话
-pub async fn search_onebox(api: &XhsApiClient, mut req: SearchOneboxRequest) -> Result<SearchOneboxResponse> {
+pub async fn search_onebox(api: &XhsApiClient, mut req: SearchOneboxRequest) -> XhsResult<SearchOneboxResponse> {
     // 只在 search_id 为空时才自动生成，保持与 notes 的会话关联
     if req.search_id.is_empty() {
         req.search_id = generate_simple_search_id();
@@ -119,24 +119,24 @@ pub async fn search_onebox(api: &XhsApiClient, mut req: SearchOneboxRequest) ->
     let payload = serde_json::to_value(&req)?;
     
     let text = api.post_algo(path, payload).await?;
-    let result = serde_json::from_str::<SearchOneboxResponse>(&text)?;
+    let result = decode::<SearchOneboxResponse>(&text)?;
     Ok(result)
 }
 
 /// 搜索筛选器
-pub async fn search_filter(api: &XhsApiClient, keyword: &str, search_id: &str) -> Result<SearchFilterResponse> {
+pub async fn search_filter(api: &XhsApiClient, keyword: &str, search_id: &str) -> XhsResult<SearchFilterResponse> {
     let encoded_kw = urlencoding::encode(keyword);
     let encoded_sid = urlencoding::encode(search_id);
     let url = format!("https://edith.xiaohongshu.com/api/sns/web/v1/search/filter?keyword={}&search_id={}", encoded_kw, encoded_sid);
     
     // get_with_url 适用于任何 edith URL，只要路径正确即可
     let text = api.get_with_url("search_filter", &url).await?;
-    let result = serde_json::from_str::<SearchFilterResponse>(&text)?;
+    let result = decode::<SearchFilterResponse>(&text)?;
     Ok(result)
 }
 
 /// 搜索用户列表
-pub async fn search_user(api: &XhsApiClient, mut req: SearchUserRequest) -> Result<SearchUserResponse> {
+pub async fn search_user(api: &XhsApiClient, mut req: SearchUserRequest) -> XhsResult<SearchUserResponse> {
     // 补全 search_id (使用简单格式)
     if req.search_id.is_none() || req.search_id.as_ref().map(|s| s.starts_with("demo")).unwrap_or(false) {
         req.search_id = Some(generate_simple_search_id());
@@ -154,6 +154,6 @@ pub async fn search_user(api: &XhsApiClient, mut req: SearchUserRequest) -> Resu
     
     let payload = serde_json::to_value(&request_wrapper)?;
     let text = api.post_algo(path, payload).await?;
-    let result = serde_json::from_str::<SearchUserResponse>(&text)?;
+    let result = decode::<SearchUserResponse>(&text)?;
     Ok(result)
 }
@@ -1,16 +1,173 @@
 use crate::api::XhsApiClient;
-use crate::models::user::UserMeResponse;
-use anyhow::Result;
+use crate::models::user::{BoardListResponse, UserMeResponse, UserNotesResponse, UserProfileResponse};
+use crate::error::{decode, XhsResult};
 
 /// 页面-我
 /// 
 /// 获取当前登录用户的个人信息
 /// 
 /// 使用 Python 端捕获的 user_me 签名发送请求
-pub async fn get_current_user(api: &XhsApiClient) -> Result<UserMeResponse> {
+pub async fn get_current_user(api: &XhsApiClient) -> XhsResult<UserMeResponse> {
     // 使用公共模块的 get 方法，自动处理签名和 headers
     let text = api.get("user_me").await?;
     
-    let result = serde_json::from_str::<UserMeResponse>(&text)?;
+    let result = decode::<UserMeResponse>(&text)?;
     Ok(result)
 }
+
+/// 用户主页
+///
+/// 获取指定用户的简介、关注/粉丝/获赞数、资料标签和 IP 属地。
+/// `xsec_token` 来自搜索用户、笔记作者或评论者信息
+pub async fn get_user_profile(api: &XhsApiClient, user_id: &str, xsec_token: &str) -> XhsResult<UserProfileResponse> {
+    let url = format!(
+        "https://edith.xiaohongshu.com/api/sns/web/v1/user/otherinfo?target_user_id={}&xsec_token={}&xsec_source=pc_note",
+        urlencoding::encode(user_id),
+        urlencoding::encode(xsec_token)
+    );
+
+    let text = api.get_with_url("user_otherinfo", &url).await?;
+    decode(&text)
+}
+
+/// 用户笔记列表
+///
+/// 按 `cursor` 分页获取指定用户发布的笔记（首次为空，后续使用上次返回的 cursor），
+/// 连续获取可使用 [`crate::api::Paginator::user_posted`]
+pub async fn get_user_posted_notes(
+    api: &XhsApiClient,
+    user_id: &str,
+    cursor: &str,
+    num: u32,
+    xsec_token: &str,
+) -> XhsResult<UserNotesResponse> {
+    let url = format!(
+        "https://edith.xiaohongshu.com/api/sns/web/v1/user_posted?num={}&cursor={}&user_id={}&image_formats=jpg,webp,avif&xsec_token={}&xsec_source=pc_note",
+        num,
+        urlencoding::encode(cursor),
+        urlencoding::encode(user_id),
+        urlencoding::encode(xsec_token)
+    );
+
+    let text = api.get_with_url("user_posted", &url).await?;
+    decode(&text)
+}
+
+/// 我的收藏
+///
+/// 按 `cursor` 分页获取当前账号收藏的笔记，
+/// 连续获取可使用 [`crate::api::Paginator::collected`]
+pub async fn get_collected_notes(api: &XhsApiClient, cursor: &str, num: u32) -> XhsResult<UserNotesResponse> {
+    let user_id = api.current_user_id().await?;
+    let uri = collected_uri(&user_id, cursor, num);
+
+    let text = api.get_with_query(&uri).await?;
+    decode(&text)
+}
+
+/// 我的点赞
+///
+/// 按 `cursor` 分页获取当前账号点赞的笔记，
+/// 连续获取可使用 [`crate::api::Paginator::liked`]
+pub async fn get_liked_notes(api: &XhsApiClient, cursor: &str, num: u32) -> XhsResult<UserNotesResponse> {
+    let user_id = api.current_user_id().await?;
+    let uri = liked_uri(&user_id, cursor, num);
+
+    let text = api.get_with_query(&uri).await?;
+    decode(&text)
+}
+
+/// 我的收藏专辑
+///
+/// 按 `page` 页码（从 1 开始）获取当前账号的收藏专辑
+pub async fn get_boards(api: &XhsApiClient, page: u32, num: u32) -> XhsResult<BoardListResponse> {
+    let user_id = api.current_user_id().await?;
+    let uri = boards_uri(&user_id, page, num);
+
+    let text = api.get_with_query(&uri).await?;
+    decode(&text)
+}
+
+/// 专辑内的笔记
+///
+/// 按 `cursor` 分页获取收藏专辑中的笔记，
+/// 连续获取可使用 [`crate::api::Paginator::board_notes`]
+pub async fn get_board_notes(api: &XhsApiClient, board_id: &str, cursor: &str, num: u32) -> XhsResult<UserNotesResponse> {
+    let uri = board_notes_uri(board_id, cursor, num);
+
+    let text = api.get_with_query(&uri).await?;
+    decode(&text)
+}
+
+fn collected_uri(user_id: &str, cursor: &str, num: u32) -> String {
+    format!(
+        "/api/sns/web/v2/note/collect/page?num={}&cursor={}&user_id={}",
+        num,
+        urlencoding::encode(cursor),
+        urlencoding::encode(user_id)
+    )
+}
+
+fn liked_uri(user_id: &str, cursor: &str, num: u32) -> String {
+    format!(
+        "/api/sns/web/v1/note/like/page?num={}&cursor={}&user_id={}",
+        num,
+        urlencoding::encode(cursor),
+        urlencoding::encode(user_id)
+    )
+}
+
+fn boards_uri(user_id: &str, page: u32, num: u32) -> String {
+    format!("/api/sns/web/v1/board/user?user_id={}&num={}&page={}", urlencoding::encode(user_id), num, page)
+}
+
+fn board_notes_uri(board_id: &str, cursor: &str, num: u32) -> String {
+    format!(
+        "/api/sns/web/v1/board/note?board_id={}&num={}&cursor={}",
+        urlencoding::encode(board_id),
+        num,
+        urlencoding::encode(cursor)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn query(uri: &str) -> HashMap<String, String> {
+        url::Url::parse(&format!("https://edith.xiaohongshu.com{}", uri))
+            .unwrap()
+            .query_pairs()
+            .into_owned()
+            .collect()
+    }
+
+    #[test]
+    fn query_parameters_cannot_inject_extra_fields() {
+        let cursor = "abc&user_id=victim";
+        let id = "u1=x&num=999";
+
+        for uri in [collected_uri(id, cursor, 30), liked_uri(id, cursor, 30)] {
+            let params = query(&uri);
+            assert_eq!(params.len(), 3, "{}", uri);
+            assert_eq!(params["cursor"], cursor);
+            assert_eq!(params["user_id"], id);
+            assert_eq!(params["num"], "30");
+        }
+
+        let params = query(&boards_uri(id, 2, 30));
+        assert_eq!((params.len(), params["user_id"].as_str(), params["num"].as_str()), (3, id, "30"));
+
+        let params = query(&board_notes_uri(id, cursor, 30));
+        assert_eq!(params.len(), 3);
+        assert_eq!(params["board_id"], id);
+        assert_eq!(params["cursor"], cursor);
+
+        // 普通取值保持原样
+        assert_eq!(
+            board_notes_uri("65a1b2c3000000001a0210de", "", 30),
+            "/api/sns/web/v1/board/note?board_id=65a1b2c3000000001a0210de&num=30&cursor="
+        );
+    }
+}
@@ -14,7 +14,7 @@ use tracing::{info, warn};
 /// 2. Navigates to XHS
 /// 3. Waits for user to scan QR code
 /// 4. Captures cookies after login
-/// 5. Saves credentials to MongoDB
+/// 5. Saves credentials to `cookie.json` (imported into the session registry)
 pub async fn trigger_python_login() -> Result<()> {
     info!("Triggering Python Playwright login script...");
     
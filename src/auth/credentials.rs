@@ -2,6 +2,9 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::client::ClientProfile;
+use crate::config::get_client_profile;
+
 /// User credentials captured from browser login
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserCredentials {
@@ -24,6 +27,10 @@ pub struct UserCredentials {
     
     /// Whether these credentials are currently valid
     pub is_valid: bool,
+
+    /// Browser fingerprint used for this account (None uses the configured default)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<ClientProfile>,
 }
 
 impl UserCredentials {
@@ -37,8 +44,14 @@ impl UserCredentials {
             created_at: now,
             updated_at: now,
             is_valid: true,
+            profile: None,
         }
     }
+
+    /// Browser fingerprint for requests made with these credentials
+    pub fn profile(&self) -> &ClientProfile {
+        self.profile.as_ref().unwrap_or_else(|| get_client_profile())
+    }
     
     /// Get cookies as a single string for HTTP headers
     pub fn cookie_string(&self) -> String {
@@ -49,6 +62,17 @@ impl UserCredentials {
             .join("; ")
     }
     
+    /// Merge `Set-Cookie` header values from an XHS response into the stored cookies
+    ///
+    /// Returns the names of the cookies that changed (see [`merge_set_cookies`]).
+    pub fn merge_set_cookies<'a>(&mut self, set_cookies: impl IntoIterator<Item = &'a str>) -> Vec<String> {
+        let changed = merge_set_cookies(&mut self.cookies, set_cookies);
+        if !changed.is_empty() {
+            self.touch();
+        }
+        changed
+    }
+
     /// Check if credentials might be expired (older than 7 days)
     pub fn is_potentially_expired(&self) -> bool {
         let now = Utc::now();
@@ -68,6 +92,62 @@ impl UserCredentials {
     }
 }
 
+/// Cookies that identify the login session or feed the signature
+///
+/// Only changes to these are worth persisting; the rest (`acw_tc`, `websectiga`, ...) are
+/// rotating tracker cookies that XHS re-issues on almost every response. Those stay in memory
+/// and are sent on later requests; they only reach storage as part of the next save.
+pub const AUTH_COOKIES: &[&str] = &[
+    "a1",
+    "webId",
+    "web_session",
+    "id_token",
+    "gid",
+    "customer-sso-sid",
+    "access-token-creator.xiaohongshu.com",
+    "galaxy.creator.beaker.session.id",
+];
+
+/// Whether a cookie belongs to [`AUTH_COOKIES`]
+pub fn is_auth_cookie(name: &str) -> bool {
+    AUTH_COOKIES.contains(&name)
+}
+
+/// Merge `Set-Cookie` header values into a cookie map
+///
+/// Cookies that are deleted or already expired (empty value, `Max-Age<=0`,
+/// past `Expires`) are removed. Returns the names of the cookies that changed.
+pub fn merge_set_cookies<'a>(
+    cookies: &mut HashMap<String, String>,
+    set_cookies: impl IntoIterator<Item = &'a str>,
+) -> Vec<String> {
+    let now = time::OffsetDateTime::now_utc();
+    let mut changed = Vec::new();
+
+    for header in set_cookies {
+        let Ok(cookie) = cookie::Cookie::parse(header) else {
+            continue;
+        };
+        let expired = cookie.value().is_empty()
+            || cookie.max_age().is_some_and(|age| age <= time::Duration::ZERO)
+            || cookie.expires_datetime().is_some_and(|at| at <= now);
+
+        let updated = if expired {
+            cookies.remove(cookie.name()).is_some()
+        } else if cookies.get(cookie.name()).map(String::as_str) != Some(cookie.value()) {
+            cookies.insert(cookie.name().to_string(), cookie.value().to_string());
+            true
+        } else {
+            false
+        };
+        if updated && !changed.iter().any(|name| name == cookie.name()) {
+            changed.push(cookie.name().to_string());
+        }
+    }
+
+    changed
+}
+
 /// API endpoint signature (legacy, kept for compatibility)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiSignature {
@@ -108,3 +188,30 @@ pub struct ApiSignature {
     pub is_valid: bool,
 }
 
+
+/// A past version of an account's credentials (stored by backends with history)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CredentialSnapshot {
+    /// When this version was saved
+    pub recorded_at: DateTime<Utc>,
+
+    /// The credentials as saved at that time
+    pub credentials: UserCredentials,
+}
+
+/// A validity transition of an account (login, relogin, invalidation, removal)
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ValidityEvent {
+    /// XHS user ID
+    pub user_id: String,
+
+    /// Event kind: "login", "relogin", "invalidated", "removed"
+    pub event: String,
+
+    /// Validity after the event
+    pub is_valid: bool,
+
+    /// When the transition happened
+    #[schema(value_type = String, format = DateTime)]
+    pub at: DateTime<Utc>,
+}
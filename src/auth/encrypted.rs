@@ -0,0 +1,367 @@
+//! Encrypted-at-rest credential storage
+//!
+//! Each account is stored as `<dir>/<user_id>.enc`, a small JSON envelope:
+//!
+//! ```text
+//! {"v": 1, "alg": "AES-256-GCM", "nonce": base64(12 bytes), "ciphertext": base64(...)}
+//! ```
+//!
+//! The plaintext is the serialized `UserCredentials`; the slot name (percent-encoded
+//! user_id) is bound as associated data so slot files cannot be swapped between accounts.
+//!
+//! Key source (32 bytes, hex or base64):
+//! 1. `XHS_CREDENTIAL_KEY`
+//! 2. keyfile at `XHS_CREDENTIAL_KEYFILE` (default `.xhs-credential.key`), generated with 0600 on first use
+//!    (refused when `*.enc` slots already exist, since they could not be decrypted with a new key)
+//!
+//! Plaintext `*.json` slots found in the directory are migrated to `*.enc` and removed.
+
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+use super::credentials::UserCredentials;
+use super::storage::{
+    create_private_dir, list_files, read_json_file, remove_slot, slot_path, write_atomic,
+    CredentialStore,
+};
+use crate::config::StorageConfig;
+
+const ENVELOPE_VERSION: u8 = 1;
+const ALGORITHM: &str = "AES-256-GCM";
+const EXTENSION: &str = "enc";
+
+/// On-disk envelope
+#[derive(Serialize, Deserialize)]
+struct Envelope {
+    v: u8,
+    alg: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// AES-256-GCM encrypted credential storage (one file per account)
+pub struct EncryptedCredentialStorage {
+    dir: PathBuf,
+    cipher: Aes256Gcm,
+}
+
+impl EncryptedCredentialStorage {
+    /// Create a new encrypted storage, migrating plaintext slots in the directory
+    pub async fn new(dir: PathBuf, key: [u8; 32]) -> Result<Self> {
+        create_private_dir(&dir).await?;
+        let storage = Self {
+            dir,
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&key)),
+        };
+        storage.migrate_plaintext().await?;
+        info!("Using encrypted credential storage: {}", storage.dir.display());
+        Ok(storage)
+    }
+
+    /// Storage directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn encrypt(&self, creds: &UserCredentials, aad: &str) -> Result<Vec<u8>> {
+        let plaintext = serde_json::to_vec(creds)?;
+
+        let mut nonce = [0u8; 12];
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload { msg: &plaintext, aad: aad.as_bytes() },
+            )
+            .map_err(|_| anyhow!("Failed to encrypt credentials for user: {}", creds.user_id))?;
+
+        let envelope = Envelope {
+            v: ENVELOPE_VERSION,
+            alg: ALGORITHM.to_string(),
+            nonce: BASE64.encode(nonce),
+            ciphertext: BASE64.encode(ciphertext),
+        };
+        Ok(serde_json::to_vec_pretty(&envelope)?)
+    }
+
+    async fn read_slot(&self, path: &Path) -> Result<UserCredentials> {
+        let content = tokio::fs::read(path).await?;
+        let envelope: Envelope = serde_json::from_slice(&content)?;
+        if envelope.v != ENVELOPE_VERSION || envelope.alg != ALGORITHM {
+            return Err(anyhow!("Unsupported credentials envelope v{} ({})", envelope.v, envelope.alg));
+        }
+
+        let nonce = BASE64.decode(&envelope.nonce)?;
+        if nonce.len() != 12 {
+            return Err(anyhow!("Invalid nonce length in {}", path.display()));
+        }
+        let ciphertext = BASE64.decode(&envelope.ciphertext)?;
+
+        let plaintext = self
+            .cipher
+            .decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: slot_name(path).as_bytes() })
+            .map_err(|_| anyhow!("Failed to decrypt {} (wrong key or tampered file)", path.display()))?;
+
+        Ok(serde_json::from_slice(&plaintext)?)
+    }
+
+    /// Move plaintext `*.json` slots to encrypted `*.enc` slots
+    async fn migrate_plaintext(&self) -> Result<()> {
+        for path in list_files(&self.dir, "json").await? {
+            match read_json_file(&path).await {
+                Ok(creds) => {
+                    self.save_credentials(&creds).await?;
+                    tokio::fs::remove_file(&path).await?;
+                    info!("Migrated plaintext credentials for user: {} to encrypted storage", creds.user_id);
+                }
+                Err(e) => warn!("Skipping unreadable credentials file {}: {}", path.display(), e),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CredentialStore for EncryptedCredentialStorage {
+    fn name(&self) -> &'static str {
+        "encrypted"
+    }
+
+    async fn list_credentials(&self) -> Result<Vec<UserCredentials>> {
+        let mut result = Vec::new();
+
+        for path in list_files(&self.dir, EXTENSION).await? {
+            match self.read_slot(&path).await {
+                Ok(creds) => result.push(creds),
+                Err(e) => warn!("Skipping unreadable credentials file {}: {}", path.display(), e),
+            }
+        }
+
+        Ok(result)
+    }
+
+    async fn get_credentials(&self, user_id: &str) -> Result<Option<UserCredentials>> {
+        let path = slot_path(&self.dir, user_id, EXTENSION);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(self.read_slot(&path).await?))
+    }
+
+    async fn save_credentials(&self, creds: &UserCredentials) -> Result<()> {
+        let path = slot_path(&self.dir, &creds.user_id, EXTENSION);
+        write_atomic(&path, &self.encrypt(creds, slot_name(&path))?).await?;
+        info!("Saved encrypted credentials for user: {} to {}", creds.user_id, path.display());
+        Ok(())
+    }
+
+    async fn remove_credentials(&self, user_id: &str) -> Result<bool> {
+        remove_slot(&slot_path(&self.dir, user_id, EXTENSION), user_id).await
+    }
+}
+
+/// Slot name (file stem), used as associated data
+fn slot_name(path: &Path) -> &str {
+    path.file_stem().and_then(|s| s.to_str()).unwrap_or_default()
+}
+
+// ============================================================================
+// Key Loading
+// ============================================================================
+
+/// Load the storage key from `XHS_CREDENTIAL_KEY` or the keyfile
+///
+/// A missing keyfile is only generated when no `*.enc` slot exists in `dir` or the
+/// configured session directories; otherwise those slots could never be decrypted.
+pub async fn load_key(config: &StorageConfig, dir: &Path) -> Result<[u8; 32]> {
+    if let Some(ref key) = config.key {
+        return parse_key(key).ok_or_else(|| anyhow!("XHS_CREDENTIAL_KEY must be 32 bytes encoded as hex or base64"));
+    }
+
+    let path = &config.keyfile();
+    if path.exists() {
+        let content = tokio::fs::read_to_string(path).await?;
+        return parse_key(&content)
+            .ok_or_else(|| anyhow!("Keyfile {} must contain 32 bytes encoded as hex or base64", path.display()));
+    }
+
+    for dir in [dir, config.sessions_dir.as_path(), config.creator_sessions_dir.as_path()] {
+        if dir.is_dir() && !list_files(dir, EXTENSION).await?.is_empty() {
+            return Err(anyhow!(
+                "Encrypted sessions exist in {} but no key is configured: set XHS_CREDENTIAL_KEY or restore {}",
+                dir.display(),
+                path.display()
+            ));
+        }
+    }
+
+    // First use: generate a random key
+    let mut key = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut key);
+    write_atomic(path, BASE64.encode(key).as_bytes()).await?;
+    warn!("Generated new credential key at {} - back it up, stored sessions cannot be read without it", path.display());
+    Ok(key)
+}
+
+/// Parse a 32-byte key from hex (64 chars) or base64
+fn parse_key(text: &str) -> Option<[u8; 32]> {
+    let text = text.trim();
+
+    let bytes = if text.len() == 64 && text.chars().all(|c| c.is_ascii_hexdigit()) {
+        (0..64)
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&text[i..i + 2], 16))
+            .collect::<std::result::Result<Vec<u8>, _>>()
+            .ok()?
+    } else {
+        BASE64.decode(text).ok()?
+    };
+
+    bytes.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    const KEY: [u8; 32] = [7u8; 32];
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            Self(std::env::temp_dir().join(format!("xhs-test-{}", uuid::Uuid::new_v4())))
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn creds(user_id: &str) -> UserCredentials {
+        let cookies = HashMap::from([
+            ("a1".to_string(), "a1-value".to_string()),
+            ("web_session".to_string(), "secret-session".to_string()),
+        ]);
+        UserCredentials::new(user_id.to_string(), cookies, None)
+    }
+
+    async fn storage(dir: &TempDir, key: [u8; 32]) -> EncryptedCredentialStorage {
+        EncryptedCredentialStorage::new(dir.0.clone(), key).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn round_trips_without_plaintext_on_disk() {
+        let dir = TempDir::new();
+        let store = storage(&dir, KEY).await;
+        store.save_credentials(&creds("u1")).await.unwrap();
+
+        let loaded = store.get_credentials("u1").await.unwrap().unwrap();
+        assert_eq!(loaded.user_id, "u1");
+        assert_eq!(loaded.cookies["web_session"], "secret-session");
+        assert_eq!(store.list_credentials().await.unwrap().len(), 1);
+
+        let raw = std::fs::read_to_string(dir.0.join("u1.enc")).unwrap();
+        assert!(!raw.contains("secret-session"));
+        assert!(raw.contains(ALGORITHM));
+    }
+
+    #[tokio::test]
+    async fn rejects_wrong_key() {
+        let dir = TempDir::new();
+        storage(&dir, KEY).await.save_credentials(&creds("u1")).await.unwrap();
+
+        let other = storage(&dir, [8u8; 32]).await;
+        assert!(other.get_credentials("u1").await.is_err());
+        assert!(other.list_credentials().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn rejects_tampered_ciphertext() {
+        let dir = TempDir::new();
+        let store = storage(&dir, KEY).await;
+        store.save_credentials(&creds("u1")).await.unwrap();
+
+        let path = dir.0.join("u1.enc");
+        let mut envelope: Envelope = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        let mut ciphertext = BASE64.decode(&envelope.ciphertext).unwrap();
+        ciphertext[0] ^= 0x01;
+        envelope.ciphertext = BASE64.encode(ciphertext);
+        std::fs::write(&path, serde_json::to_vec(&envelope).unwrap()).unwrap();
+
+        assert!(store.get_credentials("u1").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_slot_renamed_to_another_account() {
+        let dir = TempDir::new();
+        let store = storage(&dir, KEY).await;
+        store.save_credentials(&creds("u1")).await.unwrap();
+        std::fs::copy(dir.0.join("u1.enc"), dir.0.join("u2.enc")).unwrap();
+
+        assert!(store.get_credentials("u1").await.unwrap().is_some());
+        assert!(store.get_credentials("u2").await.is_err());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn slot_files_are_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new();
+        storage(&dir, KEY).await.save_credentials(&creds("u1")).await.unwrap();
+
+        let mode = std::fs::metadata(dir.0.join("u1.enc")).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+
+    #[test]
+    fn parses_hex_and_base64_keys() {
+        let hex: String = KEY.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(parse_key(&hex), Some(KEY));
+        assert_eq!(parse_key(&format!("  {}\n", hex.to_uppercase())), Some(KEY));
+        assert_eq!(parse_key(&BASE64.encode(KEY)), Some(KEY));
+
+        assert_eq!(parse_key(&hex[..62]), None);
+        assert_eq!(parse_key(&BASE64.encode([7u8; 16])), None);
+        assert_eq!(parse_key(&BASE64.encode([7u8; 33])), None);
+        assert_eq!(parse_key("not a key"), None);
+    }
+
+    #[tokio::test]
+    async fn refuses_to_generate_a_key_over_existing_slots() {
+        let dir = TempDir::new();
+        std::fs::create_dir_all(&dir.0).unwrap();
+        let config = StorageConfig {
+            keyfile: Some(dir.0.join("credential.key")),
+            sessions_dir: dir.0.join("sessions"),
+            creator_sessions_dir: dir.0.join("sessions-creator"),
+            ..StorageConfig::default()
+        };
+
+        // Nothing stored yet: a key is generated and reused
+        let key = load_key(&config, &config.sessions_dir).await.unwrap();
+        assert_eq!(load_key(&config, &config.sessions_dir).await.unwrap(), key);
+
+        let store = EncryptedCredentialStorage::new(config.creator_sessions_dir.clone(), key).await.unwrap();
+        store.save_credentials(&creds("u1")).await.unwrap();
+        std::fs::remove_file(config.keyfile()).unwrap();
+
+        let error = load_key(&config, &config.sessions_dir).await.unwrap_err();
+        assert!(error.to_string().contains("no key is configured"), "{}", error);
+        assert!(!config.keyfile().exists());
+    }
+}
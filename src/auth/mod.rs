@@ -1,9 +1,15 @@
 pub mod credentials;
 pub mod storage;
+pub mod encrypted;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
 pub mod browser;
 pub mod service;
 
 pub use credentials::UserCredentials;
-pub use storage::CredentialStorage;
+pub use storage::{CredentialStorage, CredentialStore};
+pub use encrypted::EncryptedCredentialStorage;
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteCredentialStorage;
 pub use service::AuthService;
 
@@ -1,59 +1,158 @@
 //! Authentication service that manages credentials and triggers browser login when needed
+//!
+//! Acts as a session registry: every logged-in account has its own slot keyed by
+//! `user_id`. Callers either name an account explicitly or fall back to the
+//! default account (the most recently logged-in one unless set explicitly).
 
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::sync::RwLock;
-use tracing::{info, warn};
+use tokio::sync::{Mutex, RwLock};
+use tracing::{debug, info, warn};
+use utoipa::ToSchema;
 
-use crate::auth::{CredentialStorage, UserCredentials};
+use crate::auth::UserCredentials;
+use crate::client::ClientProfile;
+use crate::auth::credentials::{is_auth_cookie, ApiSignature, CredentialSnapshot, ValidityEvent};
+use crate::auth::storage::{open_store, CredentialStore};
 use crate::auth::browser::trigger_python_login;
 
-use std::path::PathBuf;
+/// File written by `scripts/login.py` after browser login
+const LEGACY_COOKIE_FILE: &str = "cookie.json";
 
-/// Global authentication state
+/// Summary of a stored session (no cookie values)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SessionInfo {
+    /// XHS user ID (registry key)
+    pub user_id: String,
+    /// Whether the session is currently valid
+    pub is_valid: bool,
+    /// Whether this is the default account (used when no X-Xhs-Account header is sent)
+    pub is_default: bool,
+    /// Number of stored cookies
+    pub cookie_count: usize,
+    #[schema(value_type = String, format = DateTime)]
+    pub created_at: DateTime<Utc>,
+    #[schema(value_type = String, format = DateTime)]
+    pub updated_at: DateTime<Utc>,
+    /// Result of the last health check (None if never checked)
+    pub health: Option<SessionHealth>,
+    /// Account-specific browser fingerprint (None uses the configured default)
+    pub profile: Option<ClientProfile>,
+}
+
+/// Outcome of a session health check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    /// The session is logged in
+    Alive,
+    /// XHS reported the session as logged out
+    Expired,
+    /// The check itself failed (network, signer, risk control); validity unchanged
+    Error,
+}
+
+/// Last health check result of a session
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SessionHealth {
+    pub status: HealthStatus,
+    #[schema(value_type = String, format = DateTime)]
+    pub checked_at: DateTime<Utc>,
+    /// Error message for `expired` / `error`
+    pub detail: Option<String>,
+}
+
+/// Global authentication state (multi-account session registry)
 pub struct AuthService {
-    storage: CredentialStorage,
-    cached_credentials: Arc<RwLock<Option<UserCredentials>>>,
+    storage: Box<dyn CredentialStore>,
+    sessions: Arc<RwLock<HashMap<String, UserCredentials>>>,
+    default_account: Arc<RwLock<Option<String>>>,
+    health: Arc<RwLock<HashMap<String, SessionHealth>>>,
+    /// Serializes background credential saves (see `persist`)
+    persist_lock: Mutex<()>,
 }
 
 impl AuthService {
-    /// Create a new authentication service (uses JSON file storage)
-    pub async fn new(storage_path: PathBuf) -> Result<Self> {
-        let storage = CredentialStorage::new(storage_path).await?;
-        
-        // Try to load existing credentials
-        let cached = storage.get_active_credentials().await?;
-        
-        if cached.is_some() {
-            info!("Loaded existing credentials from storage");
-        }
-        
+    /// Create a new authentication service (one file per account in `storage_dir`)
+    ///
+    /// The backend (plaintext JSON, encrypted or SQLite) is selected by `XHS_STORAGE`.
+    pub async fn new(storage_dir: PathBuf) -> Result<Self> {
+        Self::with_store(open_store(storage_dir).await?).await
+    }
+
+    /// Create a new authentication service with an explicit storage backend
+    pub async fn with_store(storage: Box<dyn CredentialStore>) -> Result<Self> {
+
+        // Load all existing sessions
+        let sessions: HashMap<String, UserCredentials> = storage
+            .list_credentials()
+            .await?
+            .into_iter()
+            .map(|c| (c.user_id.clone(), c))
+            .collect();
+
+        if !sessions.is_empty() {
+            info!("Loaded {} sessions from storage", sessions.len());
+        }
+
+        let default_account = latest_valid(&sessions);
+
         Ok(Self {
             storage,
-            cached_credentials: Arc::new(RwLock::new(cached)),
+            sessions: Arc::new(RwLock::new(sessions)),
+            default_account: Arc::new(RwLock::new(default_account)),
+            health: Arc::new(RwLock::new(HashMap::new())),
+            persist_lock: Mutex::new(()),
         })
     }
-    
-    /// Get current credentials passively (check cache and file only)
-    /// Returns None if no valid credentials found, does NOT trigger login
+
+    /// Import a legacy single-account credentials file into its own slot
+    pub async fn import_file(&self, path: &Path) -> Result<Option<String>> {
+        let imported = self.storage.import_file(path).await?;
+        if let Some(ref user_id) = imported {
+            if let Some(creds) = self.storage.get_credentials(user_id).await? {
+                self.sessions.write().await.insert(user_id.clone(), creds);
+            }
+        }
+        Ok(imported)
+    }
+
+    /// Get current credentials passively (default account, does NOT trigger login)
+    /// Returns None if no valid credentials found
     pub async fn try_get_credentials(&self) -> Result<Option<UserCredentials>> {
+        self.try_get_credentials_for(None).await
+    }
+
+    /// Get credentials for a specific account, or the default account when `account` is None
+    pub async fn try_get_credentials_for(&self, account: Option<&str>) -> Result<Option<UserCredentials>> {
+        let user_id = match account {
+            Some(id) => id.to_string(),
+            None => match self.default_account().await {
+                Some(id) => id,
+                None => return Ok(None),
+            },
+        };
+
         // Check cache first
         {
-            let cache = self.cached_credentials.read().await;
-            if let Some(ref creds) = *cache {
-                if creds.is_valid && !creds.is_potentially_expired() {
-                    return Ok(Some(creds.clone()));
-                }
+            let sessions = self.sessions.read().await;
+            if let Some(creds) = sessions.get(&user_id) {
+                return Ok(creds.is_valid.then(|| creds.clone()));
             }
         }
-        
-        // Try to load from file
-        if let Some(creds) = self.storage.get_active_credentials().await? {
-            let mut cache = self.cached_credentials.write().await;
-            *cache = Some(creds.clone());
-            return Ok(Some(creds));
+
+        // Try to load from file (may have been written by another process)
+        if let Some(creds) = self.storage.get_credentials(&user_id).await? {
+            self.sessions.write().await.insert(user_id, creds.clone());
+            if creds.is_valid {
+                return Ok(Some(creds));
+            }
         }
-        
+
         Ok(None)
     }
 
@@ -63,77 +162,397 @@ impl AuthService {
         if let Some(creds) = self.try_get_credentials().await? {
             return Ok(creds);
         }
-        
+
         // No valid credentials - need to trigger login
         info!("No valid credentials found, triggering browser login...");
         self.trigger_login().await?;
-        
-        // Reload credentials from file after login
-        if let Some(creds) = self.storage.get_active_credentials().await? {
-            let mut cache = self.cached_credentials.write().await;
-            *cache = Some(creds.clone());
+
+        // The login script writes cookie.json; import it into its own slot
+        if let Some(user_id) = self.import_file(Path::new(LEGACY_COOKIE_FILE)).await? {
+            self.set_default_account(&user_id).await?;
+        }
+        if let Some(creds) = self.try_get_credentials().await? {
             return Ok(creds);
         }
-        
+
         Err(anyhow::anyhow!("Failed to get credentials after login"))
     }
-    
+
     /// Trigger browser-based login using Python Playwright
     pub async fn trigger_login(&self) -> Result<()> {
         println!("\n╔════════════════════════════════════════════════════════════╗");
         println!("║           需要登录小红书                                    ║");
         println!("║  即将打开浏览器，请在浏览器中扫码登录                        ║");
         println!("╚════════════════════════════════════════════════════════════╝\n");
-        
+
         // Run Python Playwright script (which saves to cookie.json)
         trigger_python_login().await?;
-        
+
         info!("Login successful, credentials saved to cookie.json");
-        
+
         Ok(())
     }
-    
-    /// Mark current credentials as invalid (e.g., after 406 error)
+
+    /// Mark the default account's credentials as invalid (e.g., after a login-required response)
     pub async fn invalidate_credentials(&self) -> Result<()> {
-        warn!("Invalidating current credentials");
-        
-        self.storage.invalidate_all().await?;
-        
-        let mut cache = self.cached_credentials.write().await;
-        *cache = None;
-        
+        match self.default_account().await {
+            Some(user_id) => self.invalidate_account(&user_id).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Mark a specific account's credentials as invalid
+    pub async fn invalidate_account(&self, user_id: &str) -> Result<()> {
+        warn!("Invalidating credentials for user: {}", user_id);
+
+        self.storage.invalidate_user(user_id).await?;
+
+        if let Some(creds) = self.sessions.write().await.get_mut(user_id) {
+            creds.invalidate();
+        }
+
         Ok(())
     }
-    
-    /// Save new credentials (used after QR code login success)
+
+    /// Save credentials into the account's own slot (used after QR code login success)
+    ///
+    /// Other accounts are left untouched. The saved account becomes the default
+    /// if there was none or the current default is no longer valid.
     pub async fn save_credentials(&self, creds: &UserCredentials) -> Result<()> {
         // Save to JSON file
         self.storage.save_credentials(creds).await?;
-        
+
         // Update cache
-        let mut cache = self.cached_credentials.write().await;
-        *cache = Some(creds.clone());
-        
+        let mut sessions = self.sessions.write().await;
+        sessions.insert(creds.user_id.clone(), creds.clone());
+
+        let mut default = self.default_account.write().await;
+        let default_valid = default
+            .as_ref()
+            .and_then(|id| sessions.get(id))
+            .is_some_and(|c| c.is_valid);
+        if !default_valid {
+            *default = Some(creds.user_id.clone());
+        }
+
         info!("Saved credentials for user: {}", creds.user_id);
         Ok(())
     }
-    
-    /// Get captured signature for a specific endpoint (legacy, returns None)
-    pub async fn get_endpoint_signature(&self, endpoint: &str) -> Result<Option<super::credentials::ApiSignature>> {
+
+    /// Remove an account's session, returns whether it existed
+    pub async fn remove_session(&self, user_id: &str) -> Result<bool> {
+        let existed = self.storage.remove_credentials(user_id).await?;
+
+        // Lock order: sessions -> default_account
+        let mut sessions = self.sessions.write().await;
+        let cached = sessions.remove(user_id).is_some();
+
+        let mut default = self.default_account.write().await;
+        if default.as_deref() == Some(user_id) {
+            *default = latest_valid(&sessions);
+        }
+        self.health.write().await.remove(user_id);
+
+        Ok(existed || cached)
+    }
+
+    /// List all known sessions
+    pub async fn list_sessions(&self) -> Vec<SessionInfo> {
+        let default = self.default_account().await;
+        let health = self.health.read().await.clone();
+        let sessions = self.sessions.read().await;
+
+        let mut list: Vec<SessionInfo> = sessions
+            .values()
+            .map(|c| SessionInfo {
+                user_id: c.user_id.clone(),
+                is_valid: c.is_valid,
+                is_default: default.as_deref() == Some(c.user_id.as_str()),
+                cookie_count: c.cookies.len(),
+                created_at: c.created_at,
+                updated_at: c.updated_at,
+                health: health.get(&c.user_id).cloned(),
+                profile: c.profile.clone(),
+            })
+            .collect();
+        list.sort_by_key(|s| std::cmp::Reverse(s.updated_at));
+        list
+    }
+
+    /// Record the result of a health check for an account
+    pub async fn record_health(&self, user_id: &str, health: SessionHealth) {
+        self.health.write().await.insert(user_id.to_string(), health);
+    }
+
+    /// Last health check result for an account
+    pub async fn health(&self, user_id: &str) -> Option<SessionHealth> {
+        self.health.read().await.get(user_id).cloned()
+    }
+
+    /// Current default account
+    ///
+    /// Falls back to the most recently updated valid session when the
+    /// configured default is missing or no longer valid.
+    pub async fn default_account(&self) -> Option<String> {
+        // Lock order: sessions -> default_account
+        let sessions = self.sessions.read().await;
+        let default = self.default_account.read().await.clone();
+
+        match default {
+            Some(id) if sessions.get(&id).is_some_and(|c| c.is_valid) => Some(id),
+            _ => latest_valid(&sessions),
+        }
+    }
+
+    /// Set the default account (must be a known session)
+    pub async fn set_default_account(&self, user_id: &str) -> Result<()> {
+        if !self.sessions.read().await.contains_key(user_id) {
+            return Err(anyhow::anyhow!("Unknown account: {}", user_id));
+        }
+        *self.default_account.write().await = Some(user_id.to_string());
+        info!("Default account set to: {}", user_id);
+        Ok(())
+    }
+
+    /// Merge `Set-Cookie` headers from an XHS response into an account's cookies
+    ///
+    /// The in-memory credentials are updated under a short lock. They are persisted only
+    /// when an auth-relevant cookie changed (see [`AUTH_COOKIES`]), so rotating tracker
+    /// cookies like `acw_tc` do not cost a write (or a history row) per response. Storage IO
+    /// happens outside the session lock. Returns whether anything changed.
+    ///
+    /// [`AUTH_COOKIES`]: crate::auth::credentials::AUTH_COOKIES
+    pub async fn merge_set_cookies(&self, user_id: &str, set_cookies: &[String]) -> Result<bool> {
+        let changed = {
+            let mut sessions = self.sessions.write().await;
+            let Some(creds) = sessions.get_mut(user_id) else {
+                return Ok(false);
+            };
+            creds.merge_set_cookies(set_cookies.iter().map(String::as_str))
+        };
+        if changed.is_empty() {
+            return Ok(false);
+        }
+        if !changed.iter().any(|name| is_auth_cookie(name)) {
+            debug!("Refreshed tracker cookies of {} (not persisted): {}", user_id, changed.join(", "));
+            return Ok(true);
+        }
+
+        info!("Refreshed cookies of {}: {}", user_id, changed.join(", "));
+        self.persist(user_id).await?;
+        Ok(true)
+    }
+
+    /// Set or clear (None) an account's browser fingerprint, returns whether the account exists
+    pub async fn set_profile(&self, user_id: &str, profile: Option<ClientProfile>) -> Result<bool> {
+        let user_agent = {
+            let mut sessions = self.sessions.write().await;
+            let Some(creds) = sessions.get_mut(user_id) else {
+                return Ok(false);
+            };
+            creds.profile = profile;
+            creds.profile().user_agent.clone()
+        };
+
+        info!("Client profile of {} set to: {}", user_id, user_agent);
+        self.persist(user_id).await?;
+        Ok(true)
+    }
+
+    /// Write the current in-memory credentials of an account to storage
+    ///
+    /// Takes a snapshot under a read lock and saves it without holding the session lock.
+    /// Saves are serialized so an older snapshot never overwrites a newer one.
+    async fn persist(&self, user_id: &str) -> Result<()> {
+        let _guard = self.persist_lock.lock().await;
+        let Some(snapshot) = self.sessions.read().await.get(user_id).cloned() else {
+            return Ok(());
+        };
+        self.storage.save_credentials(&snapshot).await
+    }
+
+    /// Storage backend name ("json", "encrypted", "sqlite")
+    pub fn storage_name(&self) -> &'static str {
+        self.storage.name()
+    }
+
+    /// Past versions of an account's credentials, newest first (SQLite backend only)
+    pub async fn credential_history(&self, user_id: &str) -> Result<Vec<CredentialSnapshot>> {
+        self.storage.credential_history(user_id).await
+    }
+
+    /// Validity transitions of an account, newest first (SQLite backend only)
+    pub async fn validity_events(&self, user_id: &str) -> Result<Vec<ValidityEvent>> {
+        self.storage.validity_events(user_id).await
+    }
+
+    /// Whether the storage backend keeps `ApiSignature` records (SQLite backend only)
+    pub fn records_signatures(&self) -> bool {
+        self.storage.records_signatures()
+    }
+
+    /// Record a captured API signature (SQLite backend only)
+    pub async fn save_api_signature(&self, signature: &ApiSignature) -> Result<()> {
+        self.storage.save_api_signature(signature).await
+    }
+
+    /// Latest valid API signature for an endpoint (SQLite backend only)
+    pub async fn get_api_signature(&self, endpoint: &str) -> Result<Option<ApiSignature>> {
         self.storage.get_api_signature(endpoint).await
     }
-    
+
     /// Generate a dummy signature - in new architecture, we use x-s-common from stored credentials
     /// The actual signing happens in the browser during login
     pub async fn sign_request(&self, _url: &str, _method: &str, _body: Option<&str>) -> Result<(String, i64, String)> {
         // Get credentials which contain pre-captured x-s-common
         let creds = self.get_credentials().await?;
-        
+
         // Return placeholder x-s and x-t - these should ideally come from browser
         // For now, we return empty strings as we need a different approach for signing
         let x_t = chrono::Utc::now().timestamp_millis();
-        
+
         Ok(("".to_string(), x_t, creds.x_s_common.unwrap_or_default()))
     }
 }
 
+/// Most recently updated valid session
+fn latest_valid(sessions: &HashMap<String, UserCredentials>) -> Option<String> {
+    sessions
+        .values()
+        .filter(|c| c.is_valid)
+        .max_by_key(|c| c.updated_at)
+        .map(|c| c.user_id.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// In-memory store counting saves
+    #[derive(Default)]
+    struct CountingStore {
+        saves: Arc<AtomicUsize>,
+        creds: std::sync::Mutex<HashMap<String, UserCredentials>>,
+        /// When set, the next save waits until this receiver fires
+        hold_next_save: Arc<std::sync::Mutex<Option<tokio::sync::oneshot::Receiver<()>>>>,
+    }
+
+    #[async_trait]
+    impl CredentialStore for CountingStore {
+        fn name(&self) -> &'static str {
+            "memory"
+        }
+
+        async fn list_credentials(&self) -> Result<Vec<UserCredentials>> {
+            Ok(self.creds.lock().unwrap().values().cloned().collect())
+        }
+
+        async fn get_credentials(&self, user_id: &str) -> Result<Option<UserCredentials>> {
+            Ok(self.creds.lock().unwrap().get(user_id).cloned())
+        }
+
+        async fn save_credentials(&self, creds: &UserCredentials) -> Result<()> {
+            self.saves.fetch_add(1, Ordering::SeqCst);
+            let hold = self.hold_next_save.lock().unwrap().take();
+            if let Some(hold) = hold {
+                let _ = hold.await;
+            }
+            self.creds.lock().unwrap().insert(creds.user_id.clone(), creds.clone());
+            Ok(())
+        }
+
+        async fn remove_credentials(&self, user_id: &str) -> Result<bool> {
+            Ok(self.creds.lock().unwrap().remove(user_id).is_some())
+        }
+    }
+
+    async fn service() -> (AuthService, Arc<AtomicUsize>) {
+        let store = CountingStore::default();
+        let saves = store.saves.clone();
+        let auth = AuthService::with_store(Box::new(store)).await.unwrap();
+        let cookies = HashMap::from([("a1".to_string(), "a".to_string()), ("web_session".to_string(), "s1".to_string())]);
+        auth.save_credentials(&UserCredentials::new("u1".to_string(), cookies, None)).await.unwrap();
+        saves.store(0, Ordering::SeqCst);
+        (auth, saves)
+    }
+
+    #[tokio::test]
+    async fn tracker_cookies_are_merged_but_not_persisted() {
+        let (auth, saves) = service().await;
+
+        assert!(auth.merge_set_cookies("u1", &["acw_tc=abc; Path=/".to_string()]).await.unwrap());
+        assert_eq!(saves.load(Ordering::SeqCst), 0);
+
+        let creds = auth.try_get_credentials_for(Some("u1")).await.unwrap().unwrap();
+        assert_eq!(creds.cookies["acw_tc"], "abc");
+    }
+
+    #[tokio::test]
+    async fn auth_cookie_changes_are_persisted_once() {
+        let (auth, saves) = service().await;
+
+        assert!(auth.merge_set_cookies("u1", &["web_session=s2; HttpOnly".to_string()]).await.unwrap());
+        assert_eq!(saves.load(Ordering::SeqCst), 1);
+
+        // Same value again: nothing changed, nothing saved
+        assert!(!auth.merge_set_cookies("u1", &["web_session=s2".to_string()]).await.unwrap());
+        assert_eq!(saves.load(Ordering::SeqCst), 1);
+
+        assert_eq!(auth.storage.get_credentials("u1").await.unwrap().unwrap().cookies["web_session"], "s2");
+    }
+
+    #[tokio::test]
+    async fn unknown_account_is_ignored() {
+        let (auth, saves) = service().await;
+        assert!(!auth.merge_set_cookies("nobody", &["web_session=x".to_string()]).await.unwrap());
+        assert_eq!(saves.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn set_profile_does_not_roll_back_a_concurrent_cookie_save() {
+        let store = CountingStore::default();
+        let saves = store.saves.clone();
+        let hold = store.hold_next_save.clone();
+        let auth = Arc::new(AuthService::with_store(Box::new(store)).await.unwrap());
+        let cookies = HashMap::from([("a1".to_string(), "a".to_string()), ("web_session".to_string(), "s1".to_string())]);
+        auth.save_credentials(&UserCredentials::new("u1".to_string(), cookies, None)).await.unwrap();
+        saves.store(0, Ordering::SeqCst);
+
+        // Block the profile save mid-write, then rotate web_session behind it
+        let (release, held) = tokio::sync::oneshot::channel();
+        *hold.lock().unwrap() = Some(held);
+        let profile = ClientProfile {
+            user_agent: "Mozilla/5.0 Test".to_string(),
+            ..Default::default()
+        };
+        let set = tokio::spawn({
+            let auth = auth.clone();
+            async move { auth.set_profile("u1", Some(profile)).await }
+        });
+        while saves.load(Ordering::SeqCst) == 0 {
+            tokio::task::yield_now().await;
+        }
+        let merge = tokio::spawn({
+            let auth = auth.clone();
+            async move { auth.merge_set_cookies("u1", &["web_session=s2".to_string()]).await }
+        });
+        tokio::task::yield_now().await;
+        release.send(()).unwrap();
+
+        assert!(set.await.unwrap().unwrap());
+        assert!(merge.await.unwrap().unwrap());
+
+        let stored = auth.storage.get_credentials("u1").await.unwrap().unwrap();
+        assert_eq!(stored.cookies["web_session"], "s2");
+        assert_eq!(stored.profile.unwrap().user_agent, "Mozilla/5.0 Test");
+    }
+
+    #[tokio::test]
+    async fn set_profile_on_unknown_account_saves_nothing() {
+        let (auth, saves) = service().await;
+        assert!(!auth.set_profile("nobody", None).await.unwrap());
+        assert_eq!(saves.load(Ordering::SeqCst), 0);
+    }
+}
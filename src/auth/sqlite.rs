@@ -0,0 +1,430 @@
+//! SQLite credential storage (feature `sqlite`)
+//!
+//! One database holds every registry; rows are partitioned by `scope`
+//! (the registry directory name, e.g. `sessions` / `sessions-creator`).
+//!
+//! Tables:
+//! - `credentials`: current credentials per account
+//! - `credential_history`: every saved version (relogins and past sessions are kept)
+//! - `validity_events`: login / relogin / invalidated / removed transitions
+//! - `api_signatures`: `ApiSignature` records of the signatures generated per endpoint
+//!   (the latest 20 per endpoint are kept)
+//!
+//! rusqlite is synchronous, so every call runs on the blocking thread pool.
+
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use tracing::{info, warn};
+
+use super::credentials::{ApiSignature, CredentialSnapshot, UserCredentials, ValidityEvent};
+use super::storage::CredentialStore;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS credentials (
+    scope       TEXT NOT NULL,
+    user_id     TEXT NOT NULL,
+    data        TEXT NOT NULL,
+    is_valid    INTEGER NOT NULL,
+    created_at  TEXT NOT NULL,
+    updated_at  TEXT NOT NULL,
+    PRIMARY KEY (scope, user_id)
+);
+CREATE TABLE IF NOT EXISTS credential_history (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    scope       TEXT NOT NULL,
+    user_id     TEXT NOT NULL,
+    data        TEXT NOT NULL,
+    is_valid    INTEGER NOT NULL,
+    recorded_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_history_user ON credential_history (scope, user_id, id);
+CREATE TABLE IF NOT EXISTS validity_events (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    scope       TEXT NOT NULL,
+    user_id     TEXT NOT NULL,
+    event       TEXT NOT NULL,
+    is_valid    INTEGER NOT NULL,
+    at          TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_events_user ON validity_events (scope, user_id, id);
+CREATE TABLE IF NOT EXISTS api_signatures (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    scope       TEXT NOT NULL,
+    endpoint    TEXT NOT NULL,
+    data        TEXT NOT NULL,
+    is_valid    INTEGER NOT NULL,
+    captured_at TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS idx_signatures_endpoint ON api_signatures (scope, endpoint, id);
+";
+
+/// Number of `api_signatures` rows kept per endpoint
+const SIGNATURES_PER_ENDPOINT: i64 = 20;
+
+/// SQLite credential storage
+pub struct SqliteCredentialStorage {
+    conn: Arc<Mutex<Connection>>,
+    scope: String,
+}
+
+impl SqliteCredentialStorage {
+    /// Open (or create) the database and use `scope` to partition rows
+    pub async fn open(path: &Path, scope: String) -> Result<Self> {
+        let path = path.to_path_buf();
+        let db_path = path.display().to_string();
+
+        let conn = tokio::task::spawn_blocking(move || -> Result<Connection> {
+            let conn = Connection::open(&path)?;
+            conn.execute_batch("PRAGMA journal_mode = WAL;")?;
+            conn.execute_batch(SCHEMA)?;
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::PermissionsExt;
+                std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))?;
+            }
+            Ok(conn)
+        })
+        .await??;
+
+        info!("Using SQLite credential storage: {} (scope: {})", db_path, scope);
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+            scope,
+        })
+    }
+
+    /// Run a closure against the connection on the blocking pool
+    async fn with_conn<T, F>(&self, f: F) -> Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection, &str) -> Result<T> + Send + 'static,
+    {
+        let conn = self.conn.clone();
+        let scope = self.scope.clone();
+        tokio::task::spawn_blocking(move || {
+            let conn = conn.lock().map_err(|_| anyhow!("SQLite connection poisoned"))?;
+            f(&conn, &scope)
+        })
+        .await?
+    }
+}
+
+fn record_event(conn: &Connection, scope: &str, user_id: &str, event: &str, is_valid: bool) -> Result<()> {
+    conn.execute(
+        "INSERT INTO validity_events (scope, user_id, event, is_valid, at) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![scope, user_id, event, is_valid, Utc::now().to_rfc3339()],
+    )?;
+    Ok(())
+}
+
+fn parse_time(text: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(text)
+        .map(|t| t.with_timezone(&Utc))
+        .unwrap_or_else(|_| Utc::now())
+}
+
+#[async_trait]
+impl CredentialStore for SqliteCredentialStorage {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    fn records_signatures(&self) -> bool {
+        true
+    }
+
+    async fn list_credentials(&self) -> Result<Vec<UserCredentials>> {
+        self.with_conn(|conn, scope| {
+            let mut stmt = conn.prepare("SELECT user_id, data FROM credentials WHERE scope = ?1")?;
+            let rows = stmt.query_map(params![scope], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?;
+
+            let mut result = Vec::new();
+            for row in rows {
+                let (user_id, data) = row?;
+                match serde_json::from_str(&data) {
+                    Ok(creds) => result.push(creds),
+                    Err(e) => warn!("Skipping unreadable credentials row for {}: {}", user_id, e),
+                }
+            }
+            Ok(result)
+        })
+        .await
+    }
+
+    async fn get_credentials(&self, user_id: &str) -> Result<Option<UserCredentials>> {
+        let user_id = user_id.to_string();
+        self.with_conn(move |conn, scope| {
+            let data: Option<String> = conn
+                .query_row(
+                    "SELECT data FROM credentials WHERE scope = ?1 AND user_id = ?2",
+                    params![scope, user_id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            Ok(data.map(|d| serde_json::from_str(&d)).transpose()?)
+        })
+        .await
+    }
+
+    async fn save_credentials(&self, creds: &UserCredentials) -> Result<()> {
+        let creds = creds.clone();
+        self.with_conn(move |conn, scope| {
+            let data = serde_json::to_string(&creds)?;
+            let now = Utc::now().to_rfc3339();
+
+            let previous: Option<bool> = conn
+                .query_row(
+                    "SELECT is_valid FROM credentials WHERE scope = ?1 AND user_id = ?2",
+                    params![scope, creds.user_id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+
+            let tx = conn.unchecked_transaction()?;
+            tx.execute(
+                "INSERT INTO credentials (scope, user_id, data, is_valid, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                 ON CONFLICT (scope, user_id) DO UPDATE SET
+                    data = excluded.data, is_valid = excluded.is_valid, updated_at = excluded.updated_at",
+                params![
+                    scope,
+                    creds.user_id,
+                    data,
+                    creds.is_valid,
+                    creds.created_at.to_rfc3339(),
+                    creds.updated_at.to_rfc3339()
+                ],
+            )?;
+            tx.execute(
+                "INSERT INTO credential_history (scope, user_id, data, is_valid, recorded_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![scope, creds.user_id, data, creds.is_valid, now],
+            )?;
+
+            // Validity transitions
+            let event = match (previous, creds.is_valid) {
+                (None, true) => Some("login"),
+                (Some(false), true) => Some("relogin"),
+                (Some(true), false) | (None, false) => Some("invalidated"),
+                _ => None,
+            };
+            if let Some(event) = event {
+                record_event(&tx, scope, &creds.user_id, event, creds.is_valid)?;
+            }
+            tx.commit()?;
+
+            info!("Saved credentials for user: {} to SQLite (scope: {})", creds.user_id, scope);
+            Ok(())
+        })
+        .await
+    }
+
+    async fn remove_credentials(&self, user_id: &str) -> Result<bool> {
+        let user_id = user_id.to_string();
+        self.with_conn(move |conn, scope| {
+            let removed = conn.execute(
+                "DELETE FROM credentials WHERE scope = ?1 AND user_id = ?2",
+                params![scope, user_id],
+            )?;
+            if removed > 0 {
+                // History is kept so past sessions remain queryable
+                record_event(conn, scope, &user_id, "removed", false)?;
+                info!("Removed credentials for user: {}", user_id);
+            }
+            Ok(removed > 0)
+        })
+        .await
+    }
+
+    async fn credential_history(&self, user_id: &str) -> Result<Vec<CredentialSnapshot>> {
+        let user_id = user_id.to_string();
+        self.with_conn(move |conn, scope| {
+            let mut stmt = conn.prepare(
+                "SELECT data, recorded_at FROM credential_history
+                 WHERE scope = ?1 AND user_id = ?2 ORDER BY id DESC",
+            )?;
+            let rows = stmt.query_map(params![scope, user_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?;
+
+            let mut result = Vec::new();
+            for row in rows {
+                let (data, recorded_at) = row?;
+                result.push(CredentialSnapshot {
+                    recorded_at: parse_time(&recorded_at),
+                    credentials: serde_json::from_str(&data)?,
+                });
+            }
+            Ok(result)
+        })
+        .await
+    }
+
+    async fn validity_events(&self, user_id: &str) -> Result<Vec<ValidityEvent>> {
+        let user_id = user_id.to_string();
+        self.with_conn(move |conn, scope| {
+            let mut stmt = conn.prepare(
+                "SELECT user_id, event, is_valid, at FROM validity_events
+                 WHERE scope = ?1 AND user_id = ?2 ORDER BY id DESC",
+            )?;
+            let rows = stmt.query_map(params![scope, user_id], |row| {
+                Ok(ValidityEvent {
+                    user_id: row.get(0)?,
+                    event: row.get(1)?,
+                    is_valid: row.get(2)?,
+                    at: parse_time(&row.get::<_, String>(3)?),
+                })
+            })?;
+            Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+        })
+        .await
+    }
+
+    async fn save_api_signature(&self, signature: &ApiSignature) -> Result<()> {
+        let signature = signature.clone();
+        self.with_conn(move |conn, scope| {
+            conn.execute(
+                "INSERT INTO api_signatures (scope, endpoint, data, is_valid, captured_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    scope,
+                    signature.endpoint,
+                    serde_json::to_string(&signature)?,
+                    signature.is_valid,
+                    signature.captured_at.to_rfc3339()
+                ],
+            )?;
+            // Every signed request is recorded; keep only the latest ones per endpoint
+            conn.execute(
+                "DELETE FROM api_signatures
+                 WHERE scope = ?1 AND endpoint = ?2 AND id NOT IN (
+                     SELECT id FROM api_signatures WHERE scope = ?1 AND endpoint = ?2
+                     ORDER BY id DESC LIMIT ?3
+                 )",
+                params![scope, signature.endpoint, SIGNATURES_PER_ENDPOINT],
+            )?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get_api_signature(&self, endpoint: &str) -> Result<Option<ApiSignature>> {
+        let endpoint = endpoint.to_string();
+        self.with_conn(move |conn, scope| {
+            let data: Option<String> = conn
+                .query_row(
+                    "SELECT data FROM api_signatures
+                     WHERE scope = ?1 AND endpoint = ?2 AND is_valid = 1
+                     ORDER BY id DESC LIMIT 1",
+                    params![scope, endpoint],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            Ok(data.map(|d| serde_json::from_str(&d)).transpose()?)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    struct TempDb(std::path::PathBuf);
+
+    impl Drop for TempDb {
+        fn drop(&mut self) {
+            for suffix in ["", "-wal", "-shm"] {
+                let _ = std::fs::remove_file(format!("{}{}", self.0.display(), suffix));
+            }
+        }
+    }
+
+    async fn open() -> (SqliteCredentialStorage, TempDb) {
+        let path = std::env::temp_dir().join(format!("xhs-test-{}.db", uuid::Uuid::new_v4()));
+        let storage = SqliteCredentialStorage::open(&path, "sessions".to_string()).await.unwrap();
+        (storage, TempDb(path))
+    }
+
+    fn creds(web_session: &str) -> UserCredentials {
+        let cookies = HashMap::from([
+            ("a1".to_string(), "a1-value".to_string()),
+            ("web_session".to_string(), web_session.to_string()),
+        ]);
+        UserCredentials::new("u1".to_string(), cookies, None)
+    }
+
+    fn signature(endpoint: &str, x_t: u64) -> ApiSignature {
+        ApiSignature {
+            endpoint: endpoint.to_string(),
+            x_s: format!("XYS_{}", x_t),
+            x_t: x_t.to_string(),
+            x_s_common: "common".to_string(),
+            x_b3_traceid: "b3".to_string(),
+            x_xray_traceid: "xray".to_string(),
+            method: Some("GET".to_string()),
+            post_body: None,
+            request_url: Some("/api/sns/web/v2/user/me".to_string()),
+            captured_at: Utc::now(),
+            is_valid: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn credentials_round_trip_with_history() {
+        let (storage, _db) = open().await;
+
+        storage.save_credentials(&creds("s1")).await.unwrap();
+        storage.save_credentials(&creds("s2")).await.unwrap();
+
+        let loaded = storage.get_credentials("u1").await.unwrap().unwrap();
+        assert_eq!(loaded.cookies["web_session"], "s2");
+        assert_eq!(storage.list_credentials().await.unwrap().len(), 1);
+
+        let history = storage.credential_history("u1").await.unwrap();
+        let sessions: Vec<_> = history.iter().map(|h| h.credentials.cookies["web_session"].as_str()).collect();
+        assert_eq!(sessions, ["s2", "s1"]);
+
+        storage.invalidate_user("u1").await.unwrap();
+        assert!(!storage.get_credentials("u1").await.unwrap().unwrap().is_valid);
+        assert!(!storage.validity_events("u1").await.unwrap().is_empty());
+
+        assert!(storage.remove_credentials("u1").await.unwrap());
+        assert!(storage.get_credentials("u1").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn signatures_round_trip_and_are_pruned() {
+        let (storage, _db) = open().await;
+        assert!(storage.get_api_signature("user_me").await.unwrap().is_none());
+
+        for x_t in 0..(SIGNATURES_PER_ENDPOINT as u64 + 5) {
+            storage.save_api_signature(&signature("user_me", x_t)).await.unwrap();
+        }
+        storage.save_api_signature(&signature("search_notes", 1)).await.unwrap();
+
+        let latest = storage.get_api_signature("user_me").await.unwrap().unwrap();
+        assert_eq!(latest.x_t, (SIGNATURES_PER_ENDPOINT as u64 + 4).to_string());
+        assert_eq!(latest.request_url.as_deref(), Some("/api/sns/web/v2/user/me"));
+
+        let rows: i64 = storage
+            .with_conn(|conn, scope| {
+                Ok(conn.query_row(
+                    "SELECT COUNT(*) FROM api_signatures WHERE scope = ?1 AND endpoint = 'user_me'",
+                    params![scope],
+                    |row| row.get(0),
+                )?)
+            })
+            .await
+            .unwrap();
+        assert_eq!(rows, SIGNATURES_PER_ENDPOINT);
+        assert!(storage.get_api_signature("search_notes").await.unwrap().is_some());
+    }
+}
@@ -1,95 +1,395 @@
-//! JSON file-based credential storage
+//! Credential storage
 //!
-//! Stores credentials in `cookie.json` in the project root directory.
+//! [`CredentialStore`] is the storage abstraction used by `AuthService`.
+//! Backends:
+//! - [`CredentialStorage`]: plaintext JSON, one file per account (`<dir>/<user_id>.json`)
+//! - [`super::encrypted::EncryptedCredentialStorage`]: AES-256-GCM, one file per account (`<dir>/<user_id>.enc`)
+//! - `SqliteCredentialStorage` (feature `sqlite`): embedded database with credential history,
+//!   validity transitions and `ApiSignature` records
+//!
+//! All file backends write atomically (temp file + rename) with 0600 permissions.
+//! A legacy single-account file (`cookie.json`, written by `scripts/login.py`)
+//! can be imported into its own slot with [`CredentialStore::import_file`]; backends other
+//! than plaintext JSON delete it after the import.
 
 use anyhow::Result;
-use std::path::PathBuf;
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
 use tracing::{info, warn};
 
-use super::credentials::UserCredentials;
+use super::credentials::{ApiSignature, CredentialSnapshot, UserCredentials, ValidityEvent};
+use super::encrypted::{load_key, EncryptedCredentialStorage};
+use crate::config::{get_storage_config, StorageBackend};
+
+// ============================================================================
+// Storage Trait
+// ============================================================================
+
+/// Credential storage backend
+#[async_trait]
+pub trait CredentialStore: Send + Sync {
+    /// Backend name (for logs)
+    fn name(&self) -> &'static str;
+
+    /// Load all stored credentials (valid and invalid)
+    async fn list_credentials(&self) -> Result<Vec<UserCredentials>>;
+
+    /// Get credentials for a specific account
+    async fn get_credentials(&self, user_id: &str) -> Result<Option<UserCredentials>>;
+
+    /// Save or update credentials in the account's own slot
+    async fn save_credentials(&self, creds: &UserCredentials) -> Result<()>;
+
+    /// Remove an account slot, returns whether it existed
+    async fn remove_credentials(&self, user_id: &str) -> Result<bool>;
+
+    /// Get the most recently updated valid credentials
+    async fn get_active_credentials(&self) -> Result<Option<UserCredentials>> {
+        let active = self
+            .list_credentials()
+            .await?
+            .into_iter()
+            .filter(|c| c.is_valid)
+            .max_by_key(|c| c.updated_at);
+
+        match &active {
+            Some(creds) => info!("Found active credentials for user: {}", creds.user_id),
+            None => info!("No active credentials in {} storage", self.name()),
+        }
+        Ok(active)
+    }
+
+    /// Invalidate credentials for a specific user
+    async fn invalidate_user(&self, user_id: &str) -> Result<()> {
+        if let Some(mut creds) = self.get_credentials(user_id).await? {
+            if creds.is_valid {
+                creds.invalidate();
+                self.save_credentials(&creds).await?;
+                warn!("Invalidated credentials for user: {}", user_id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Mark all credentials as invalid
+    async fn invalidate_all(&self) -> Result<()> {
+        for creds in self.list_credentials().await? {
+            self.invalidate_user(&creds.user_id).await?;
+        }
+        Ok(())
+    }
+
+    /// Past versions of an account's credentials, newest first
+    ///
+    /// File backends keep only the current version and return an empty list.
+    async fn credential_history(&self, _user_id: &str) -> Result<Vec<CredentialSnapshot>> {
+        Ok(Vec::new())
+    }
+
+    /// Validity transitions of an account, newest first (empty for file backends)
+    async fn validity_events(&self, _user_id: &str) -> Result<Vec<ValidityEvent>> {
+        Ok(Vec::new())
+    }
+
+    /// Whether `save_api_signature` stores anything (false for file backends)
+    fn records_signatures(&self) -> bool {
+        false
+    }
+
+    /// Store a captured API signature (ignored by file backends)
+    async fn save_api_signature(&self, _signature: &ApiSignature) -> Result<()> {
+        Ok(())
+    }
+
+    /// Latest valid API signature for an endpoint (None for file backends)
+    async fn get_api_signature(&self, _endpoint: &str) -> Result<Option<ApiSignature>> {
+        Ok(None)
+    }
+
+    /// Whether slots are stored as plaintext JSON
+    ///
+    /// Other backends delete a legacy file once it has been imported.
+    fn is_plaintext(&self) -> bool {
+        false
+    }
 
+    /// Import a legacy plaintext single-account file (e.g. `cookie.json`) into its own slot
+    ///
+    /// The slot is only overwritten when the file is newer than the stored copy.
+    /// Unless the backend [is plaintext](CredentialStore::is_plaintext), the file is
+    /// deleted afterwards so no plaintext copy of the session stays on disk.
+    /// Returns the imported user_id.
+    async fn import_file(&self, path: &Path) -> Result<Option<String>> {
+        if !path.exists() {
+            return Ok(None);
+        }
 
+        let creds = read_json_file(path).await?;
+        let is_newer = match self.get_credentials(&creds.user_id).await? {
+            Some(existing) => existing.updated_at < creds.updated_at,
+            None => true,
+        };
+        if is_newer {
+            self.save_credentials(&creds).await?;
+            info!("Imported credentials for user: {} from {}", creds.user_id, path.display());
+        }
 
-/// JSON file-based credential storage
+        if !self.is_plaintext() {
+            tokio::fs::remove_file(path).await?;
+            info!("Removed plaintext {} after importing it into {} storage", path.display(), self.name());
+        }
+
+        Ok(is_newer.then_some(creds.user_id))
+    }
+}
+
+/// Open the configured storage backend for a directory
+///
+/// Selected by `XHS_STORAGE` (see [`crate::config::StorageConfig`]).
+///
+/// File backends use `dir` directly; the SQLite backend stores every registry in
+/// one database and uses the directory name as the scope (e.g. `sessions-creator`).
+pub async fn open_store(dir: PathBuf) -> Result<Box<dyn CredentialStore>> {
+    let config = get_storage_config();
+    match config.backend() {
+        StorageBackend::Json => Ok(Box::new(CredentialStorage::new(dir).await?)),
+        StorageBackend::Encrypted => {
+            let key = load_key(config, &dir).await?;
+            Ok(Box::new(EncryptedCredentialStorage::new(dir, key).await?))
+        }
+        #[cfg(feature = "sqlite")]
+        StorageBackend::Sqlite => {
+            let scope = dir
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("sessions")
+                .to_string();
+            Ok(Box::new(super::sqlite::SqliteCredentialStorage::open(&config.sqlite_path, scope).await?))
+        }
+        #[cfg(not(feature = "sqlite"))]
+        StorageBackend::Sqlite => Err(anyhow::anyhow!(
+            "XHS_STORAGE=sqlite requires building with `--features sqlite`"
+        )),
+    }
+}
+
+// ============================================================================
+// Plaintext JSON Backend
+// ============================================================================
+
+/// JSON file-based credential storage (one file per account)
 pub struct CredentialStorage {
-    file_path: PathBuf,
+    dir: PathBuf,
 }
 
 impl CredentialStorage {
-    /// Create a new storage instance
-    pub async fn new(file_path: PathBuf) -> Result<Self> {
-        info!("Using JSON credential storage: {}", file_path.display());
-        Ok(Self { file_path })
-    }
-    
-    /// Get the currently active (valid) credentials
-    pub async fn get_active_credentials(&self) -> Result<Option<UserCredentials>> {
-        if !self.file_path.exists() {
-            info!("No cookie.json found");
-            return Ok(None);
+    /// Create a new storage instance, creating the directory if needed
+    pub async fn new(dir: PathBuf) -> Result<Self> {
+        create_private_dir(&dir).await?;
+        info!("Using JSON credential storage: {}", dir.display());
+        Ok(Self { dir })
+    }
+
+    /// Storage directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+#[async_trait]
+impl CredentialStore for CredentialStorage {
+    fn name(&self) -> &'static str {
+        "json"
+    }
+
+    fn is_plaintext(&self) -> bool {
+        true
+    }
+
+    async fn list_credentials(&self) -> Result<Vec<UserCredentials>> {
+        let mut result = Vec::new();
+
+        for path in list_files(&self.dir, "json").await? {
+            match read_json_file(&path).await {
+                Ok(creds) => result.push(creds),
+                Err(e) => warn!("Skipping unreadable credentials file {}: {}", path.display(), e),
+            }
         }
-        
-        let content = tokio::fs::read_to_string(&self.file_path).await?;
-        let creds: UserCredentials = serde_json::from_str(&content)?;
-        
-        if creds.is_valid {
-            info!("Found active credentials for user: {}", creds.user_id);
-            Ok(Some(creds))
-        } else {
-            info!("Found credentials but marked as invalid");
-            Ok(None)
+
+        Ok(result)
+    }
+
+    async fn get_credentials(&self, user_id: &str) -> Result<Option<UserCredentials>> {
+        let path = slot_path(&self.dir, user_id, "json");
+        if !path.exists() {
+            return Ok(None);
         }
+        Ok(Some(read_json_file(&path).await?))
     }
-    
-    /// Save or update credentials
-    pub async fn save_credentials(&self, creds: &UserCredentials) -> Result<()> {
+
+    async fn save_credentials(&self, creds: &UserCredentials) -> Result<()> {
+        let path = slot_path(&self.dir, &creds.user_id, "json");
         let content = serde_json::to_string_pretty(creds)?;
-        tokio::fs::write(&self.file_path, content).await?;
-        info!("Saved credentials for user: {} to {}", creds.user_id, self.file_path.display());
+        write_atomic(&path, content.as_bytes()).await?;
+        info!("Saved credentials for user: {} to {}", creds.user_id, path.display());
         Ok(())
     }
-    
-    /// Mark all credentials as invalid
-    pub async fn invalidate_all(&self) -> Result<()> {
-        if !self.file_path.exists() {
-            return Ok(());
-        }
-        
-        let content = tokio::fs::read_to_string(&self.file_path).await?;
-        let mut creds: UserCredentials = serde_json::from_str(&content)?;
-        
-        if creds.is_valid {
-            creds.invalidate();
-            let content = serde_json::to_string_pretty(&creds)?;
-            tokio::fs::write(&self.file_path, content).await?;
-            warn!("Invalidated credentials for user: {}", creds.user_id);
+
+    async fn remove_credentials(&self, user_id: &str) -> Result<bool> {
+        remove_slot(&slot_path(&self.dir, user_id, "json"), user_id).await
+    }
+}
+
+// ============================================================================
+// File Helpers (shared by file backends)
+// ============================================================================
+
+/// File path for an account slot
+///
+/// The user ID is percent-encoded, so distinct IDs never share a file and plain
+/// alphanumeric IDs (every real XHS ID) keep their existing file names.
+pub(crate) fn slot_path(dir: &Path, user_id: &str, extension: &str) -> PathBuf {
+    dir.join(format!("{}.{}", urlencoding::encode(user_id), extension))
+}
+
+/// List files with the given extension in a directory
+pub(crate) async fn list_files(dir: &Path, extension: &str) -> Result<Vec<PathBuf>> {
+    let mut result = Vec::new();
+    let mut entries = tokio::fs::read_dir(dir).await?;
+
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.extension().and_then(|e| e.to_str()) == Some(extension) {
+            result.push(path);
         }
-        
-        Ok(())
     }
-    
-    /// Invalidate credentials for a specific user (same as invalidate_all for single-user storage)
-    pub async fn invalidate_user(&self, user_id: &str) -> Result<()> {
-        if !self.file_path.exists() {
-            return Ok(());
+
+    Ok(result)
+}
+
+/// Read a plaintext credentials file
+pub(crate) async fn read_json_file(path: &Path) -> Result<UserCredentials> {
+    let content = tokio::fs::read_to_string(path).await?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+/// Remove a slot file, returns whether it existed
+pub(crate) async fn remove_slot(path: &Path, user_id: &str) -> Result<bool> {
+    if !path.exists() {
+        return Ok(false);
+    }
+    tokio::fs::remove_file(path).await?;
+    info!("Removed credentials for user: {}", user_id);
+    Ok(true)
+}
+
+/// Create the storage directory (0700 on unix)
+pub(crate) async fn create_private_dir(dir: &Path) -> Result<()> {
+    tokio::fs::create_dir_all(dir).await?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        tokio::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700)).await?;
+    }
+    Ok(())
+}
+
+/// Write a file atomically: temp file (0600) in the same directory, fsync, then rename
+pub(crate) async fn write_atomic(path: &Path, content: &[u8]) -> Result<()> {
+    use tokio::io::AsyncWriteExt;
+
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| anyhow::anyhow!("Invalid credentials path: {}", path.display()))?;
+    let tmp_path = path.with_file_name(format!(".{}.{}.tmp", file_name, uuid::Uuid::new_v4().simple()));
+
+    let mut options = tokio::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+
+    let result = async {
+        let mut file = options.open(&tmp_path).await?;
+        file.write_all(content).await?;
+        file.sync_all().await?;
+        drop(file);
+        tokio::fs::rename(&tmp_path, path).await?;
+        Ok::<_, anyhow::Error>(())
+    }
+    .await;
+
+    if result.is_err() {
+        let _ = tokio::fs::remove_file(&tmp_path).await;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn slot_names_are_distinct_and_reversible() {
+        let dir = Path::new("sessions");
+        assert_eq!(slot_path(dir, "664ec6ef0000000007004173", "json"), dir.join("664ec6ef0000000007004173.json"));
+
+        let ids = ["a/b", "a_b", "a?b", "a%2Fb", "../x"];
+        let paths: Vec<PathBuf> = ids.iter().map(|id| slot_path(dir, id, "json")).collect();
+        for (id, path) in ids.iter().zip(&paths) {
+            assert_eq!(path.parent(), Some(dir));
+            let stem = path.file_stem().unwrap().to_str().unwrap();
+            assert_eq!(urlencoding::decode(stem).unwrap(), *id);
         }
-        
-        let content = tokio::fs::read_to_string(&self.file_path).await?;
-        let mut creds: UserCredentials = serde_json::from_str(&content)?;
-        
-        if creds.user_id == user_id && creds.is_valid {
-            creds.invalidate();
-            let content = serde_json::to_string_pretty(&creds)?;
-            tokio::fs::write(&self.file_path, content).await?;
-            warn!("Invalidated credentials for user: {}", user_id);
+        let unique: std::collections::HashSet<_> = paths.iter().collect();
+        assert_eq!(unique.len(), ids.len());
+    }
+
+    #[tokio::test]
+    async fn similar_user_ids_keep_separate_slots() {
+        let dir = std::env::temp_dir().join(format!("xhs-test-{}", uuid::Uuid::new_v4()));
+        let storage = CredentialStorage::new(dir.clone()).await.unwrap();
+        for (user_id, session) in [("a/b", "s1"), ("a_b", "s2")] {
+            let cookies = HashMap::from([("web_session".to_string(), session.to_string())]);
+            storage.save_credentials(&UserCredentials::new(user_id.to_string(), cookies, None)).await.unwrap();
         }
-        
-        Ok(())
+
+        assert_eq!(storage.list_credentials().await.unwrap().len(), 2);
+        assert_eq!(storage.get_credentials("a/b").await.unwrap().unwrap().cookies["web_session"], "s1");
+        assert_eq!(storage.get_credentials("a_b").await.unwrap().unwrap().cookies["web_session"], "s2");
+
+        let _ = std::fs::remove_dir_all(&dir);
     }
-    
-    /// Get API signature for a specific endpoint (legacy, returns None for JSON storage)
-    pub async fn get_api_signature(&self, _endpoint: &str) -> Result<Option<super::credentials::ApiSignature>> {
-        // API signatures are not stored in JSON storage (they are generated on-demand via Agent)
-        Ok(None)
+
+    #[tokio::test]
+    async fn import_removes_legacy_file_only_for_non_plaintext_backends() {
+        let dir = std::env::temp_dir().join(format!("xhs-test-{}", uuid::Uuid::new_v4()));
+        let legacy = dir.join("cookie.json");
+        let write_legacy = || {
+            let cookies = HashMap::from([("web_session".to_string(), "s1".to_string())]);
+            let creds = UserCredentials::new("u1".to_string(), cookies, None);
+            std::fs::write(&legacy, serde_json::to_string(&creds).unwrap()).unwrap();
+        };
+
+        let plain = CredentialStorage::new(dir.join("plain")).await.unwrap();
+        write_legacy();
+        assert_eq!(plain.import_file(&legacy).await.unwrap().as_deref(), Some("u1"));
+        assert!(legacy.exists());
+
+        let encrypted = EncryptedCredentialStorage::new(dir.join("encrypted"), [7u8; 32]).await.unwrap();
+        assert_eq!(encrypted.import_file(&legacy).await.unwrap().as_deref(), Some("u1"));
+        assert!(!legacy.exists());
+        assert_eq!(encrypted.get_credentials("u1").await.unwrap().unwrap().cookies["web_session"], "s1");
+
+        // An older copy is not imported, but still removed
+        write_legacy();
+        let mut newer = encrypted.get_credentials("u1").await.unwrap().unwrap();
+        newer.updated_at += chrono::Duration::seconds(60);
+        encrypted.save_credentials(&newer).await.unwrap();
+        assert_eq!(encrypted.import_file(&legacy).await.unwrap(), None);
+        assert!(!legacy.exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
     }
 }
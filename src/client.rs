@@ -1,34 +1,329 @@
-use std::sync::Arc;
-use reqwest::{Client, cookie::Jar};
+use reqwest::Client;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, ACCEPT_LANGUAGE, ORIGIN, REFERER, SET_COOKIE, USER_AGENT};
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use utoipa::ToSchema;
 
+use crate::config::{get_client_profile, get_http_config};
+
+/// Shared HTTP client for XHS requests
+///
+/// Deliberately has no process-wide cookie jar: it would mix the cookies of different
+/// accounts. Each account's cookies live in its `UserCredentials`, which act as that
+/// account's jar: requests send them and `Set-Cookie` responses are merged back and
+/// persisted (see `AuthService::merge_set_cookies`).
 #[derive(Clone)]
 pub struct XhsClient {
     http_client: Client,
-    cookie_store: Arc<Jar>,
 }
 
 impl XhsClient {
     pub fn new() -> Result<Self> {
-        let cookie_store = Arc::new(Jar::default());
-        // Configure the client with a standard browser User-Agent
+        // Configure the client with the default profile's User-Agent and the configured timeouts;
+        // request builders override the fingerprint headers per account (see `ClientProfile`)
+        let config = get_http_config();
         let client = Client::builder()
-            .cookie_store(true)
-            .cookie_provider(cookie_store.clone())
-            .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
+            .user_agent(get_client_profile().user_agent_header())
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .connect_timeout(Duration::from_secs(config.connect_timeout_secs))
             .build()?;
 
-        Ok(Self {
-            http_client: client,
-            cookie_store,
-        })
+        Ok(Self { http_client: client })
     }
 
     pub fn get_client(&self) -> &Client {
         &self.http_client
     }
+}
+
+/// `Set-Cookie` header values of a response
+pub fn set_cookie_headers(headers: &HeaderMap) -> Vec<String> {
+    headers
+        .get_all(SET_COOKIE)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .map(str::to_string)
+        .collect()
+}
+
+// ============================================================================
+// Client Profile
+// ============================================================================
+
+/// Default User-Agent (Chrome 143 on Windows)
+pub const DEFAULT_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/143.0.0.0 Safari/537.36";
+
+/// XHS site a request is sent on behalf of (decides Origin / Referer)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Site {
+    /// www.xiaohongshu.com (edith API, login)
+    Web,
+    /// creator.xiaohongshu.com (galaxy API, creator login)
+    Creator,
+}
+
+impl Site {
+    pub fn origin(self) -> &'static str {
+        match self {
+            Self::Web => "https://www.xiaohongshu.com",
+            Self::Creator => "https://creator.xiaohongshu.com",
+        }
+    }
+
+    pub fn referer(self) -> &'static str {
+        match self {
+            Self::Web => "https://www.xiaohongshu.com/",
+            Self::Creator => "https://creator.xiaohongshu.com/",
+        }
+    }
+}
+
+/// Browser fingerprint presented to XHS
+///
+/// Owns every header that identifies the browser (User-Agent, `sec-ch-ua*`, Accept-Language)
+/// and the platform passed to the signer, so the signature and the headers always describe
+/// the same browser. The default comes from the `[profile]` config section; an account can
+/// carry its own profile in its credentials.
+///
+/// `sec_ch_ua` and `platform` are derived from `user_agent` unless set explicitly.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct ClientProfile {
+    /// User-Agent
+    pub user_agent: String,
+    /// `sec-ch-ua` brand list (derived from the Chrome / Edge version in `user_agent` when absent)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sec_ch_ua: Option<String>,
+    /// Platform for `sec-ch-ua-platform` and the signer, e.g. `Windows`, `macOS`
+    /// (derived from `user_agent` when absent)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+    /// `sec-ch-ua-mobile`
+    pub mobile: bool,
+    /// Accept-Language
+    pub accept_language: String,
+}
+
+impl Default for ClientProfile {
+    fn default() -> Self {
+        Self {
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            sec_ch_ua: None,
+            platform: None,
+            mobile: false,
+            accept_language: "zh-CN,zh;q=0.9".to_string(),
+        }
+    }
+}
+
+impl ClientProfile {
+    /// Major version after `token` in the User-Agent (e.g. `Chrome/`)
+    fn major_version(&self, token: &str) -> Option<&str> {
+        let version = self.user_agent.split(token).nth(1)?;
+        let major = version.split('.').next()?;
+        (!major.is_empty() && major.bytes().all(|b| b.is_ascii_digit())).then_some(major)
+    }
+
+    /// Major Chrome version in the User-Agent
+    fn chrome_version(&self) -> Option<&str> {
+        self.major_version("Chrome/")
+    }
+
+    /// `sec-ch-ua` value; None for browsers that do not send client hints
+    pub fn sec_ch_ua(&self) -> Option<String> {
+        if let Some(sec_ch_ua) = &self.sec_ch_ua {
+            return Some(sec_ch_ua.clone());
+        }
+        let chromium = self.chrome_version()?;
+        let (brand, version) = match self.major_version("Edg/") {
+            Some(edge) => ("Microsoft Edge", edge),
+            None => ("Google Chrome", chromium),
+        };
+        Some(format!(
+            r#""{}";v="{}", "Chromium";v="{}", "Not A(Brand";v="24""#,
+            brand, version, chromium
+        ))
+    }
+
+    /// Platform name
+    pub fn platform(&self) -> &str {
+        if let Some(platform) = &self.platform {
+            return platform;
+        }
+        let ua = self.user_agent.as_str();
+        if ua.contains("Windows") {
+            "Windows"
+        } else if ua.contains("Android") {
+            "Android"
+        } else if ua.contains("iPhone") || ua.contains("iPad") {
+            "iOS"
+        } else if ua.contains("Macintosh") || ua.contains("Mac OS X") {
+            "macOS"
+        } else if ua.contains("CrOS") {
+            "Chrome OS"
+        } else if ua.contains("Linux") {
+            "Linux"
+        } else {
+            "Windows"
+        }
+    }
+
+    /// Copy with `sec_ch_ua` and `platform` filled in, so a remote signer sees the same
+    /// values that go into the request headers
+    pub fn resolved(&self) -> Self {
+        Self {
+            sec_ch_ua: self.sec_ch_ua(),
+            platform: Some(self.platform().to_string()),
+            ..self.clone()
+        }
+    }
+
+    /// User-Agent header (falls back to the default if the configured value is not a valid header)
+    pub fn user_agent_header(&self) -> HeaderValue {
+        HeaderValue::from_str(&self.user_agent).unwrap_or_else(|_| HeaderValue::from_static(DEFAULT_USER_AGENT))
+    }
+
+    /// Fingerprint headers plus Accept / Origin / Referer for `site`
+    pub fn headers(&self, site: Site) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_static("application/json, text/plain, */*"));
+        if let Ok(value) = HeaderValue::from_str(&self.accept_language) {
+            headers.insert(ACCEPT_LANGUAGE, value);
+        }
+        if let Some(value) = self.sec_ch_ua().and_then(|v| HeaderValue::from_str(&v).ok()) {
+            headers.insert("sec-ch-ua", value);
+            headers.insert("sec-ch-ua-mobile", HeaderValue::from_static(if self.mobile { "?1" } else { "?0" }));
+            if let Ok(value) = HeaderValue::from_str(&format!("\"{}\"", self.platform())) {
+                headers.insert("sec-ch-ua-platform", value);
+            }
+        }
+        headers.insert(USER_AGENT, self.user_agent_header());
+        headers.insert(ORIGIN, HeaderValue::from_static(site.origin()));
+        headers.insert(REFERER, HeaderValue::from_static(site.referer()));
+        headers
+    }
+
+    /// Problems that would make the headers inconsistent or unsendable
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.user_agent.trim().is_empty() || HeaderValue::from_str(&self.user_agent).is_err() {
+            problems.push("user_agent is not a valid header value".to_string());
+        }
+        if HeaderValue::from_str(&self.accept_language).is_err() {
+            problems.push("accept_language is not a valid header value".to_string());
+        }
+        if let Some(sec_ch_ua) = &self.sec_ch_ua {
+            if HeaderValue::from_str(sec_ch_ua).is_err() {
+                problems.push("sec_ch_ua is not a valid header value".to_string());
+            }
+            if let Some(version) = self.chrome_version() {
+                if !sec_ch_ua.contains(&format!("v=\"{}\"", version)) {
+                    problems.push(format!("sec_ch_ua does not mention Chrome {} from user_agent", version));
+                }
+            }
+        }
+        if let Some(platform) = &self.platform {
+            if platform.trim().is_empty() || platform.contains('"') {
+                problems.push("platform must be a non-empty name without quotes".to_string());
+            }
+        }
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHROME_WINDOWS: &str =
+        "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/131.0.0.0 Safari/537.36";
+    const EDGE_MAC: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/130.0.0.0 Safari/537.36 Edg/130.0.2849.80";
+    const FIREFOX_LINUX: &str = "Mozilla/5.0 (X11; Linux x86_64; rv:133.0) Gecko/20100101 Firefox/133.0";
+    const SAFARI_IPHONE: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_5 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.5 Mobile/15E148 Safari/604.1";
+
+    fn profile(user_agent: &str) -> ClientProfile {
+        ClientProfile { user_agent: user_agent.to_string(), ..ClientProfile::default() }
+    }
+
+    #[test]
+    fn derives_sec_ch_ua_from_the_user_agent() {
+        assert_eq!(
+            profile(CHROME_WINDOWS).sec_ch_ua().unwrap(),
+            r#""Google Chrome";v="131", "Chromium";v="131", "Not A(Brand";v="24""#
+        );
+        assert_eq!(
+            profile(EDGE_MAC).sec_ch_ua().unwrap(),
+            r#""Microsoft Edge";v="130", "Chromium";v="130", "Not A(Brand";v="24""#
+        );
+        assert_eq!(profile(FIREFOX_LINUX).sec_ch_ua(), None);
+        assert_eq!(profile(SAFARI_IPHONE).sec_ch_ua(), None);
+        assert_eq!(profile("Mozilla/5.0 Chrome/abc").sec_ch_ua(), None);
+
+        let explicit = ClientProfile { sec_ch_ua: Some("custom".to_string()), ..profile(CHROME_WINDOWS) };
+        assert_eq!(explicit.sec_ch_ua().as_deref(), Some("custom"));
+    }
+
+    #[test]
+    fn non_chromium_profiles_send_no_client_hints() {
+        let headers = profile(FIREFOX_LINUX).headers(Site::Web);
+        assert_eq!(headers[USER_AGENT], FIREFOX_LINUX);
+        assert!(headers.get("sec-ch-ua").is_none());
+        assert!(headers.get("sec-ch-ua-platform").is_none());
+
+        let headers = profile(EDGE_MAC).headers(Site::Web);
+        assert_eq!(headers["sec-ch-ua-platform"], "\"macOS\"");
+        assert_eq!(headers["sec-ch-ua-mobile"], "?0");
+    }
+
+    #[test]
+    fn derives_platform_from_the_user_agent() {
+        assert_eq!(profile(CHROME_WINDOWS).platform(), "Windows");
+        assert_eq!(profile(EDGE_MAC).platform(), "macOS");
+        assert_eq!(profile(FIREFOX_LINUX).platform(), "Linux");
+        assert_eq!(profile(SAFARI_IPHONE).platform(), "iOS");
+        assert_eq!(profile("Mozilla/5.0 (Linux; Android 14; Pixel 8) Chrome/131.0.0.0 Mobile").platform(), "Android");
+        assert_eq!(profile("Mozilla/5.0 (X11; CrOS x86_64 14541.0.0) Chrome/131.0.0.0").platform(), "Chrome OS");
+        assert_eq!(profile("curl/8.0").platform(), "Windows");
+
+        let explicit = ClientProfile { platform: Some("Linux".to_string()), ..profile(CHROME_WINDOWS) };
+        assert_eq!(explicit.platform(), "Linux");
+    }
+
+    #[test]
+    fn resolved_fills_derived_fields_only() {
+        let resolved = profile(EDGE_MAC).resolved();
+        assert_eq!(resolved.platform.as_deref(), Some("macOS"));
+        assert_eq!(resolved.sec_ch_ua, profile(EDGE_MAC).sec_ch_ua());
+        assert_eq!(resolved.user_agent, EDGE_MAC);
+        // Resolving again changes nothing
+        assert_eq!(resolved.resolved(), resolved);
+
+        let resolved = profile(FIREFOX_LINUX).resolved();
+        assert_eq!(resolved.sec_ch_ua, None);
+        assert_eq!(resolved.platform.as_deref(), Some("Linux"));
+
+        let explicit = ClientProfile { platform: Some("Windows".to_string()), ..profile(FIREFOX_LINUX) };
+        assert_eq!(explicit.resolved().platform.as_deref(), Some("Windows"));
+    }
+
+    #[test]
+    fn reports_inconsistent_or_unsendable_profiles() {
+        assert!(ClientProfile::default().problems().is_empty());
+        assert!(profile(EDGE_MAC).problems().is_empty());
+        assert!(profile(FIREFOX_LINUX).resolved().problems().is_empty());
 
-    pub fn get_cookie_store(&self) -> Arc<Jar> {
-        self.cookie_store.clone()
+        let problems = |p: ClientProfile| p.problems().join("; ");
+        assert!(problems(profile("")).contains("user_agent"));
+        assert!(problems(profile("bad\nagent")).contains("user_agent"));
+        assert!(problems(ClientProfile { accept_language: "zh\r\n".to_string(), ..ClientProfile::default() })
+            .contains("accept_language"));
+        assert!(problems(ClientProfile { sec_ch_ua: Some(r#""Google Chrome";v="120""#.to_string()), ..profile(CHROME_WINDOWS) })
+            .contains("does not mention Chrome 131"));
+        assert!(problems(ClientProfile { sec_ch_ua: Some("bad\n".to_string()), ..profile(FIREFOX_LINUX) })
+            .contains("sec_ch_ua is not a valid header value"));
+        assert!(problems(ClientProfile { platform: Some(" ".to_string()), ..ClientProfile::default() }).contains("platform"));
+        assert!(problems(ClientProfile { platform: Some("\"macOS\"".to_string()), ..ClientProfile::default() })
+            .contains("platform"));
     }
 }
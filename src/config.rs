@@ -1,41 +1,895 @@
 //! 配置模块 (Configuration Module)
 //!
-//! 统一管理应用配置，支持环境变量覆盖
+//! 分层加载配置，后者覆盖前者：
+//! 1. 默认值
+//! 2. TOML 配置文件（`--config` / `XHS_CONFIG` 指定；缺省时读取当前目录的 `xhs.toml`，不存在则跳过）
+//! 3. 环境变量（`PORT`、`XHS_AGENT_URL`、`XHS_STORAGE` 等，见各字段说明）
+//! 4. 命令行参数（由 `main` 应用）
+//!
+//! 配置文件无法解析、环境变量的值无法识别时均视为配置错误，不会被忽略或退回默认值。
+//! 启动时由 `main` 调用 [`init`] 校验并安装；未安装时（作为库使用）首次访问按 1-3 层加载。
+//! 生效的配置可通过 `xhs-rs config show` 查看。
+
+use anyhow::{anyhow, bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::api::limiter::{BackoffPolicy, EndpointFamily, Rate};
+use crate::api::retry::RetryPolicy;
+use crate::client::ClientProfile;
+use crate::signature::FallbackPolicy;
+
+/// 默认配置文件
+pub const DEFAULT_CONFIG_FILE: &str = "xhs.toml";
+
+/// 应用配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub server: ServerConfig,
+    pub agent: AgentConfig,
+    pub signer: SignerConfig,
+    pub storage: StorageConfig,
+    pub http: HttpConfig,
+    /// 默认浏览器指纹（账号可在凭据中单独指定）
+    pub profile: ClientProfile,
+    pub download: DownloadConfig,
+    pub health: HealthConfig,
+    pub cache: CacheConfig,
+    pub limits: RateLimitConfig,
+    pub retry: RetryConfig,
+    /// 无法解析的环境变量，由 [`Config::validate`] 报告
+    #[serde(skip)]
+    env_errors: Vec<String>,
+}
+
+impl Config {
+    /// 按 默认值 → 配置文件 → 环境变量 加载
+    ///
+    /// `path` 为 None 时使用 `XHS_CONFIG`，其次当前目录的 `xhs.toml`（不存在则跳过）；
+    /// 显式指定的文件不存在或无法解析时返回错误
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        Self::load_with_env(path, |name| std::env::var(name).ok())
+    }
+
+    /// 同 [`Config::load`]，环境变量从 `vars` 读取
+    pub fn load_with_env(path: Option<&Path>, vars: impl Fn(&str) -> Option<String>) -> Result<Self> {
+        let mut env = EnvVars::new(&vars);
+        let explicit = path.map(Path::to_path_buf).or_else(|| env.get("XHS_CONFIG").map(PathBuf::from));
+        let path = explicit.clone().unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG_FILE));
+
+        let mut config = if explicit.is_some() || path.exists() {
+            let content = std::fs::read_to_string(&path)
+                .with_context(|| format!("Failed to read config file {}", path.display()))?;
+            let config: Self = toml::from_str(&content)
+                .with_context(|| format!("Invalid config file {}", path.display()))?;
+            tracing::info!("[Config] Loaded {}", path.display());
+            config
+        } else {
+            Self::default()
+        };
+
+        config.fill_defaults();
+        config.apply_env(&mut env);
+        config.env_errors = env.errors;
+        Ok(config)
+    }
+
+    /// 配置文件中部分列出的表与默认值合并（未列出的端点族 / 端点保持默认）
+    fn fill_defaults(&mut self) {
+        for (family, rate) in RateLimitConfig::default().rates {
+            self.limits.rates.entry(family).or_insert(rate);
+        }
+        for (endpoint, ttl) in CacheConfig::default().ttl {
+            self.cache.ttl.entry(endpoint).or_insert(ttl);
+        }
+    }
+
+    /// 应用环境变量覆盖，无法解析的值记录在 `env.errors` 中
+    fn apply_env(&mut self, env: &mut EnvVars) {
+        // Server
+        if let Some(port) = env.get("PORT").or_else(|| env.get("XHS_API_PORT")) {
+            env.parse_value("PORT", &port, &mut self.server.port);
+        }
+        env.parse_into("XHS_HOST", &mut self.server.host);
+
+        // Agent
+        if let Some(url) = env.get("XHS_AGENT_URL") {
+            self.agent.url = Some(url);
+        }
+        env.parse_into("XHS_AGENT_PORT", &mut self.agent.port);
+        env.parse_into("XHS_AGENT_PYTHON", &mut self.agent.python);
+        if env.is_set("SKIP_LOCAL_AGENT") {
+            self.agent.autostart = false;
+        }
+
+        // Signer: XHS_SIGNER=agent,native,fixture （逗号分隔，按顺序降级）
+        if let Some(chain) = env.get("XHS_SIGNER") {
+            let mut kinds = Vec::new();
+            for name in chain.split(',').filter(|s| !s.trim().is_empty()) {
+                match SignerKind::parse(name) {
+                    Some(kind) => kinds.push(kind),
+                    None => env.invalid("XHS_SIGNER", name.trim()),
+                }
+            }
+            self.signer.chain = kinds;
+        }
+        env.parse_into("XHS_SIGNATURE_FIXTURE", &mut self.signer.fixture_path);
+        env.parse_into("XHS_SIGNER_FALLBACK", &mut self.signer.fallback);
+
+        // Storage
+        if let Some(backend) = env.get("XHS_STORAGE") {
+            match StorageBackend::parse(&backend) {
+                Some(backend) => self.storage.backend = Some(backend),
+                None => env.invalid("XHS_STORAGE", &backend),
+            }
+        }
+        if let Some(key) = env.get("XHS_CREDENTIAL_KEY") {
+            self.storage.key = Some(key);
+        }
+        if let Some(keyfile) = env.get("XHS_CREDENTIAL_KEYFILE") {
+            self.storage.keyfile = Some(keyfile.into());
+        }
+        env.parse_into("XHS_SQLITE_PATH", &mut self.storage.sqlite_path);
+        env.parse_into("XHS_SESSIONS_DIR", &mut self.storage.sessions_dir);
+        env.parse_into("XHS_CREATOR_SESSIONS_DIR", &mut self.storage.creator_sessions_dir);
+        env.parse_into("XHS_NOTIFICATION_SYNC_DIR", &mut self.storage.notification_sync_dir);
+
+        // Profile
+        env.parse_into("XHS_USER_AGENT", &mut self.profile.user_agent);
+        env.parse_into("XHS_ACCEPT_LANGUAGE", &mut self.profile.accept_language);
+
+        // HTTP
+        env.parse_into("XHS_HTTP_TIMEOUT", &mut self.http.timeout_secs);
+        env.parse_into("XHS_HTTP_CONNECT_TIMEOUT", &mut self.http.connect_timeout_secs);
+
+        // Download
+        if let Some(root) = env.get("XHS_DOWNLOAD_ROOT") {
+            self.download.root = Some(root.into());
+        }
+
+        // Health
+        env.parse_into("XHS_HEALTH_INTERVAL", &mut self.health.interval_secs);
+        if let Some(url) = env.get("XHS_HEALTH_WEBHOOK") {
+            self.health.webhook_url = Some(url);
+        }
+
+        // Cache: XHS_CACHE_TTL=search_trending=60,note_detail=0 （0 表示不缓存该端点）
+        env.parse_into("XHS_CACHE_CAPACITY", &mut self.cache.capacity);
+        if let Some(dir) = env.get("XHS_CACHE_DIR") {
+            self.cache.dir = Some(dir.into());
+        }
+        for entry in env.get("XHS_CACHE_TTL").unwrap_or_default().split(',') {
+            if entry.trim().is_empty() {
+                continue;
+            }
+            match entry.split_once('=').map(|(k, v)| (k.trim(), v.trim().parse::<u64>())) {
+                Some((endpoint, Ok(ttl))) if !endpoint.is_empty() => {
+                    self.cache.ttl.insert(endpoint.to_string(), ttl);
+                }
+                _ => env.invalid("XHS_CACHE_TTL", entry.trim()),
+            }
+        }
+
+        // Limits: XHS_RATE_LIMIT=search=0.2:1,feed=2 （每秒请求数[:突发容量]，0 表示不限速）
+        for entry in env.get("XHS_RATE_LIMIT").unwrap_or_default().split(',') {
+            if entry.trim().is_empty() {
+                continue;
+            }
+            let parsed = entry.split_once('=').and_then(|(family, rate)| {
+                let family = EndpointFamily::parse(family)?;
+                let current = self.limits.rates.get(&family).copied()?;
+                let (per_second, burst) = match rate.split_once(':') {
+                    Some((per_second, burst)) => (per_second.trim().parse().ok()?, burst.trim().parse().ok()?),
+                    None => (rate.trim().parse().ok()?, current.burst),
+                };
+                Some((family, Rate { per_second, burst }))
+            });
+            match parsed {
+                Some((family, rate)) => {
+                    self.limits.rates.insert(family, rate);
+                }
+                None => env.invalid("XHS_RATE_LIMIT", entry.trim()),
+            }
+        }
+        env.parse_into("XHS_BACKOFF_BASE", &mut self.limits.backoff_base_secs);
+        env.parse_into("XHS_BACKOFF_MAX", &mut self.limits.backoff_max_secs);
+        env.parse_into("XHS_BREAKER_THRESHOLD", &mut self.limits.breaker_threshold);
+        env.parse_into("XHS_BREAKER_COOLDOWN", &mut self.limits.breaker_cooldown_secs);
+
+        // Retry
+        env.parse_into("XHS_RETRY_ATTEMPTS", &mut self.retry.attempts);
+        env.parse_into("XHS_RETRY_BACKOFF_MS", &mut self.retry.backoff_ms);
+        env.parse_into("XHS_RETRY_BACKOFF_MAX_MS", &mut self.retry.backoff_max_ms);
+    }
+
+    /// 校验配置，返回全部问题
+    pub fn validate(&self) -> Result<()> {
+        let mut errors = self.env_errors.clone();
+
+        if self.server.host.trim().is_empty() {
+            errors.push("server.host must not be empty".to_string());
+        }
+
+        if let Some(url) = &self.agent.url {
+            if !matches!(url::Url::parse(url), Ok(u) if u.scheme() == "http" || u.scheme() == "https") {
+                errors.push(format!("agent.url is not an http(s) URL: {}", url));
+            }
+        }
+        if self.agent.port == 0 {
+            errors.push("agent.port must not be 0".to_string());
+        }
+        if self.agent.autostart && !self.agent.is_container_mode() && self.agent.python.trim().is_empty() {
+            errors.push("agent.python must not be empty when agent.autostart is enabled".to_string());
+        }
+
+        if self.signer.chain.is_empty() {
+            errors.push("signer.chain must list at least one signer".to_string());
+        }
+        if self.signer.chain.contains(&SignerKind::Fixture) && self.signer.fixture_path.trim().is_empty() {
+            errors.push("signer.fixture_path is required by the fixture signer".to_string());
+        }
+
+        if self.storage.backend() == StorageBackend::Sqlite && !cfg!(feature = "sqlite") {
+            errors.push("storage.backend = \"sqlite\" requires building with `--features sqlite`".to_string());
+        }
+
+        errors.extend(self.profile.problems().into_iter().map(|p| format!("profile.{}", p)));
+        if self.http.timeout_secs == 0 {
+            errors.push("http.timeout_secs must be greater than 0".to_string());
+        }
+        if self.http.connect_timeout_secs == 0 {
+            errors.push("http.connect_timeout_secs must be greater than 0".to_string());
+        }
+
+        if let Some(url) = &self.health.webhook_url {
+            if url::Url::parse(url).is_err() {
+                errors.push(format!("health.webhook_url is not a valid URL: {}", url));
+            }
+        }
+
+        for (family, rate) in &self.limits.rates {
+            if !rate.per_second.is_finite() || rate.per_second < 0.0 {
+                errors.push(format!("limits.rates.{}.per_second must be >= 0", family.name()));
+            }
+            if rate.burst == 0 {
+                errors.push(format!("limits.rates.{}.burst must be at least 1", family.name()));
+            }
+        }
+        if self.limits.backoff_base_secs > self.limits.backoff_max_secs {
+            errors.push("limits.backoff_base_secs must not exceed limits.backoff_max_secs".to_string());
+        }
+        if self.limits.breaker_threshold == 0 {
+            errors.push("limits.breaker_threshold must be at least 1".to_string());
+        }
+
+        if self.retry.attempts == 0 {
+            errors.push("retry.attempts must be at least 1".to_string());
+        }
+        if self.retry.backoff_ms > self.retry.backoff_max_ms {
+            errors.push("retry.backoff_ms must not exceed retry.backoff_max_ms".to_string());
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            bail!("Invalid configuration:\n  - {}", errors.join("\n  - "))
+        }
+    }
+
+    /// 以 TOML 输出（密钥已隐藏），用于 `config show`
+    pub fn to_toml(&self) -> Result<String> {
+        let mut shown = self.clone();
+        if shown.storage.key.is_some() {
+            shown.storage.key = Some("********".to_string());
+        }
+        if let Some(url) = &shown.health.webhook_url {
+            shown.health.webhook_url = Some(mask_url(url));
+        }
+        Ok(toml::to_string_pretty(&shown)?)
+    }
+}
+
+// ============================================================================
+// Sections
+// ============================================================================
+
+/// HTTP 服务配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ServerConfig {
+    /// 监听地址，来自 XHS_HOST
+    pub host: String,
+    /// 监听端口，来自 PORT / XHS_API_PORT
+    pub port: u16,
+}
 
-use std::sync::LazyLock;
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self { host: "0.0.0.0".to_string(), port: 3000 }
+    }
+}
 
 /// Agent 配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct AgentConfig {
-    /// Agent 服务 URL
-    pub url: String,
-    /// 是否为容器模式（检测到 XHS_AGENT_URL 环境变量）
-    pub is_container_mode: bool,
+    /// 外部 Agent 服务 URL，来自 XHS_AGENT_URL；设置后为容器模式（不启动本地 Agent）
+    pub url: Option<String>,
+    /// 本地 Agent 端口，来自 XHS_AGENT_PORT
+    pub port: u16,
+    /// 启动本地 Agent 的 Python 解释器，来自 XHS_AGENT_PYTHON
+    pub python: String,
+    /// 是否随服务启动本地 Agent（设置 SKIP_LOCAL_AGENT 时为 false）
+    pub autostart: bool,
 }
 
-impl AgentConfig {
-    fn from_env() -> Self {
-        match std::env::var("XHS_AGENT_URL") {
-            Ok(url) => Self {
-                url,
-                is_container_mode: true,
-            },
-            Err(_) => Self {
-                url: "http://127.0.0.1:8765".to_string(),
-                is_container_mode: false,
-            },
+impl Default for AgentConfig {
+    fn default() -> Self {
+        Self {
+            url: None,
+            port: 8765,
+            python: "python".to_string(),
+            autostart: true,
         }
     }
 }
 
-/// 全局 Agent 配置实例
-pub static AGENT_CONFIG: LazyLock<AgentConfig> = LazyLock::new(AgentConfig::from_env);
+impl AgentConfig {
+    /// 是否为容器模式（配置了外部 Agent URL）
+    pub fn is_container_mode(&self) -> bool {
+        self.url.is_some()
+    }
+
+    /// Agent 服务 URL（未配置外部 URL 时为本地 Agent）
+    pub fn url(&self) -> String {
+        match &self.url {
+            Some(url) => url.trim_end_matches('/').to_string(),
+            None => format!("http://127.0.0.1:{}", self.port),
+        }
+    }
+}
 
 /// 获取 Agent URL
-pub fn get_agent_url() -> &'static str {
-    &AGENT_CONFIG.url
+pub fn get_agent_url() -> String {
+    get_config().agent.url()
 }
 
 /// 检查是否为容器模式
 pub fn is_container_mode() -> bool {
-    AGENT_CONFIG.is_container_mode
+    get_config().agent.is_container_mode()
+}
+
+/// 签名实现选择
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SignerKind {
+    /// 调用 Python Agent `/sign`
+    Agent,
+    /// 进程内纯 Rust 签名
+    Native,
+    /// 回放录制的签名文件
+    Fixture,
+}
+
+impl SignerKind {
+    fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "agent" => Some(Self::Agent),
+            "native" => Some(Self::Native),
+            "fixture" => Some(Self::Fixture),
+            _ => None,
+        }
+    }
+}
+
+/// 签名器配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SignerConfig {
+    /// 按顺序尝试的签名实现（第一个为主签名器，其余为降级），来自 XHS_SIGNER
+    pub chain: Vec<SignerKind>,
+    /// 签名回放文件路径，来自 XHS_SIGNATURE_FIXTURE
+    pub fixture_path: String,
+    /// 签名器失败时何时降级到链中的下一个，来自 XHS_SIGNER_FALLBACK
+    pub fallback: FallbackPolicy,
+}
+
+impl Default for SignerConfig {
+    fn default() -> Self {
+        Self {
+            chain: vec![SignerKind::Agent],
+            fixture_path: "tests/fixtures/agent_signatures.json".to_string(),
+            fallback: FallbackPolicy::default(),
+        }
+    }
+}
+
+/// 获取签名器链
+pub fn get_signer_chain() -> &'static [SignerKind] {
+    &get_config().signer.chain
+}
+
+/// 获取签名器降级策略
+pub fn get_signer_fallback() -> FallbackPolicy {
+    get_config().signer.fallback
+}
+
+/// 获取签名回放文件路径
+pub fn get_signature_fixture_path() -> &'static str {
+    &get_config().signer.fixture_path
+}
+
+/// 凭据存储后端
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// 明文 JSON（每个账号一个文件）
+    Json,
+    /// AES-256-GCM 加密（每个账号一个文件）
+    Encrypted,
+    /// 嵌入式 SQLite（需启用 `sqlite` feature），保留历史与签名记录
+    Sqlite,
+}
+
+impl StorageBackend {
+    fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "encrypted" => Some(Self::Encrypted),
+            "sqlite" => Some(Self::Sqlite),
+            _ => None,
+        }
+    }
+}
+
+/// 凭据存储配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StorageConfig {
+    /// 存储后端，来自 XHS_STORAGE；未指定时，配置了密钥即启用加密
+    pub backend: Option<StorageBackend>,
+    /// 加密密钥（32 字节，hex 或 base64），来自 XHS_CREDENTIAL_KEY
+    pub key: Option<String>,
+    /// 密钥文件路径，来自 XHS_CREDENTIAL_KEYFILE（不存在时自动生成，默认 .xhs-credential.key）
+    pub keyfile: Option<PathBuf>,
+    /// SQLite 数据库路径，来自 XHS_SQLITE_PATH
+    pub sqlite_path: PathBuf,
+    /// 主站会话目录，来自 XHS_SESSIONS_DIR
+    pub sessions_dir: PathBuf,
+    /// 创作者中心会话目录，来自 XHS_CREATOR_SESSIONS_DIR
+    pub creator_sessions_dir: PathBuf,
+    /// 通知增量同步的高水位目录，来自 XHS_NOTIFICATION_SYNC_DIR
+    pub notification_sync_dir: PathBuf,
+    /// 启动时导入的旧版单账号主站 Cookie 文件
+    pub legacy_cookie: PathBuf,
+    /// 启动时导入的旧版单账号创作者 Cookie 文件
+    pub legacy_creator_cookie: PathBuf,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            backend: None,
+            key: None,
+            keyfile: None,
+            sqlite_path: "xhs.db".into(),
+            sessions_dir: "sessions".into(),
+            creator_sessions_dir: "sessions-creator".into(),
+            notification_sync_dir: "notification-sync".into(),
+            legacy_cookie: "cookie.json".into(),
+            legacy_creator_cookie: "cookie-creator.json".into(),
+        }
+    }
+}
+
+impl StorageConfig {
+    /// 生效的存储后端
+    pub fn backend(&self) -> StorageBackend {
+        match self.backend {
+            Some(backend) => backend,
+            None if self.key.is_some() || self.keyfile.is_some() => StorageBackend::Encrypted,
+            None => StorageBackend::Json,
+        }
+    }
+
+    /// 生效的密钥文件路径
+    pub fn keyfile(&self) -> PathBuf {
+        self.keyfile.clone().unwrap_or_else(|| ".xhs-credential.key".into())
+    }
+}
+
+/// 获取凭据存储配置
+pub fn get_storage_config() -> &'static StorageConfig {
+    &get_config().storage
+}
+
+/// 上游 HTTP 客户端配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpConfig {
+    /// 请求超时（秒），来自 XHS_HTTP_TIMEOUT
+    pub timeout_secs: u64,
+    /// 连接超时（秒），来自 XHS_HTTP_CONNECT_TIMEOUT
+    pub connect_timeout_secs: u64,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            timeout_secs: 30,
+            connect_timeout_secs: 10,
+        }
+    }
+}
+
+/// 获取上游 HTTP 客户端配置
+pub fn get_http_config() -> &'static HttpConfig {
+    &get_config().http
+}
+
+/// 获取默认浏览器指纹（User-Agent 来自 XHS_USER_AGENT，Accept-Language 来自 XHS_ACCEPT_LANGUAGE）
+pub fn get_client_profile() -> &'static ClientProfile {
+    &get_config().profile
+}
+
+/// 媒体下载配置
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DownloadConfig {
+    /// 下载根目录，来自 XHS_DOWNLOAD_ROOT；设置后 `save_path` 相对于此目录解析，且不能超出此目录
+    pub root: Option<PathBuf>,
+}
+
+/// 获取媒体下载配置
+pub fn get_download_config() -> &'static DownloadConfig {
+    &get_config().download
+}
+
+/// 会话健康检查配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HealthConfig {
+    /// 检查间隔（秒），0 表示禁用后台检查，来自 XHS_HEALTH_INTERVAL
+    pub interval_secs: u64,
+    /// 会话失效时通知的 Webhook 地址，来自 XHS_HEALTH_WEBHOOK
+    pub webhook_url: Option<String>,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self { interval_secs: 1800, webhook_url: None }
+    }
+}
+
+/// 获取会话健康检查配置
+pub fn get_health_config() -> &'static HealthConfig {
+    &get_config().health
+}
+
+/// 响应缓存配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    /// 内存 LRU 容量（条），0 表示禁用缓存，来自 XHS_CACHE_CAPACITY
+    pub capacity: usize,
+    /// 磁盘缓存目录（可选），来自 XHS_CACHE_DIR
+    pub dir: Option<PathBuf>,
+    /// 各端点缓存时长（秒），0 或未列出的端点不缓存，来自 XHS_CACHE_TTL
+    pub ttl: BTreeMap<String, u64>,
+}
+
+/// 默认缓存的只读端点及缓存时长（秒）
+const DEFAULT_CACHE_TTLS: &[(&str, u64)] = &[
+    ("search_trending", 300),
+    ("search_recommend", 300),
+    ("search_filter", 600),
+    ("note_detail", 600),
+    ("user_otherinfo", 600),
+];
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 1000,
+            dir: None,
+            ttl: DEFAULT_CACHE_TTLS.iter().map(|(key, ttl)| (key.to_string(), *ttl)).collect(),
+        }
+    }
+}
+
+impl CacheConfig {
+    /// 需要缓存的端点及缓存时长（秒）
+    pub fn ttls(&self) -> HashMap<String, u64> {
+        self.ttl.iter().filter(|(_, ttl)| **ttl > 0).map(|(k, v)| (k.clone(), *v)).collect()
+    }
+}
+
+/// 获取响应缓存配置
+pub fn get_cache_config() -> &'static CacheConfig {
+    &get_config().cache
+}
+
+/// 客户端限速配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RateLimitConfig {
+    /// 各端点族的令牌桶速率，来自 XHS_RATE_LIMIT
+    pub rates: BTreeMap<EndpointFamily, Rate>,
+    /// 首次退避时长（秒），来自 XHS_BACKOFF_BASE
+    pub backoff_base_secs: u64,
+    /// 退避上限（秒），来自 XHS_BACKOFF_MAX
+    pub backoff_max_secs: u64,
+    /// 连续触发多少次后熔断，来自 XHS_BREAKER_THRESHOLD
+    pub breaker_threshold: u32,
+    /// 熔断冷却时长（秒），来自 XHS_BREAKER_COOLDOWN
+    pub breaker_cooldown_secs: u64,
+}
+
+/// 默认速率：(端点族, 每秒请求数, 突发容量)
+const DEFAULT_RATES: &[(EndpointFamily, f64, u32)] = &[
+    (EndpointFamily::Search, 0.5, 2),
+    (EndpointFamily::Feed, 1.0, 3),
+    (EndpointFamily::Note, 1.0, 3),
+    (EndpointFamily::Notification, 0.5, 3),
+    (EndpointFamily::User, 1.0, 3),
+    (EndpointFamily::Creator, 0.5, 2),
+    (EndpointFamily::Other, 2.0, 5),
+];
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            rates: DEFAULT_RATES
+                .iter()
+                .map(|(family, per_second, burst)| (*family, Rate { per_second: *per_second, burst: *burst }))
+                .collect(),
+            backoff_base_secs: 10,
+            backoff_max_secs: 300,
+            breaker_threshold: 3,
+            breaker_cooldown_secs: 900,
+        }
+    }
+}
+
+impl RateLimitConfig {
+    /// 各端点族的速率
+    pub fn rates(&self) -> HashMap<EndpointFamily, Rate> {
+        self.rates.iter().map(|(family, rate)| (*family, *rate)).collect()
+    }
+
+    /// 461 退避与熔断策略
+    pub fn policy(&self) -> BackoffPolicy {
+        BackoffPolicy {
+            base: Duration::from_secs(self.backoff_base_secs),
+            max: Duration::from_secs(self.backoff_max_secs),
+            threshold: self.breaker_threshold,
+            cooldown: Duration::from_secs(self.breaker_cooldown_secs),
+        }
+    }
+}
+
+/// 获取客户端限速配置
+pub fn get_rate_limit_config() -> &'static RateLimitConfig {
+    &get_config().limits
+}
+
+/// 重试配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetryConfig {
+    /// 最多尝试次数（含首次），1 表示不重试，来自 XHS_RETRY_ATTEMPTS
+    pub attempts: u32,
+    /// 首次重试间隔（毫秒），来自 XHS_RETRY_BACKOFF_MS
+    pub backoff_ms: u64,
+    /// 重试间隔上限（毫秒），来自 XHS_RETRY_BACKOFF_MAX_MS
+    pub backoff_max_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self { attempts: 3, backoff_ms: 500, backoff_max_ms: 5000 }
+    }
+}
+
+impl RetryConfig {
+    /// 重试策略
+    pub fn policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            attempts: self.attempts,
+            base: Duration::from_millis(self.backoff_ms),
+            max: Duration::from_millis(self.backoff_max_ms),
+        }
+    }
+}
+
+/// 获取重试配置
+pub fn get_retry_config() -> &'static RetryConfig {
+    &get_config().retry
+}
+
+// ============================================================================
+// Global Instance
+// ============================================================================
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+/// 校验并安装全局配置（启动时调用一次，须早于任何配置访问）
+pub fn init(config: Config) -> Result<()> {
+    config.validate()?;
+    CONFIG.set(config).map_err(|_| anyhow!("Configuration is already initialized"))
+}
+
+/// 全局配置；未调用 [`init`] 时按 默认值 → 配置文件 → 环境变量 加载
+///
+/// # Panics
+/// 未调用 [`init`] 且配置文件无法解析或配置校验失败时 panic（不会静默退回默认值）
+pub fn get_config() -> &'static Config {
+    CONFIG.get_or_init(|| {
+        Config::load(None)
+            .and_then(|config| config.validate().map(|_| config))
+            .unwrap_or_else(|e| panic!("[Config] {:#}", e))
+    })
+}
+
+/// 环境变量读取，记录无法解析的值
+struct EnvVars<'a> {
+    lookup: &'a dyn Fn(&str) -> Option<String>,
+    errors: Vec<String>,
+}
+
+impl<'a> EnvVars<'a> {
+    fn new(lookup: &'a dyn Fn(&str) -> Option<String>) -> Self {
+        Self { lookup, errors: Vec::new() }
+    }
+
+    /// 非空的环境变量
+    fn get(&self, name: &str) -> Option<String> {
+        (self.lookup)(name).map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+    }
+
+    /// 环境变量是否已设置（可以为空）
+    fn is_set(&self, name: &str) -> bool {
+        (self.lookup)(name).is_some()
+    }
+
+    /// 环境变量存在时解析并覆盖 `target`
+    fn parse_into<T: FromStr>(&mut self, name: &str, target: &mut T) {
+        if let Some(value) = self.get(name) {
+            self.parse_value(name, &value, target);
+        }
+    }
+
+    fn parse_value<T: FromStr>(&mut self, name: &str, value: &str, target: &mut T) {
+        match value.parse() {
+            Ok(parsed) => *target = parsed,
+            Err(_) => self.invalid(name, value),
+        }
+    }
+
+    fn invalid(&mut self, name: &str, value: &str) {
+        self.errors.push(format!("{} has an invalid value: '{}'", name, value));
+    }
+}
+
+/// 隐藏 URL 中的凭据、路径与查询参数（Webhook 地址中通常带有 token），只保留 scheme 与主机
+fn mask_url(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(url) if url.has_host() => format!("{}://{}/********", url.scheme(), url.host_str().unwrap_or_default()),
+        _ => "********".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 写入临时配置文件，离开作用域时删除
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(content: &str) -> Self {
+            let path = std::env::temp_dir().join(format!("xhs-config-{}.toml", uuid::Uuid::new_v4()));
+            std::fs::write(&path, content).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    fn load(file: Option<&TempFile>, vars: &[(&str, &str)]) -> Result<Config> {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        let default_file;
+        let file = match file {
+            Some(file) => file,
+            None => {
+                default_file = TempFile::new("");
+                &default_file
+            }
+        };
+        Config::load_with_env(Some(&file.0), |name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn env_overrides_file_overrides_defaults() {
+        let file = TempFile::new("[server]\nhost = \"127.0.0.1\"\nport = 4000\n\n[retry]\nattempts = 5\n");
+
+        let config = load(Some(&file), &[]).unwrap();
+        assert_eq!((config.server.host.as_str(), config.server.port), ("127.0.0.1", 4000));
+        assert_eq!(config.retry.attempts, 5);
+        assert_eq!(config.retry.backoff_ms, RetryConfig::default().backoff_ms);
+
+        let config = load(Some(&file), &[("PORT", "5000"), ("XHS_RETRY_ATTEMPTS", "2")]).unwrap();
+        assert_eq!((config.server.host.as_str(), config.server.port), ("127.0.0.1", 5000));
+        assert_eq!(config.retry.attempts, 2);
+        config.validate().unwrap();
+    }
+
+    #[test]
+    fn partial_tables_keep_default_entries() {
+        let file = TempFile::new("[limits.rates.search]\nper_second = 0.1\nburst = 1\n\n[cache.ttl]\nnote_detail = 0\n");
+        let config = load(Some(&file), &[("XHS_RATE_LIMIT", "feed=4")]).unwrap();
+
+        assert_eq!(config.limits.rates[&EndpointFamily::Search], Rate { per_second: 0.1, burst: 1 });
+        assert_eq!(config.limits.rates[&EndpointFamily::Feed], Rate { per_second: 4.0, burst: 3 });
+        assert_eq!(config.limits.rates.len(), DEFAULT_RATES.len());
+        assert!(!config.cache.ttls().contains_key("note_detail"));
+        assert_eq!(config.cache.ttls()["search_trending"], 300);
+    }
+
+    #[test]
+    fn invalid_env_values_fail_validation() {
+        let cases = [
+            ("XHS_SIGNER", "agent,bogus"),
+            ("XHS_STORAGE", "mongo"),
+            ("XHS_CACHE_TTL", "note_detail=abc"),
+            ("XHS_RATE_LIMIT", "search=fast"),
+            ("XHS_RATE_LIMIT", "unknown=1"),
+            ("PORT", "abc"),
+            ("XHS_SIGNER_FALLBACK", "sometimes"),
+        ];
+        for (name, value) in cases {
+            let config = load(None, &[(name, value)]).unwrap();
+            let error = config.validate().unwrap_err().to_string();
+            assert!(error.contains(name), "{}={} should be rejected, got: {}", name, value, error);
+        }
+
+        let config = load(None, &[("XHS_SIGNER", "native,fixture"), ("XHS_STORAGE", "encrypted")]).unwrap();
+        assert_eq!(config.signer.chain, [SignerKind::Native, SignerKind::Fixture]);
+        config.validate().unwrap();
+    }
+
+    #[test]
+    fn unparsable_file_is_an_error() {
+        let file = TempFile::new("[server]\nport = \"not a number\"\n");
+        assert!(load(Some(&file), &[]).is_err());
+
+        let file = TempFile::new("[server]\nunknown_field = 1\n");
+        assert!(load(Some(&file), &[]).is_err());
+    }
+
+    #[test]
+    fn to_toml_masks_secrets() {
+        let config = load(
+            None,
+            &[
+                ("XHS_CREDENTIAL_KEY", "00112233445566778899aabbccddeeff00112233445566778899aabbccddeeff"),
+                ("XHS_HEALTH_WEBHOOK", "https://hooks.example.com/services/T000/B000/secret-token?key=abc"),
+            ],
+        )
+        .unwrap();
+
+        let shown = config.to_toml().unwrap();
+        assert!(!shown.contains("00112233"));
+        assert!(!shown.contains("secret-token") && !shown.contains("key=abc"));
+        assert!(shown.contains("https://hooks.example.com/********"));
+    }
 }
@@ -0,0 +1,261 @@
+//! 错误类型模块 (Error Module)
+//!
+//! 所有 `api::*` 函数统一返回 [`XhsError`]，调用方可以按错误种类匹配，
+//! 而不需要解析错误信息文本。HTTP 层通过 `IntoResponse` 将其映射为
+//! 不同的状态码和机器可读的 `error` 字段。
+
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+
+/// API 调用结果
+pub type XhsResult<T> = std::result::Result<T, XhsError>;
+
+/// XHS 表示未登录 / 登录过期的业务码
+/// - `-100`: 登录已过期
+/// - `-101`: 无登录信息，或登录信息为空
+pub const LOGIN_REQUIRED_CODES: &[i32] = &[-100, -101];
+
+/// 业务码是否表示需要重新登录
+pub fn is_login_required_code(code: i32) -> bool {
+    LOGIN_REQUIRED_CODES.contains(&code)
+}
+
+/// XHS API 错误
+#[derive(Debug, thiserror::Error)]
+pub enum XhsError {
+    /// 请求参数无效
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+
+    /// 签名被拒绝 (HTTP 406)
+    #[error("XHS 签名被拒绝 (406): {0}")]
+    SignatureRejected(String),
+
+    /// 风控触发 (HTTP 461)
+    #[error("XHS 风控触发 (461): 请稍后重试或更换关键词。Response: {0}")]
+    RiskControl461(String),
+
+    /// 客户端限速熔断中 (连续触发风控后的冷却期)
+    #[error("Rate limited: {family} requests paused after repeated risk control, retry after {retry_after}s")]
+    RateLimited { family: String, retry_after: u64 },
+
+    /// 未登录或没有可用的凭据
+    #[error("Not logged in. Please complete QR login via /api/auth/qrcode/create first.")]
+    NotLoggedIn,
+
+    /// 指定的账号不存在或会话已失效 (X-Xhs-Account)
+    #[error("No valid session for account: {0}")]
+    AccountNotFound(String),
+
+    /// 会话已失效：XHS 返回未登录 / 登录过期，凭据已被标记为无效，需重新扫码登录
+    #[error("Session expired for account {account} ({code}): {msg}. Please log in again.")]
+    SessionExpired { account: String, code: i32, msg: String },
+
+    /// 签名 Agent 不可用
+    #[error("Signature agent unavailable: {0}")]
+    AgentUnavailable(String),
+
+    /// 签名器无法为该请求生成签名 (例如回放文件中没有匹配的记录)
+    #[error("Signer unavailable: {0}")]
+    SignerUnavailable(String),
+
+    /// XHS 返回的其他错误 (HTTP 状态码或业务码)
+    #[error("XHS API 错误 ({code}): {msg}")]
+    Upstream { code: i32, msg: String },
+
+    /// 响应体无法解析
+    #[error("Failed to decode response: {reason} - Body: {body}")]
+    Decode { reason: String, body: String },
+
+    /// 网络传输错误
+    #[error("Transport error: {0}")]
+    Transport(#[from] reqwest::Error),
+
+    /// 内部错误 (存储、IO 等)
+    #[error(transparent)]
+    Internal(#[from] anyhow::Error),
+}
+
+impl XhsError {
+    /// 机器可读的错误种类
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::InvalidInput(_) => "invalid_input",
+            Self::SignatureRejected(_) => "signature_rejected",
+            Self::RiskControl461(_) => "risk_control",
+            Self::RateLimited { .. } => "rate_limited",
+            Self::NotLoggedIn => "not_logged_in",
+            Self::AccountNotFound(_) => "account_not_found",
+            Self::SessionExpired { .. } => "session_expired",
+            Self::AgentUnavailable(_) => "agent_unavailable",
+            Self::SignerUnavailable(_) => "signer_unavailable",
+            Self::Upstream { .. } => "upstream",
+            Self::Decode { .. } => "decode",
+            Self::Transport(_) => "transport",
+            Self::Internal(_) => "internal",
+        }
+    }
+
+    /// 对应的 HTTP 状态码
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            Self::InvalidInput(_) => StatusCode::BAD_REQUEST,
+            Self::NotLoggedIn | Self::SessionExpired { .. } => StatusCode::UNAUTHORIZED,
+            Self::AccountNotFound(_) => StatusCode::NOT_FOUND,
+            Self::RiskControl461(_) | Self::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            Self::AgentUnavailable(_) | Self::SignerUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+            Self::Transport(e) if e.is_timeout() => StatusCode::GATEWAY_TIMEOUT,
+            Self::SignatureRejected(_)
+            | Self::Upstream { .. }
+            | Self::Decode { .. }
+            | Self::Transport(_) => StatusCode::BAD_GATEWAY,
+            Self::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// 构造解析错误，保留原始响应体
+    pub fn decode(reason: impl std::fmt::Display, body: &str) -> Self {
+        Self::Decode {
+            reason: reason.to_string(),
+            body: body.to_string(),
+        }
+    }
+}
+
+impl From<serde_json::Error> for XhsError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::decode(e, "")
+    }
+}
+
+impl From<reqwest::header::InvalidHeaderValue> for XhsError {
+    fn from(e: reqwest::header::InvalidHeaderValue) -> Self {
+        Self::Internal(anyhow::anyhow!("Invalid header value: {}", e))
+    }
+}
+
+impl From<std::io::Error> for XhsError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Internal(e.into())
+    }
+}
+
+impl IntoResponse for XhsError {
+    fn into_response(self) -> Response {
+        let status = self.status_code();
+        let body = Json(serde_json::json!({
+            "code": -1,
+            "success": false,
+            "error": self.kind(),
+            "msg": self.to_string(),
+            "data": null
+        }));
+        let mut response = (status, body).into_response();
+        if let Self::RateLimited { retry_after, .. } = self {
+            response.headers_mut().insert(axum::http::header::RETRY_AFTER, retry_after.into());
+        }
+        response
+    }
+}
+
+/// 解析 JSON 响应，失败时保留响应体
+pub fn decode<T: serde::de::DeserializeOwned>(text: &str) -> XhsResult<T> {
+    serde_json::from_str(text).map_err(|e| XhsError::decode(e, text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn transport_error() -> reqwest::Error {
+        reqwest::Client::new().get("not a url").build().unwrap_err()
+    }
+
+    async fn body(response: Response) -> serde_json::Value {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[test]
+    fn maps_each_variant_to_kind_and_status() {
+        let cases = [
+            (XhsError::InvalidInput("x".into()), "invalid_input", StatusCode::BAD_REQUEST),
+            (XhsError::SignatureRejected("x".into()), "signature_rejected", StatusCode::BAD_GATEWAY),
+            (XhsError::RiskControl461("x".into()), "risk_control", StatusCode::TOO_MANY_REQUESTS),
+            (
+                XhsError::RateLimited { family: "search".into(), retry_after: 30 },
+                "rate_limited",
+                StatusCode::TOO_MANY_REQUESTS,
+            ),
+            (XhsError::NotLoggedIn, "not_logged_in", StatusCode::UNAUTHORIZED),
+            (XhsError::AccountNotFound("u1".into()), "account_not_found", StatusCode::NOT_FOUND),
+            (
+                XhsError::SessionExpired { account: "u1".into(), code: -100, msg: "登录已过期".into() },
+                "session_expired",
+                StatusCode::UNAUTHORIZED,
+            ),
+            (XhsError::AgentUnavailable("x".into()), "agent_unavailable", StatusCode::SERVICE_UNAVAILABLE),
+            (XhsError::SignerUnavailable("x".into()), "signer_unavailable", StatusCode::SERVICE_UNAVAILABLE),
+            (XhsError::Upstream { code: 500, msg: "x".into() }, "upstream", StatusCode::BAD_GATEWAY),
+            (XhsError::decode("bad json", "{"), "decode", StatusCode::BAD_GATEWAY),
+            (XhsError::Transport(transport_error()), "transport", StatusCode::BAD_GATEWAY),
+            (XhsError::Internal(anyhow::anyhow!("disk full")), "internal", StatusCode::INTERNAL_SERVER_ERROR),
+        ];
+
+        for (error, kind, status) in cases {
+            assert_eq!(error.kind(), kind, "{:?}", error);
+            assert_eq!(error.status_code(), status, "{:?}", error);
+        }
+    }
+
+    #[tokio::test]
+    async fn transport_timeout_maps_to_gateway_timeout() {
+        // Accepts the connection but never answers
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let _server = tokio::spawn(async move {
+            let (_socket, _) = listener.accept().await.unwrap();
+            tokio::time::sleep(Duration::from_secs(5)).await;
+        });
+
+        let error = reqwest::Client::new()
+            .get(format!("http://{}/", addr))
+            .timeout(Duration::from_millis(100))
+            .send()
+            .await
+            .unwrap_err();
+        assert_eq!(XhsError::Transport(error).status_code(), StatusCode::GATEWAY_TIMEOUT);
+    }
+
+    #[tokio::test]
+    async fn session_expired_response_body() {
+        let error = XhsError::SessionExpired { account: "u1".into(), code: -101, msg: "无登录信息".into() };
+        let response = error.into_response();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(response.headers().get(axum::http::header::RETRY_AFTER).is_none());
+
+        let body = body(response).await;
+        assert_eq!(body["code"], -1);
+        assert_eq!(body["success"], false);
+        assert_eq!(body["error"], "session_expired");
+        assert!(body["msg"].as_str().unwrap().contains("u1"));
+        assert!(body["data"].is_null());
+    }
+
+    #[tokio::test]
+    async fn rate_limited_response_sets_retry_after() {
+        let response = XhsError::RateLimited { family: "search".into(), retry_after: 42 }.into_response();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[axum::http::header::RETRY_AFTER], "42");
+
+        let body = body(response).await;
+        assert_eq!(body["error"], "rate_limited");
+        assert_eq!(body["success"], false);
+        assert!(body["msg"].as_str().unwrap().contains("retry after 42s"));
+        assert!(body["data"].is_null());
+    }
+}
@@ -83,7 +83,7 @@ pub async fn create_qrcode_handler(
         guard.clone()
     };
     
-    let cookies = match cookies {
+    let mut cookies = match cookies {
         Some(c) => c,
         None => {
             return Json(CreateQrCodeResponse {
@@ -96,7 +96,11 @@ pub async fn create_qrcode_handler(
         }
     };
     
-    match api::login::create_qrcode(&cookies).await {
+    let result = api::login::create_qrcode(&mut cookies).await;
+    // Keep cookies refreshed by the response for the following status polls
+    *state.guest_cookies.write().await = Some(cookies);
+
+    match result {
         Ok(resp) => {
             if resp.success {
                 if let Some(data) = resp.data {
@@ -208,6 +212,13 @@ pub async fn poll_qrcode_status_handler(
                 .unwrap_or(-1);
             
             let login_info = resp.data.as_ref().and_then(|d| d.login_info.clone());
+
+            // While waiting for the scan, keep the guest cookies refreshed by the response
+            if code_status != 2 {
+                if let (Some(new_c), Some(guest)) = (&new_cookies, state.guest_cookies.write().await.as_mut()) {
+                    guest.extend(new_c.clone());
+                }
+            }
             
             // If login success, use FULL synced cookies (NOT merged with guest cookies)
             // This prevents 461 errors caused by mixing guest and user cookies
@@ -4,9 +4,12 @@
 
 use axum::{Json, response::IntoResponse, extract::State};
 use std::sync::Arc;
+use crate::error::{is_login_required_code, XhsError};
 use crate::server::AppState;
+use crate::handlers::session::AccountSelector;
 use crate::api::creator::{auth, models::{CreatorQrcodeCreateRequest, CreatorQrcodeStatusRequest}};
 use crate::api::login::{GuestInitResponse, CreateQrCodeResponse};
+use crate::api::limiter::EndpointFamily;
 
 /// 1. 初始化创作者访客会话
 ///
@@ -97,7 +100,7 @@ pub async fn creator_check_qrcode_status(
     match auth::check_creator_qrcode_status(&payload.qr_id, &payload.cookies).await {
         Ok((mut json, new_cookies)) => {
             if let Some(nc) = new_cookies {
-                // Save credentials to cookie-creator.json
+                // Save credentials into the account's own slot
                 let user_id = json.get("data")
                     .and_then(|d| d.get("user_id"))
                     .and_then(|u| u.as_str())
@@ -139,38 +142,36 @@ use crate::api::creator::{info, models::{CreatorUserInfo, CreatorHomeInfo}};
     get,
     path = "/api/galaxy/user/info",
     tag = "Creator",
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "创作者账号 user_id，缺省为默认账号")
+    ),
     responses(
         (status = 200, description = "User info retrieved", body = CreatorUserInfo)
     )
 )]
 pub async fn creator_user_info_handler(
     State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
 ) -> impl IntoResponse {
     // 1. Get credentials from creator_auth
-    let cookies_result = state.creator_auth.try_get_credentials().await;
+    let cookies_result = state.creator_auth.try_get_credentials_for(account.as_deref()).await;
     
-    let cookies = match cookies_result {
-        Ok(Some(creds)) => creds.cookies.clone(),
-        Ok(None) => return Json(serde_json::json!({
-            "success": false,
-            "error": "Not logged in (Creator). Please login first."
-        })).into_response(),
-        Err(e) => return Json(serde_json::json!({
-            "success": false,
-            "error": e.to_string()
-        })).into_response(),
+    let creds = match cookies_result {
+        Ok(Some(creds)) => creds,
+        Ok(None) => return match account {
+            Some(account) => XhsError::AccountNotFound(account),
+            None => XhsError::NotLoggedIn,
+        }.into_response(),
+        Err(e) => return XhsError::from(e).into_response(),
     };
     
     // 2. Call API
-    match info::get_creator_user_info(&cookies).await {
+    match state.api.limiter().run(EndpointFamily::Creator, info::get_creator_user_info(&state.creator_auth, &creds)).await {
         Ok(info) => Json(serde_json::json!({
             "success": true,
             "data": info
         })).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "success": false, 
-            "error": e.to_string()
-        })).into_response(),
+        Err(e) => expire_on_login_required(&state, &creds.user_id, e).await.into_response(),
     }
 }
 
@@ -181,37 +182,49 @@ pub async fn creator_user_info_handler(
     get,
     path = "/api/galaxy/creator/home/personal_info",
     tag = "Creator",
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "创作者账号 user_id，缺省为默认账号")
+    ),
     responses(
         (status = 200, description = "Home info retrieved", body = CreatorHomeInfo)
     )
 )]
 pub async fn creator_home_info_handler(
     State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
 ) -> impl IntoResponse {
     // 1. Get credentials from creator_auth
-    let cookies_result = state.creator_auth.try_get_credentials().await;
+    let cookies_result = state.creator_auth.try_get_credentials_for(account.as_deref()).await;
     
-    let cookies = match cookies_result {
-        Ok(Some(creds)) => creds.cookies.clone(),
-        Ok(None) => return Json(serde_json::json!({
-            "success": false,
-            "error": "Not logged in (Creator). Please login first."
-        })).into_response(),
-        Err(e) => return Json(serde_json::json!({
-            "success": false,
-            "error": e.to_string()
-        })).into_response(),
+    let creds = match cookies_result {
+        Ok(Some(creds)) => creds,
+        Ok(None) => return match account {
+            Some(account) => XhsError::AccountNotFound(account),
+            None => XhsError::NotLoggedIn,
+        }.into_response(),
+        Err(e) => return XhsError::from(e).into_response(),
     };
     
     // 2. Call API
-    match info::get_creator_home_info(&cookies).await {
+    match state.api.limiter().run(EndpointFamily::Creator, info::get_creator_home_info(&state.creator_auth, &creds)).await {
         Ok(info) => Json(serde_json::json!({
             "success": true,
             "data": info
         })).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "success": false, 
-            "error": e.to_string()
-        })).into_response(),
+        Err(e) => expire_on_login_required(&state, &creds.user_id, e).await.into_response(),
+    }
+}
+
+/// 创作者接口返回未登录 / 登录过期业务码时，将会话标记为失效
+async fn expire_on_login_required(state: &AppState, user_id: &str, err: XhsError) -> XhsError {
+    match err {
+        XhsError::Upstream { code, msg } if code == 401 || is_login_required_code(code) => {
+            tracing::warn!("[Creator] Session {} reported login required ({}), invalidating", user_id, code);
+            if let Err(e) = state.creator_auth.invalidate_account(user_id).await {
+                tracing::warn!("[Creator] Failed to invalidate session {}: {}", user_id, e);
+            }
+            XhsError::SessionExpired { account: user_id.to_string(), code, msg }
+        }
+        err => err,
     }
 }
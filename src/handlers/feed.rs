@@ -11,6 +11,7 @@ use std::sync::Arc;
 
 use crate::api;
 use crate::server::AppState;
+use crate::handlers::session::AccountSelector;
 
 
 // ============================================================================
@@ -23,14 +24,10 @@ use crate::server::AppState;
 /// 获取小红书主页推荐内容流
 pub async fn homefeed_recommend_handler(
     State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
 ) -> impl IntoResponse {
-    match api::feed::recommend::get_homefeed_recommend(&state.api).await {
+    match api::feed::recommend::get_homefeed_recommend(&state.api.with_account(account)).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
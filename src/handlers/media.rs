@@ -11,6 +11,7 @@ use std::sync::Arc;
 
 use crate::api::media;
 use crate::server::AppState;
+use crate::handlers::session::AccountSelector;
 
 // ============================================================================
 // Handlers
@@ -26,6 +27,9 @@ use crate::server::AppState;
     summary = "视频地址解析",
     description = "从视频笔记中提取所有画质的视频下载 URL，返回 CDN 直链",
     request_body = media::video::VideoRequest,
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号")
+    ),
     responses(
         (status = 200, description = "视频地址列表", body = media::video::VideoResponse),
         (status = 500, description = "请求失败")
@@ -33,15 +37,12 @@ use crate::server::AppState;
 )]
 pub async fn video_handler(
     State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
     Json(req): Json<media::video::VideoRequest>,
 ) -> impl IntoResponse {
-    match media::video::get_video_urls(&state.api, req).await {
+    match media::video::get_video_urls(&state.api.with_account(account), req).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
@@ -56,6 +57,9 @@ pub async fn video_handler(
     summary = "图片地址解析",
     description = "从图文笔记中提取所有图片的下载 URL。返回两个版本：url_watermark (有水印) 和 url_original (无水印)",
     request_body = media::images::ImagesRequest,
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号")
+    ),
     responses(
         (status = 200, description = "图片地址列表", body = media::images::ImagesResponse),
         (status = 500, description = "请求失败")
@@ -63,15 +67,12 @@ pub async fn video_handler(
 )]
 pub async fn images_handler(
     State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
     Json(req): Json<media::images::ImagesRequest>,
 ) -> impl IntoResponse {
-    match media::images::get_image_urls(&state.api, req).await {
+    match media::images::get_image_urls(&state.api.with_account(account), req).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
@@ -95,10 +96,6 @@ pub async fn download_handler(
 ) -> impl IntoResponse {
     match media::download::download_media(req).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
@@ -9,6 +9,9 @@ pub mod user;
 pub mod feed;
 pub mod media;
 pub mod creator;
+pub mod session;
+pub mod stream;
+pub mod status;
 
 // Re-export all handlers for convenient access
 pub use search::*;
@@ -18,3 +21,6 @@ pub use user::*;
 pub use feed::*;
 pub use media::*;
 pub use creator::*;
+pub use session::*;
+pub use stream::*;
+pub use status::*;
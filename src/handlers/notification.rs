@@ -1,16 +1,45 @@
 //! Notification HTTP Handlers
 //! 
-//! Handles: mentions, connections, likes
+//! Handles: mentions, connections, likes, unread count, incremental sync
 
 use axum::{
-    extract::State,
+    extract::{Query, State},
     response::IntoResponse,
     Json,
 };
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
 use crate::api;
+use crate::models::notification::NotificationType;
+use crate::notification_sync::SyncResult;
 use crate::server::AppState;
+use crate::handlers::session::AccountSelector;
+
+// ============================================================================
+// Request / Response Structs
+// ============================================================================
+
+/// 增量同步请求参数
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct SyncParams {
+    /// 通知类型: likes / mentions / connections
+    #[serde(rename = "type")]
+    #[param(rename = "type", inline)]
+    pub notification_type: NotificationType,
+    /// 最多请求页数 (默认 5，首次同步时限制返回的历史数量)
+    #[serde(default = "default_sync_max_pages")]
+    pub max_pages: usize,
+}
+
+fn default_sync_max_pages() -> usize { 5 }
+
+/// 增量同步响应
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct SyncResponse {
+    pub success: bool,
+    pub data: SyncResult,
+}
 
 // ============================================================================
 // Handlers
@@ -25,6 +54,7 @@ use crate::server::AppState;
     tag = "xhs",
     summary = "通知页-评论和@",
     params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
         ("num" = Option<i32>, Query, description = "每页数量，固定为 20", example = 20),
         ("cursor" = Option<String>, Query, description = "分页游标，首次请求为空，后续使用响应中的 cursor/strCursor 值", example = "")
     ),
@@ -34,16 +64,12 @@ use crate::server::AppState;
 )]
 pub async fn mentions_handler(
     State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
     axum::extract::Query(params): axum::extract::Query<api::notification::mentions::MentionsParams>,
 ) -> impl IntoResponse {
-    match api::notification::mentions::get_mentions_with_params(&state.api, params).await {
+    match api::notification::mentions::get_mentions_with_params(&state.api.with_account(account), params).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
@@ -56,6 +82,7 @@ pub async fn mentions_handler(
     tag = "xhs",
     summary = "通知页-新增关注",
     params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
         ("num" = Option<i32>, Query, description = "每页数量，固定为 20", example = 20),
         ("cursor" = Option<String>, Query, description = "分页游标，首次请求为空，后续使用响应中的 cursor/strCursor 值", example = "")
     ),
@@ -65,16 +92,12 @@ pub async fn mentions_handler(
 )]
 pub async fn connections_handler(
     State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
     axum::extract::Query(params): axum::extract::Query<api::notification::connections::ConnectionsParams>,
 ) -> impl IntoResponse {
-    match api::notification::connections::get_connections_with_params(&state.api, params).await {
+    match api::notification::connections::get_connections_with_params(&state.api.with_account(account), params).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
@@ -87,6 +110,7 @@ pub async fn connections_handler(
     tag = "xhs",
     summary = "通知页-赞和收藏",
     params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
         ("num" = Option<i32>, Query, description = "每页数量，固定为 20", example = 20),
         ("cursor" = Option<String>, Query, description = "分页游标，首次请求为空，后续使用响应中的 cursor/strCursor 值", example = "")
     ),
@@ -96,15 +120,65 @@ pub async fn connections_handler(
 )]
 pub async fn likes_handler(
     State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
     axum::extract::Query(params): axum::extract::Query<api::notification::likes::LikesParams>,
 ) -> impl IntoResponse {
-    match api::notification::likes::get_likes_with_params(&state.api, params).await {
+    match api::notification::likes::get_likes_with_params(&state.api.with_account(account), params).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// 通知页-未读数
+///
+/// 获取各类通知的未读数，不拉取通知列表，适合显示角标或判断是否需要同步
+#[utoipa::path(
+    get,
+    path = "/api/notification/unread_count",
+    tag = "xhs",
+    summary = "通知页-未读数",
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号")
+    ),
+    responses(
+        (status = 200, description = "各类通知的未读数", body = api::notification::unread::UnreadCountResponse)
+    )
+)]
+pub async fn unread_count_handler(
+    State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
+) -> impl IntoResponse {
+    match api::notification::unread::get_unread_count(&state.api.with_account(account)).await {
+        Ok(res) => Json(res).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// 通知增量同步
+///
+/// 按账号和通知类型记录已见过的最新消息，只返回上次同步之后的新通知，适合定时轮询。
+/// `truncated` 为 true 时高水位未前移，可增大 `max_pages` 重新同步
+#[utoipa::path(
+    get,
+    path = "/api/notification/sync",
+    tag = "xhs",
+    summary = "通知增量同步",
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
+        SyncParams
+    ),
+    responses(
+        (status = 200, description = "上次同步之后的新通知", body = SyncResponse)
+    )
+)]
+pub async fn notification_sync_handler(
+    State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
+    Query(params): Query<SyncParams>,
+) -> impl IntoResponse {
+    let api = state.api.with_account(account);
+    match state.notification_sync.sync(&api, params.notification_type, params.max_pages).await {
+        Ok(data) => Json(SyncResponse { success: true, data }).into_response(),
+        Err(e) => e.into_response(),
     }
 }
@@ -11,6 +11,7 @@ use std::sync::Arc;
 
 use crate::api;
 use crate::server::AppState;
+use crate::handlers::session::AccountSelector;
 use crate::models::search::{
     SearchNotesRequest, SearchNotesResponse,
     SearchOneboxRequest, SearchOneboxResponse,
@@ -47,21 +48,20 @@ pub struct SearchFilterParams {
     path = "/api/search/trending",
     tag = "xhs",
     summary = "猜你想搜",
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号")
+    ),
     responses(
         (status = 200, description = "热门搜索词列表", body = QueryTrendingResponse)
     )
 )]
 pub async fn query_trending_handler(
     State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
 ) -> impl IntoResponse {
-    match api::search::query_trending(&state.api).await {
+    match api::search::query_trending(&state.api.with_account(account)).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
@@ -74,6 +74,7 @@ pub async fn query_trending_handler(
     tag = "Search",
     summary = "搜索推荐",
     params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
         SearchParams
     ),
     responses(
@@ -82,16 +83,12 @@ pub async fn query_trending_handler(
 )]
 pub async fn search_recommend_handler(
     State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
     Query(params): Query<SearchParams>,
 ) -> impl IntoResponse {
-    match api::search::recommend_search(&state.api, &params.keyword).await {
+    match api::search::recommend_search(&state.api.with_account(account), &params.keyword).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
@@ -104,22 +101,21 @@ pub async fn search_recommend_handler(
     tag = "Search",
     summary = "搜索笔记",
     request_body = SearchNotesRequest,
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号")
+    ),
     responses(
         (status = 200, description = "笔记列表", body = SearchNotesResponse)
     )
 )]
 pub async fn search_notes_handler(
     State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
     Json(req): Json<SearchNotesRequest>,
 ) -> impl IntoResponse {
-    match api::search::search_notes(&state.api, req).await {
+    match api::search::search_notes(&state.api.with_account(account), req).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
@@ -132,22 +128,21 @@ pub async fn search_notes_handler(
     tag = "Search",
     summary = "搜索 OneBox",
     request_body = SearchOneboxRequest,
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号")
+    ),
     responses(
         (status = 200, description = "OneBox 结果", body = SearchOneboxResponse)
     )
 )]
 pub async fn search_onebox_handler(
     State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
     Json(req): Json<SearchOneboxRequest>,
 ) -> impl IntoResponse {
-    match api::search::search_onebox(&state.api, req).await {
+    match api::search::search_onebox(&state.api.with_account(account), req).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
@@ -160,6 +155,7 @@ pub async fn search_onebox_handler(
     tag = "Search",
     summary = "搜索筛选器",
     params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
         SearchFilterParams
     ),
     responses(
@@ -168,16 +164,12 @@ pub async fn search_onebox_handler(
 )]
 pub async fn search_filter_handler(
     State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
     Query(params): Query<SearchFilterParams>,
 ) -> impl IntoResponse {
-    match api::search::search_filter(&state.api, &params.keyword, &params.search_id).await {
+    match api::search::search_filter(&state.api.with_account(account), &params.keyword, &params.search_id).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
 
@@ -190,21 +182,20 @@ pub async fn search_filter_handler(
     tag = "Search",
     summary = "搜索用户",
     request_body = SearchUserRequest,
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号")
+    ),
     responses(
         (status = 200, description = "用户搜索结果", body = SearchUserResponse)
     )
 )]
 pub async fn search_user_handler(
     State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
     Json(req): Json<SearchUserRequest>,
 ) -> impl IntoResponse {
-    match api::search::search_user(&state.api, req).await {
+    match api::search::search_user(&state.api.with_account(account), req).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
@@ -0,0 +1,441 @@
+//! Session Registry HTTP Handlers
+//!
+//! Handles: sessions list/add/remove/default/history/health check, and the `X-Xhs-Account` selector
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Path, Query, State},
+    http::request::Parts,
+    response::IntoResponse,
+    Json,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use crate::auth::{AuthService, UserCredentials};
+use crate::auth::credentials::ValidityEvent;
+use crate::auth::service::SessionInfo;
+use crate::client::ClientProfile;
+use crate::error::XhsError;
+use crate::server::AppState;
+
+/// 账号选择 Header
+pub const ACCOUNT_HEADER: &str = "X-Xhs-Account";
+
+// ============================================================================
+// Account Selector
+// ============================================================================
+
+/// 从 `X-Xhs-Account` Header 中提取账号（user_id），缺省为默认账号
+#[derive(Debug, Clone, Default)]
+pub struct AccountSelector(pub Option<String>);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for AccountSelector {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let account = parts
+            .headers
+            .get(ACCOUNT_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string);
+        Ok(Self(account))
+    }
+}
+
+// ============================================================================
+// Request / Response Structs
+// ============================================================================
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct SessionScopeParams {
+    /// 会话类型: main(默认) 或 creator
+    pub scope: Option<String>,
+}
+
+impl SessionScopeParams {
+    fn is_creator(&self) -> bool {
+        self.scope.as_deref() == Some("creator")
+    }
+
+    fn auth<'a>(&self, state: &'a AppState) -> &'a Arc<AuthService> {
+        if self.is_creator() {
+            &state.creator_auth
+        } else {
+            &state.auth
+        }
+    }
+}
+
+/// 手动添加会话
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct AddSessionRequest {
+    /// XHS user ID
+    pub user_id: String,
+    /// Cookie 键值对（至少包含 a1、web_session）
+    pub cookies: HashMap<String, String>,
+    /// 是否设为默认账号
+    #[serde(default)]
+    pub make_default: bool,
+    /// 该账号使用的浏览器指纹（缺省使用配置中的默认指纹）
+    #[serde(default)]
+    pub profile: Option<ClientProfile>,
+}
+
+impl AddSessionRequest {
+    /// 校验 user_id 与必需 Cookie
+    ///
+    /// 主站会话必须包含 a1 与 web_session；创作者会话至少包含一个登录态 Cookie
+    fn validate(&self, creator: bool) -> Result<(), XhsError> {
+        if self.user_id.trim().is_empty() {
+            return Err(XhsError::InvalidInput("user_id must not be empty".to_string()));
+        }
+        let has = |name: &str| self.cookies.get(name).is_some_and(|v| !v.trim().is_empty());
+        if creator {
+            if !CREATOR_SESSION_COOKIES.iter().any(|name| has(name)) {
+                return Err(XhsError::InvalidInput(format!(
+                    "cookies must contain one of: {}",
+                    CREATOR_SESSION_COOKIES.join(", ")
+                )));
+            }
+        } else {
+            let missing: Vec<&str> = MAIN_SESSION_COOKIES.iter().copied().filter(|name| !has(name)).collect();
+            if !missing.is_empty() {
+                return Err(XhsError::InvalidInput(format!("cookies missing: {}", missing.join(", "))));
+            }
+        }
+        if let Some(problems) = self.profile.as_ref().map(ClientProfile::problems).filter(|p| !p.is_empty()) {
+            return Err(XhsError::InvalidInput(format!("Invalid profile: {}", problems.join("; "))));
+        }
+        Ok(())
+    }
+}
+
+/// 主站会话必需的 Cookie
+const MAIN_SESSION_COOKIES: &[&str] = &["a1", "web_session"];
+
+/// 创作者会话的登录态 Cookie (任意一个即可)
+const CREATOR_SESSION_COOKIES: &[&str] = &[
+    "web_session",
+    "customer-sso-sid",
+    "access-token-creator.xiaohongshu.com",
+    "galaxy.creator.beaker.session.id",
+];
+
+/// 设置账号浏览器指纹
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct SetProfileRequest {
+    /// 浏览器指纹，null 表示恢复为配置中的默认指纹
+    pub profile: Option<ClientProfile>,
+}
+
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct SessionListResponse {
+    pub success: bool,
+    pub data: Vec<SessionInfo>,
+}
+
+/// 凭据历史版本摘要（不包含 Cookie 值）
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct SessionVersion {
+    /// 保存时间
+    #[schema(value_type = String, format = DateTime)]
+    pub recorded_at: chrono::DateTime<chrono::Utc>,
+    /// 保存时是否有效
+    pub is_valid: bool,
+    /// Cookie 数量
+    pub cookie_count: usize,
+}
+
+/// 账号凭据历史
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct SessionHistory {
+    pub user_id: String,
+    /// 存储后端 (json / encrypted / sqlite)，仅 sqlite 保留历史
+    pub backend: String,
+    /// 历史版本（新到旧）
+    pub versions: Vec<SessionVersion>,
+    /// 有效性变更记录（新到旧）
+    pub events: Vec<ValidityEvent>,
+}
+
+#[derive(Debug, serde::Serialize, utoipa::ToSchema)]
+pub struct SessionHistoryResponse {
+    pub success: bool,
+    pub data: SessionHistory,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// 会话列表
+///
+/// 列出所有已登录账号（不包含 Cookie 值）
+#[utoipa::path(
+    get,
+    path = "/api/auth/sessions",
+    tag = "auth",
+    summary = "会话列表",
+    params(SessionScopeParams),
+    responses(
+        (status = 200, description = "会话列表", body = SessionListResponse)
+    )
+)]
+pub async fn list_sessions_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<SessionScopeParams>,
+) -> impl IntoResponse {
+    let sessions = params.auth(&state).list_sessions().await;
+    Json(SessionListResponse { success: true, data: sessions })
+}
+
+/// 添加会话
+///
+/// 使用已有的 Cookie 手动添加一个账号会话（写入独立的存储槽位）
+#[utoipa::path(
+    post,
+    path = "/api/auth/sessions",
+    tag = "auth",
+    summary = "添加会话",
+    params(SessionScopeParams),
+    request_body = AddSessionRequest,
+    responses(
+        (status = 200, description = "会话列表", body = SessionListResponse),
+        (status = 400, description = "user_id 为空或缺少必需 Cookie")
+    )
+)]
+pub async fn add_session_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<SessionScopeParams>,
+    Json(req): Json<AddSessionRequest>,
+) -> impl IntoResponse {
+    if let Err(e) = req.validate(params.is_creator()) {
+        return e.into_response();
+    }
+    let auth = params.auth(&state);
+    let mut creds = UserCredentials::new(req.user_id.clone(), req.cookies, None);
+    creds.profile = req.profile;
+
+    if let Err(e) = auth.save_credentials(&creds).await {
+        return XhsError::from(e).into_response();
+    }
+    if req.make_default {
+        if let Err(e) = auth.set_default_account(&req.user_id).await {
+            return XhsError::from(e).into_response();
+        }
+    }
+
+    Json(SessionListResponse { success: true, data: auth.list_sessions().await }).into_response()
+}
+
+/// 检查会话健康状态
+///
+/// 立即对所有有效会话（主站与创作者）发起真实请求，失效会话将被标记并触发 Webhook；
+/// 返回 `scope` 对应的会话列表（含 `health` 字段）
+#[utoipa::path(
+    post,
+    path = "/api/auth/sessions/check",
+    tag = "auth",
+    summary = "检查会话健康状态",
+    params(SessionScopeParams),
+    responses(
+        (status = 200, description = "会话列表", body = SessionListResponse)
+    )
+)]
+pub async fn check_sessions_handler(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<SessionScopeParams>,
+) -> impl IntoResponse {
+    state.health.check_all().await;
+    Json(SessionListResponse { success: true, data: params.auth(&state).list_sessions().await })
+}
+
+/// 删除会话
+///
+/// 删除指定账号的会话及其存储文件
+#[utoipa::path(
+    delete,
+    path = "/api/auth/sessions/{user_id}",
+    tag = "auth",
+    summary = "删除会话",
+    params(
+        ("user_id" = String, Path, description = "账号 user_id"),
+        SessionScopeParams
+    ),
+    responses(
+        (status = 200, description = "会话列表", body = SessionListResponse),
+        (status = 404, description = "账号不存在")
+    )
+)]
+pub async fn remove_session_handler(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<String>,
+    Query(params): Query<SessionScopeParams>,
+) -> impl IntoResponse {
+    let auth = params.auth(&state);
+
+    match auth.remove_session(&user_id).await {
+        Ok(true) => Json(SessionListResponse { success: true, data: auth.list_sessions().await }).into_response(),
+        Ok(false) => XhsError::AccountNotFound(user_id).into_response(),
+        Err(e) => XhsError::from(e).into_response(),
+    }
+}
+
+/// 设为默认账号
+///
+/// 未携带 `X-Xhs-Account` Header 的请求将使用默认账号
+#[utoipa::path(
+    post,
+    path = "/api/auth/sessions/{user_id}/default",
+    tag = "auth",
+    summary = "设为默认账号",
+    params(
+        ("user_id" = String, Path, description = "账号 user_id"),
+        SessionScopeParams
+    ),
+    responses(
+        (status = 200, description = "会话列表", body = SessionListResponse),
+        (status = 404, description = "账号不存在")
+    )
+)]
+pub async fn set_default_session_handler(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<String>,
+    Query(params): Query<SessionScopeParams>,
+) -> impl IntoResponse {
+    let auth = params.auth(&state);
+
+    match auth.set_default_account(&user_id).await {
+        Ok(()) => Json(SessionListResponse { success: true, data: auth.list_sessions().await }).into_response(),
+        Err(_) => XhsError::AccountNotFound(user_id).into_response(),
+    }
+}
+
+/// 设置账号浏览器指纹
+///
+/// 指定该账号请求使用的 User-Agent、sec-ch-ua、平台与语言（签名使用相同的平台）
+#[utoipa::path(
+    put,
+    path = "/api/auth/sessions/{user_id}/profile",
+    tag = "auth",
+    summary = "设置账号浏览器指纹",
+    params(
+        ("user_id" = String, Path, description = "账号 user_id"),
+        SessionScopeParams
+    ),
+    request_body = SetProfileRequest,
+    responses(
+        (status = 200, description = "会话列表", body = SessionListResponse),
+        (status = 400, description = "指纹无效"),
+        (status = 404, description = "账号不存在")
+    )
+)]
+pub async fn set_session_profile_handler(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<String>,
+    Query(params): Query<SessionScopeParams>,
+    Json(req): Json<SetProfileRequest>,
+) -> impl IntoResponse {
+    let auth = params.auth(&state);
+    if let Some(problems) = req.profile.as_ref().map(ClientProfile::problems).filter(|p| !p.is_empty()) {
+        return XhsError::InvalidInput(format!("Invalid profile: {}", problems.join("; "))).into_response();
+    }
+
+    match auth.set_profile(&user_id, req.profile).await {
+        Ok(true) => Json(SessionListResponse { success: true, data: auth.list_sessions().await }).into_response(),
+        Ok(false) => XhsError::AccountNotFound(user_id).into_response(),
+        Err(e) => XhsError::from(e).into_response(),
+    }
+}
+
+/// 会话历史
+///
+/// 账号的凭据历史版本与有效性变更记录（登录 / 重新登录 / 失效 / 删除），需 `XHS_STORAGE=sqlite`
+#[utoipa::path(
+    get,
+    path = "/api/auth/sessions/{user_id}/history",
+    tag = "auth",
+    summary = "会话历史",
+    params(
+        ("user_id" = String, Path, description = "账号 user_id"),
+        SessionScopeParams
+    ),
+    responses(
+        (status = 200, description = "会话历史", body = SessionHistoryResponse)
+    )
+)]
+pub async fn session_history_handler(
+    State(state): State<Arc<AppState>>,
+    Path(user_id): Path<String>,
+    Query(params): Query<SessionScopeParams>,
+) -> impl IntoResponse {
+    let auth = params.auth(&state);
+
+    let versions = match auth.credential_history(&user_id).await {
+        Ok(history) => history
+            .into_iter()
+            .map(|s| SessionVersion {
+                recorded_at: s.recorded_at,
+                is_valid: s.credentials.is_valid,
+                cookie_count: s.credentials.cookies.len(),
+            })
+            .collect(),
+        Err(e) => return XhsError::from(e).into_response(),
+    };
+    let events = match auth.validity_events(&user_id).await {
+        Ok(events) => events,
+        Err(e) => return XhsError::from(e).into_response(),
+    };
+
+    Json(SessionHistoryResponse {
+        success: true,
+        data: SessionHistory {
+            user_id,
+            backend: auth.storage_name().to_string(),
+            versions,
+            events,
+        },
+    })
+    .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(user_id: &str, cookies: &[(&str, &str)]) -> AddSessionRequest {
+        AddSessionRequest {
+            user_id: user_id.to_string(),
+            cookies: cookies.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            make_default: false,
+            profile: None,
+        }
+    }
+
+    #[test]
+    fn add_session_requires_user_id_and_session_cookies() {
+        let full = [("a1", "a"), ("web_session", "s")];
+        assert!(request("u1", &full).validate(false).is_ok());
+
+        for invalid in [
+            request(" ", &full),
+            request("u1", &[("a1", "a")]),
+            request("u1", &[("web_session", "s")]),
+            request("u1", &[("a1", "a"), ("web_session", "")]),
+        ] {
+            assert!(matches!(invalid.validate(false), Err(XhsError::InvalidInput(_))), "{:?}", invalid);
+        }
+    }
+
+    #[test]
+    fn creator_session_accepts_any_creator_cookie() {
+        assert!(request("u1", &[("galaxy.creator.beaker.session.id", "g")]).validate(true).is_ok());
+        assert!(matches!(request("u1", &[("a1", "a")]).validate(true), Err(XhsError::InvalidInput(_))));
+    }
+}
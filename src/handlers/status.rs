@@ -0,0 +1,37 @@
+//! Status HTTP Handlers
+//!
+//! Handles: client-side rate limiter state
+
+use axum::{extract::State, response::IntoResponse, Json};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::api::limiter::LimitStatus;
+use crate::server::AppState;
+
+/// 限速状态响应
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct LimitsResponse {
+    pub success: bool,
+    pub data: Vec<LimitStatus>,
+}
+
+/// 客户端限速状态
+///
+/// 各端点族的令牌桶、退避与熔断状态（所有账号共享）
+#[utoipa::path(
+    get,
+    path = "/api/status/limits",
+    tag = "Status",
+    summary = "客户端限速状态",
+    description = "各端点族（search / feed / note / notification / user / creator / other）的速率、可用令牌、退避与熔断状态。461 每次计入退避；406 先重新签名，重试用尽后仍被拒绝时计入一次。",
+    responses(
+        (status = 200, description = "限速状态", body = LimitsResponse)
+    )
+)]
+pub async fn limits_handler(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    Json(LimitsResponse {
+        success: true,
+        data: state.api.limiter().snapshot(),
+    })
+}
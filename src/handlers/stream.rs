@@ -0,0 +1,513 @@
+//! Streaming (NDJSON) HTTP Handlers
+//!
+//! Handles: server-side "fetch all pages" for comments (and full reply threads), user notes, collections / likes / boards, notifications, homefeed and search.
+//! Results are streamed as `application/x-ndjson`, one item per line.
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::api::cache;
+use crate::api::note::sub_comments;
+use crate::api::Paginator;
+use crate::error::{XhsError, XhsResult};
+use crate::handlers::session::AccountSelector;
+use crate::models::search::{SearchNotesRequest, SearchUserRequest};
+use crate::models::feed::HomefeedRequest;
+use crate::server::AppState;
+
+/// NDJSON Content-Type
+const NDJSON: &str = "application/x-ndjson";
+
+// ============================================================================
+// Request Structs
+// ============================================================================
+
+/// 分页上限与翻页间隔
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct StreamParams {
+    /// 最多请求页数 (默认 10)
+    #[serde(default = "default_max_pages")]
+    pub max_pages: usize,
+    /// 最多返回条数 (默认不限)
+    pub max_items: Option<usize>,
+    /// 翻页间隔毫秒 (默认 1000，建议 ≥ 1000 以避免风控)
+    #[serde(default = "default_delay_ms")]
+    pub delay_ms: u64,
+}
+
+fn default_max_pages() -> usize { 10 }
+fn default_delay_ms() -> u64 { 1000 }
+
+impl StreamParams {
+    /// 应用到分页器
+    fn apply<T: Send + 'static, C: Clone + Send + 'static>(&self, paginator: Paginator<T, C>) -> Paginator<T, C> {
+        let paginator = paginator
+            .max_pages(self.max_pages)
+            .delay(Duration::from_millis(self.delay_ms));
+        match self.max_items {
+            Some(max_items) => paginator.max_items(max_items),
+            None => paginator,
+        }
+    }
+}
+
+/// 评论流请求参数
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct CommentStreamParams {
+    /// 笔记 ID (必填)
+    pub note_id: String,
+    /// xsec_token (必填)
+    pub xsec_token: String,
+}
+
+/// 用户笔记流请求参数
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct UserStreamParams {
+    /// xsec_token (必填)
+    pub xsec_token: String,
+}
+
+/// 完整评论串请求参数
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct ThreadStreamParams {
+    /// 笔记 ID (必填)
+    pub note_id: String,
+    /// xsec_token (必填)
+    pub xsec_token: String,
+    /// 每条一级评论最多展开的回复数 (默认 100)
+    #[serde(default = "default_max_replies")]
+    pub max_replies: usize,
+}
+
+fn default_max_replies() -> usize { 100 }
+
+// ============================================================================
+// NDJSON Response
+// ============================================================================
+
+/// 流中途出错时输出的最后一行
+#[derive(Serialize)]
+struct StreamError {
+    error: &'static str,
+    msg: String,
+}
+
+/// 将数据流转换为 NDJSON 响应
+///
+/// 第一页失败时直接返回对应的错误响应（状态码同普通接口）；
+/// 之后的错误（包括单条数据无法序列化）以 `{"error": ..., "msg": ...}` 作为最后一行输出后结束。
+/// 后续页面在当前请求的缓存上下文中获取（见 [`cache::in_request_scope`]）。
+async fn ndjson<T, S>(stream: S) -> Response
+where
+    T: Serialize + Send + 'static,
+    S: Stream<Item = XhsResult<T>> + Send + 'static,
+{
+    let mut stream = Box::pin(cache::in_request_scope(stream));
+
+    let first = match stream.next().await {
+        Some(Err(e)) => return e.into_response(),
+        first => first,
+    };
+
+    let items = futures::stream::iter(first).chain(stream);
+    let lines = futures::stream::unfold((items, false), |(mut items, done)| async move {
+        if done {
+            return None;
+        }
+        let (line, failed) = ndjson_line(items.next().await?);
+        Some((Ok::<_, Infallible>(line), (items, failed)))
+    });
+
+    ([(header::CONTENT_TYPE, NDJSON)], Body::from_stream(lines)).into_response()
+}
+
+/// 单条 NDJSON 行，错误行返回 `true`
+fn ndjson_line<T: Serialize>(item: XhsResult<T>) -> (Bytes, bool) {
+    let (mut line, failed) = match item.map(|item| serde_json::to_vec(&item)) {
+        Ok(Ok(line)) => (line, false),
+        Ok(Err(e)) => (error_line(&XhsError::from(e)), true),
+        Err(e) => (error_line(&e), true),
+    };
+    line.push(b'\n');
+    (Bytes::from(line), failed)
+}
+
+fn error_line(e: &XhsError) -> Vec<u8> {
+    serde_json::to_vec(&StreamError { error: e.kind(), msg: e.to_string() }).unwrap_or_default()
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// 笔记评论 (全部分页)
+///
+/// 服务端按 `cursor` 连续翻页，逐条输出评论 (NDJSON)
+#[utoipa::path(
+    get,
+    path = "/api/note/comments/stream",
+    tag = "Note",
+    summary = "笔记评论 (NDJSON 流)",
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
+        CommentStreamParams,
+        StreamParams
+    ),
+    responses(
+        (status = 200, description = "每行一条评论", content_type = "application/x-ndjson")
+    )
+)]
+pub async fn comments_stream_handler(
+    State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
+    Query(target): Query<CommentStreamParams>,
+    Query(params): Query<StreamParams>,
+) -> impl IntoResponse {
+    let api = state.api.with_account(account);
+    let paginator = Paginator::comments(&api, &target.note_id, &target.xsec_token);
+    ndjson(params.apply(paginator).into_stream()).await
+}
+
+/// 笔记完整评论串 (全部分页 + 展开回复)
+///
+/// 逐条输出一级评论，`sub_comments` 中已展开被折叠的回复（每条最多 `max_replies` 条）。
+/// `max_pages` / `max_items` 作用于一级评论，`delay_ms` 同时作用于回复翻页
+#[utoipa::path(
+    get,
+    path = "/api/note/thread/stream",
+    tag = "Note",
+    summary = "笔记完整评论串 (NDJSON 流)",
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
+        ThreadStreamParams,
+        StreamParams
+    ),
+    responses(
+        (status = 200, description = "每行一条一级评论（含展开的回复）", content_type = "application/x-ndjson")
+    )
+)]
+pub async fn thread_stream_handler(
+    State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
+    Query(target): Query<ThreadStreamParams>,
+    Query(params): Query<StreamParams>,
+) -> impl IntoResponse {
+    let api = state.api.with_account(account);
+    let comments = params.apply(Paginator::comments(&api, &target.note_id, &target.xsec_token)).into_stream();
+    let threads = sub_comments::full_threads(
+        &api,
+        comments,
+        &target.note_id,
+        &target.xsec_token,
+        target.max_replies,
+        Duration::from_millis(params.delay_ms),
+    );
+    ndjson(threads).await
+}
+
+/// 用户笔记列表 (全部分页)
+#[utoipa::path(
+    get,
+    path = "/api/user/posted/{user_id}/stream",
+    tag = "xhs",
+    summary = "用户笔记列表 (NDJSON 流)",
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
+        ("user_id" = String, Path, description = "目标用户 ID"),
+        UserStreamParams,
+        StreamParams
+    ),
+    responses(
+        (status = 200, description = "每行一条笔记卡片", content_type = "application/x-ndjson")
+    )
+)]
+pub async fn user_posted_stream_handler(
+    State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
+    Path(user_id): Path<String>,
+    Query(target): Query<UserStreamParams>,
+    Query(params): Query<StreamParams>,
+) -> impl IntoResponse {
+    let paginator = Paginator::user_posted(&state.api.with_account(account), &user_id, &target.xsec_token);
+    ndjson(params.apply(paginator).into_stream()).await
+}
+
+/// 我的收藏 (全部分页)
+#[utoipa::path(
+    get,
+    path = "/api/user/me/collected/stream",
+    tag = "xhs",
+    summary = "我的收藏 (NDJSON 流)",
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
+        StreamParams
+    ),
+    responses(
+        (status = 200, description = "每行一条笔记卡片", content_type = "application/x-ndjson")
+    )
+)]
+pub async fn collected_stream_handler(
+    State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
+    Query(params): Query<StreamParams>,
+) -> impl IntoResponse {
+    let paginator = Paginator::collected(&state.api.with_account(account));
+    ndjson(params.apply(paginator).into_stream()).await
+}
+
+/// 我的点赞 (全部分页)
+#[utoipa::path(
+    get,
+    path = "/api/user/me/liked/stream",
+    tag = "xhs",
+    summary = "我的点赞 (NDJSON 流)",
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
+        StreamParams
+    ),
+    responses(
+        (status = 200, description = "每行一条笔记卡片", content_type = "application/x-ndjson")
+    )
+)]
+pub async fn liked_stream_handler(
+    State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
+    Query(params): Query<StreamParams>,
+) -> impl IntoResponse {
+    let paginator = Paginator::liked(&state.api.with_account(account));
+    ndjson(params.apply(paginator).into_stream()).await
+}
+
+/// 专辑内的笔记 (全部分页)
+#[utoipa::path(
+    get,
+    path = "/api/user/boards/{board_id}/notes/stream",
+    tag = "xhs",
+    summary = "专辑内的笔记 (NDJSON 流)",
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
+        ("board_id" = String, Path, description = "专辑 ID"),
+        StreamParams
+    ),
+    responses(
+        (status = 200, description = "每行一条笔记卡片", content_type = "application/x-ndjson")
+    )
+)]
+pub async fn board_notes_stream_handler(
+    State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
+    Path(board_id): Path<String>,
+    Query(params): Query<StreamParams>,
+) -> impl IntoResponse {
+    let paginator = Paginator::board_notes(&state.api.with_account(account), &board_id);
+    ndjson(params.apply(paginator).into_stream()).await
+}
+
+/// 通知页-评论和@ (全部分页)
+#[utoipa::path(
+    get,
+    path = "/api/notification/mentions/stream",
+    tag = "xhs",
+    summary = "通知页-评论和@ (NDJSON 流)",
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
+        StreamParams
+    ),
+    responses(
+        (status = 200, description = "每行一条通知", content_type = "application/x-ndjson")
+    )
+)]
+pub async fn mentions_stream_handler(
+    State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
+    Query(params): Query<StreamParams>,
+) -> impl IntoResponse {
+    let paginator = Paginator::mentions(&state.api.with_account(account));
+    ndjson(params.apply(paginator).into_stream()).await
+}
+
+/// 通知页-新增关注 (全部分页)
+#[utoipa::path(
+    get,
+    path = "/api/notification/connections/stream",
+    tag = "xhs",
+    summary = "通知页-新增关注 (NDJSON 流)",
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
+        StreamParams
+    ),
+    responses(
+        (status = 200, description = "每行一条通知", content_type = "application/x-ndjson")
+    )
+)]
+pub async fn connections_stream_handler(
+    State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
+    Query(params): Query<StreamParams>,
+) -> impl IntoResponse {
+    let paginator = Paginator::connections(&state.api.with_account(account));
+    ndjson(params.apply(paginator).into_stream()).await
+}
+
+/// 通知页-赞和收藏 (全部分页)
+#[utoipa::path(
+    get,
+    path = "/api/notification/likes/stream",
+    tag = "xhs",
+    summary = "通知页-赞和收藏 (NDJSON 流)",
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
+        StreamParams
+    ),
+    responses(
+        (status = 200, description = "每行一条通知", content_type = "application/x-ndjson")
+    )
+)]
+pub async fn likes_stream_handler(
+    State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
+    Query(params): Query<StreamParams>,
+) -> impl IntoResponse {
+    let paginator = Paginator::likes(&state.api.with_account(account));
+    ndjson(params.apply(paginator).into_stream()).await
+}
+
+/// 主页发现-频道 (连续翻页)
+///
+/// 服务端按 `cursor_score` + `note_index` 规则翻页，Feed 没有终点，请通过 `max_pages` / `max_items` 控制数量
+#[utoipa::path(
+    post,
+    path = "/api/feed/homefeed/{category}/stream",
+    tag = "Feed",
+    summary = "主页发现-频道 (NDJSON 流)",
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
+        ("category" = String, Path, description = "频道名称: recommend/fashion/food/..."),
+        StreamParams
+    ),
+    responses(
+        (status = 200, description = "每行一条笔记卡片", content_type = "application/x-ndjson")
+    )
+)]
+pub async fn homefeed_stream_handler(
+    State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
+    Path(category): Path<String>,
+    Query(params): Query<StreamParams>,
+) -> impl IntoResponse {
+    let paginator = Paginator::<_, HomefeedRequest>::homefeed(&state.api.with_account(account), &category);
+    ndjson(params.apply(paginator).into_stream()).await
+}
+
+/// 搜索笔记 (全部分页)
+///
+/// 服务端按 `page` 翻页（保持同一 `search_id`），逐条输出笔记
+#[utoipa::path(
+    post,
+    path = "/api/search/notes/stream",
+    tag = "Search",
+    summary = "搜索笔记 (NDJSON 流)",
+    request_body = SearchNotesRequest,
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
+        StreamParams
+    ),
+    responses(
+        (status = 200, description = "每行一条笔记", content_type = "application/x-ndjson")
+    )
+)]
+pub async fn search_notes_stream_handler(
+    State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
+    Query(params): Query<StreamParams>,
+    Json(req): Json<SearchNotesRequest>,
+) -> impl IntoResponse {
+    let paginator = Paginator::search_notes(&state.api.with_account(account), req);
+    ndjson(params.apply(paginator).into_stream()).await
+}
+
+/// 搜索用户 (全部分页)
+#[utoipa::path(
+    post,
+    path = "/api/search/usersearch/stream",
+    tag = "Search",
+    summary = "搜索用户 (NDJSON 流)",
+    request_body = SearchUserRequest,
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
+        StreamParams
+    ),
+    responses(
+        (status = 200, description = "每行一个用户", content_type = "application/x-ndjson")
+    )
+)]
+pub async fn search_user_stream_handler(
+    State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
+    Query(params): Query<StreamParams>,
+    Json(req): Json<SearchUserRequest>,
+) -> impl IntoResponse {
+    let paginator = Paginator::search_users(&state.api.with_account(account), req);
+    ndjson(params.apply(paginator).into_stream()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::to_bytes;
+
+    /// 无法序列化的数据
+    struct Unserializable;
+
+    impl Serialize for Unserializable {
+        fn serialize<S: serde::Serializer>(&self, _serializer: S) -> Result<S::Ok, S::Error> {
+            Err(serde::ser::Error::custom("not serializable"))
+        }
+    }
+
+    async fn body_lines(response: Response) -> Vec<serde_json::Value> {
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        body.split(|b| *b == b'\n')
+            .filter(|line| !line.is_empty())
+            .map(|line| serde_json::from_slice(line).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn serialization_failure_ends_with_error_line() {
+        let items = vec![Ok(None), Ok(Some(Unserializable)), Ok(None)];
+        let lines = body_lines(ndjson(futures::stream::iter(items)).await).await;
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], serde_json::Value::Null);
+        assert_eq!(lines[1]["error"], "decode");
+    }
+
+    #[tokio::test]
+    async fn later_errors_end_the_stream() {
+        let items = vec![Ok(1), Err(XhsError::RiskControl461("461".to_string())), Ok(2)];
+        let lines = body_lines(ndjson(futures::stream::iter(items)).await).await;
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0], 1);
+        assert_eq!(lines[1]["error"], "risk_control");
+    }
+
+    #[tokio::test]
+    async fn first_error_is_an_error_response() {
+        let items: Vec<XhsResult<u32>> = vec![Err(XhsError::NotLoggedIn)];
+        let response = ndjson(futures::stream::iter(items)).await;
+        assert_eq!(response.status(), axum::http::StatusCode::UNAUTHORIZED);
+    }
+}
+
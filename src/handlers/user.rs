@@ -1,17 +1,70 @@
 //! User HTTP Handlers
 //! 
-//! Handles: user/me
+//! Handles: user/me, user profile, user posted notes, collected / liked notes, boards
 
 use axum::{
-    extract::State,
+    extract::{Path, Query, State},
     response::IntoResponse,
     Json,
 };
+use serde::Deserialize;
 use std::sync::Arc;
 
 use crate::api;
 use crate::server::AppState;
-use crate::models::user::UserMeResponse;
+use crate::handlers::session::AccountSelector;
+use crate::models::user::{BoardListResponse, UserMeResponse, UserNotesResponse, UserProfileResponse};
+
+// ============================================================================
+// Request Structs
+// ============================================================================
+
+/// 用户主页请求参数
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct UserProfileParams {
+    /// xsec_token (必填，来自搜索用户、笔记作者或评论者信息)
+    pub xsec_token: String,
+}
+
+/// 用户笔记列表请求参数
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct UserPostedParams {
+    /// 分页游标 (首次为空，后续使用上次返回的 cursor)
+    #[serde(default)]
+    pub cursor: String,
+    /// 每页数量 (默认: 30)
+    #[serde(default = "default_posted_num")]
+    pub num: u32,
+    /// xsec_token (必填)
+    pub xsec_token: String,
+}
+
+fn default_posted_num() -> u32 { 30 }
+
+/// 收藏 / 点赞 / 专辑笔记请求参数
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct NoteListParams {
+    /// 分页游标 (首次为空，后续使用上次返回的 cursor)
+    #[serde(default)]
+    pub cursor: String,
+    /// 每页数量 (默认: 30)
+    #[serde(default = "default_posted_num")]
+    pub num: u32,
+}
+
+/// 收藏专辑列表请求参数
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct BoardListParams {
+    /// 页码 (从 1 开始)
+    #[serde(default = "default_board_page")]
+    pub page: u32,
+    /// 每页数量 (默认: 15)
+    #[serde(default = "default_board_num")]
+    pub num: u32,
+}
+
+fn default_board_page() -> u32 { 1 }
+fn default_board_num() -> u32 { 15 }
 
 // ============================================================================
 // Handlers
@@ -25,20 +78,189 @@ use crate::models::user::UserMeResponse;
     path = "/api/user/me",
     tag = "xhs",
     summary = "页面-我",
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号")
+    ),
     responses(
         (status = 200, description = "当前用户信息（未登录时返回 Not logged in）", body = UserMeResponse)
     )
 )]
 pub async fn user_me_handler(
     State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
+) -> impl IntoResponse {
+    match api::user::get_current_user(&state.api.with_account(account)).await {
+        Ok(res) => Json(res).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// 用户主页
+///
+/// 获取指定用户的简介、关注/粉丝/获赞与收藏数、资料标签和 IP 属地
+#[utoipa::path(
+    get,
+    path = "/api/user/profile/{user_id}",
+    tag = "xhs",
+    summary = "用户主页",
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
+        ("user_id" = String, Path, description = "目标用户 ID"),
+        UserProfileParams
+    ),
+    responses(
+        (status = 200, description = "用户主页信息", body = UserProfileResponse)
+    )
+)]
+pub async fn user_profile_handler(
+    State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
+    Path(user_id): Path<String>,
+    Query(params): Query<UserProfileParams>,
+) -> impl IntoResponse {
+    match api::user::get_user_profile(&state.api.with_account(account), &user_id, &params.xsec_token).await {
+        Ok(res) => Json(res).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// 用户笔记列表
+///
+/// 获取指定用户发布的笔记（`cursor` 分页），全部分页可使用 `/api/user/posted/{user_id}/stream`
+#[utoipa::path(
+    get,
+    path = "/api/user/posted/{user_id}",
+    tag = "xhs",
+    summary = "用户笔记列表",
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
+        ("user_id" = String, Path, description = "目标用户 ID"),
+        UserPostedParams
+    ),
+    responses(
+        (status = 200, description = "用户笔记列表", body = UserNotesResponse)
+    )
+)]
+pub async fn user_posted_handler(
+    State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
+    Path(user_id): Path<String>,
+    Query(params): Query<UserPostedParams>,
+) -> impl IntoResponse {
+    let api = state.api.with_account(account);
+    match api::user::get_user_posted_notes(&api, &user_id, &params.cursor, params.num, &params.xsec_token).await {
+        Ok(res) => Json(res).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// 我的收藏
+///
+/// 获取当前账号收藏的笔记（`cursor` 分页），全部分页可使用 `/api/user/me/collected/stream`
+#[utoipa::path(
+    get,
+    path = "/api/user/me/collected",
+    tag = "xhs",
+    summary = "我的收藏",
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
+        NoteListParams
+    ),
+    responses(
+        (status = 200, description = "收藏的笔记", body = UserNotesResponse)
+    )
+)]
+pub async fn collected_notes_handler(
+    State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
+    Query(params): Query<NoteListParams>,
+) -> impl IntoResponse {
+    match api::user::get_collected_notes(&state.api.with_account(account), &params.cursor, params.num).await {
+        Ok(res) => Json(res).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// 我的点赞
+///
+/// 获取当前账号点赞的笔记（`cursor` 分页），全部分页可使用 `/api/user/me/liked/stream`
+#[utoipa::path(
+    get,
+    path = "/api/user/me/liked",
+    tag = "xhs",
+    summary = "我的点赞",
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
+        NoteListParams
+    ),
+    responses(
+        (status = 200, description = "点赞的笔记", body = UserNotesResponse)
+    )
+)]
+pub async fn liked_notes_handler(
+    State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
+    Query(params): Query<NoteListParams>,
+) -> impl IntoResponse {
+    match api::user::get_liked_notes(&state.api.with_account(account), &params.cursor, params.num).await {
+        Ok(res) => Json(res).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// 我的收藏专辑
+///
+/// 获取当前账号的收藏专辑（`page` 分页）
+#[utoipa::path(
+    get,
+    path = "/api/user/me/boards",
+    tag = "xhs",
+    summary = "我的收藏专辑",
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
+        BoardListParams
+    ),
+    responses(
+        (status = 200, description = "收藏专辑列表", body = BoardListResponse)
+    )
+)]
+pub async fn boards_handler(
+    State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
+    Query(params): Query<BoardListParams>,
+) -> impl IntoResponse {
+    match api::user::get_boards(&state.api.with_account(account), params.page, params.num).await {
+        Ok(res) => Json(res).into_response(),
+        Err(e) => e.into_response(),
+    }
+}
+
+/// 专辑内的笔记
+///
+/// 获取收藏专辑中的笔记（`cursor` 分页），全部分页可使用 `/api/user/boards/{board_id}/notes/stream`
+#[utoipa::path(
+    get,
+    path = "/api/user/boards/{board_id}/notes",
+    tag = "xhs",
+    summary = "专辑内的笔记",
+    params(
+        ("X-Xhs-Account" = Option<String>, Header, description = "账号 user_id，缺省为默认账号"),
+        ("board_id" = String, Path, description = "专辑 ID (来自 /api/user/me/boards)"),
+        NoteListParams
+    ),
+    responses(
+        (status = 200, description = "专辑内的笔记", body = UserNotesResponse)
+    )
+)]
+pub async fn board_notes_handler(
+    State(state): State<Arc<AppState>>,
+    AccountSelector(account): AccountSelector,
+    Path(board_id): Path<String>,
+    Query(params): Query<NoteListParams>,
 ) -> impl IntoResponse {
-    match api::user::get_current_user(&state.api).await {
+    let api = state.api.with_account(account);
+    match api::user::get_board_notes(&api, &board_id, &params.cursor, params.num).await {
         Ok(res) => Json(res).into_response(),
-        Err(e) => Json(serde_json::json!({
-            "code": -1,
-            "success": false,
-            "msg": e.to_string(),
-            "data": null
-        })).into_response(),
+        Err(e) => e.into_response(),
     }
 }
@@ -0,0 +1,341 @@
+//! 会话健康检查模块 (Session Health Checker)
+//!
+//! 后台定期对每个有效会话发起真实请求，而不是依赖 7 天过期的估算：
+//! - 主站会话: `api::user::get_current_user`
+//! - 创作者会话: `api::creator::info::get_creator_user_info`
+//!
+//! 检查结果写入 `AuthService`（`/api/auth/sessions` 返回 `health` 字段）。
+//! XHS 明确返回未登录时将会话标记为失效，并发送 `session_expired` 事件
+//! （进程内广播 + 可选 Webhook），便于在批量任务失败前重新扫码登录。
+//! 网络、签名或风控错误只记录为 `error`，不会改变会话有效性。
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+
+use crate::api::{self, XhsApiClient};
+use crate::auth::service::{HealthStatus, SessionHealth};
+use crate::auth::AuthService;
+use crate::config::get_health_config;
+use crate::error::{is_login_required_code, XhsError, XhsResult};
+use crate::models::user::UserMeResponse;
+
+/// 会话类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionScope {
+    /// 主站会话 (sessions/)
+    Main,
+    /// 创作者中心会话 (sessions-creator/)
+    Creator,
+}
+
+/// 会话事件（Webhook 请求体）
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionEvent {
+    /// 事件类型，目前为 "session_expired"
+    pub event: &'static str,
+    pub scope: SessionScope,
+    pub user_id: String,
+    /// 失效原因
+    pub detail: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+/// 会话健康检查器
+pub struct HealthChecker {
+    api: XhsApiClient,
+    auth: Arc<AuthService>,
+    creator_auth: Arc<AuthService>,
+    http: reqwest::Client,
+    webhook_url: Option<String>,
+    events: broadcast::Sender<SessionEvent>,
+}
+
+impl HealthChecker {
+    /// 创建健康检查器（Webhook 地址来自 XHS_HEALTH_WEBHOOK）
+    pub fn new(api: XhsApiClient, auth: Arc<AuthService>, creator_auth: Arc<AuthService>) -> Self {
+        let (events, _) = broadcast::channel(64);
+        Self {
+            api,
+            auth,
+            creator_auth,
+            http: reqwest::Client::new(),
+            webhook_url: get_health_config().webhook_url.clone(),
+            events,
+        }
+    }
+
+    /// 订阅会话事件
+    pub fn subscribe(&self) -> broadcast::Receiver<SessionEvent> {
+        self.events.subscribe()
+    }
+
+    /// 启动后台定期检查（XHS_HEALTH_INTERVAL 为 0 时不启动）
+    pub fn spawn(self: Arc<Self>) {
+        let interval_secs = get_health_config().interval_secs;
+        if interval_secs == 0 {
+            info!("[HealthChecker] Disabled (XHS_HEALTH_INTERVAL=0)");
+            return;
+        }
+
+        info!("[HealthChecker] Checking sessions every {}s", interval_secs);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(Duration::from_secs(interval_secs));
+            loop {
+                ticker.tick().await;
+                self.check_all().await;
+            }
+        });
+    }
+
+    /// 立即检查所有有效会话
+    pub async fn check_all(&self) {
+        for session in self.auth.list_sessions().await.into_iter().filter(|s| s.is_valid) {
+            let result = api::user::get_current_user(&self.api.with_account(Some(session.user_id.clone())))
+                .await
+                .and_then(|resp| guest_as_expired(&session.user_id, &resp));
+            self.record(SessionScope::Main, &session.user_id, result).await;
+        }
+
+        for session in self.creator_auth.list_sessions().await.into_iter().filter(|s| s.is_valid) {
+            let result = match self.creator_auth.try_get_credentials_for(Some(&session.user_id)).await {
+                Ok(Some(creds)) => api::creator::info::get_creator_user_info(&self.creator_auth, &creds).await.map(|_| ()),
+                // 检查期间被删除或失效
+                Ok(None) => continue,
+                Err(e) => Err(XhsError::from(e)),
+            };
+            self.record(SessionScope::Creator, &session.user_id, result).await;
+        }
+    }
+
+    /// 记录检查结果，会话失效时标记并发送事件
+    async fn record(&self, scope: SessionScope, user_id: &str, result: XhsResult<()>) {
+        let auth = match scope {
+            SessionScope::Main => &self.auth,
+            SessionScope::Creator => &self.creator_auth,
+        };
+
+        let (status, detail) = match &result {
+            Ok(()) => (HealthStatus::Alive, None),
+            Err(e) if is_logged_out(e) => (HealthStatus::Expired, Some(e.to_string())),
+            Err(e) => (HealthStatus::Error, Some(e.to_string())),
+        };
+        auth.record_health(user_id, SessionHealth { status, checked_at: Utc::now(), detail: detail.clone() }).await;
+
+        match status {
+            HealthStatus::Alive => info!("[HealthChecker] {:?} session {} is alive", scope, user_id),
+            HealthStatus::Error => warn!("[HealthChecker] {:?} session {} check failed: {}", scope, user_id, detail.as_deref().unwrap_or_default()),
+            HealthStatus::Expired => {
+                warn!("[HealthChecker] {:?} session {} expired, re-login required", scope, user_id);
+                if let Err(e) = auth.invalidate_account(user_id).await {
+                    warn!("[HealthChecker] Failed to invalidate {}: {}", user_id, e);
+                }
+                self.emit(SessionEvent {
+                    event: "session_expired",
+                    scope,
+                    user_id: user_id.to_string(),
+                    detail,
+                    at: Utc::now(),
+                })
+                .await;
+            }
+        }
+    }
+
+    /// 广播事件并调用 Webhook
+    async fn emit(&self, event: SessionEvent) {
+        // 没有订阅者时发送失败，忽略即可
+        let _ = self.events.send(event.clone());
+
+        let Some(url) = &self.webhook_url else { return };
+        match self.http.post(url).json(&event).timeout(Duration::from_secs(10)).send().await {
+            Ok(resp) if resp.status().is_success() => {
+                info!("[HealthChecker] Webhook notified for {}", event.user_id)
+            }
+            Ok(resp) => warn!("[HealthChecker] Webhook returned {}", resp.status()),
+            Err(e) => warn!("[HealthChecker] Webhook failed: {}", e),
+        }
+    }
+}
+
+/// 游客身份视为会话失效
+fn guest_as_expired(user_id: &str, resp: &UserMeResponse) -> XhsResult<()> {
+    if resp.data.guest {
+        Err(XhsError::SessionExpired {
+            account: user_id.to_string(),
+            code: -101,
+            msg: "guest session".to_string(),
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// 错误是否表示 XHS 明确判定为未登录
+fn is_logged_out(err: &XhsError) -> bool {
+    match err {
+        // XhsApiClient 已识别并将会话标记为失效
+        XhsError::SessionExpired { .. } => true,
+        // 创作者接口直接返回业务码
+        XhsError::Upstream { code, .. } => *code == 401 || is_login_required_code(*code),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::service::SessionInfo;
+    use crate::auth::UserCredentials;
+    use crate::client::XhsClient;
+    use crate::signature::NativeSigner;
+    use axum::{extract::State, routing::post, Json, Router};
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+    use std::sync::Mutex;
+
+    type Received = Arc<Mutex<Vec<serde_json::Value>>>;
+
+    struct Fixture {
+        checker: HealthChecker,
+        webhook: Received,
+        dirs: Vec<PathBuf>,
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            for dir in &self.dirs {
+                let _ = std::fs::remove_dir_all(dir);
+            }
+        }
+    }
+
+    /// Local webhook receiver
+    async fn webhook() -> (String, Received) {
+        async fn receive(State(received): State<Received>, Json(body): Json<serde_json::Value>) {
+            received.lock().unwrap().push(body);
+        }
+
+        let received = Received::default();
+        let app = Router::new().route("/hook", post(receive)).with_state(received.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (url, received)
+    }
+
+    async fn fixture() -> Fixture {
+        let dirs: Vec<PathBuf> = (0..2)
+            .map(|_| std::env::temp_dir().join(format!("xhs-health-{}", uuid::Uuid::new_v4())))
+            .collect();
+        let auth = Arc::new(AuthService::new(dirs[0].clone()).await.unwrap());
+        let creator_auth = Arc::new(AuthService::new(dirs[1].clone()).await.unwrap());
+        for store in [&auth, &creator_auth] {
+            let cookies = HashMap::from([("a1".to_string(), "a".to_string()), ("web_session".to_string(), "s".to_string())]);
+            store.save_credentials(&UserCredentials::new("u1".to_string(), cookies, None)).await.unwrap();
+        }
+
+        let api = XhsApiClient::new(XhsClient::new().unwrap(), auth.clone(), Arc::new(NativeSigner::new()));
+        let (url, webhook) = webhook().await;
+        let (events, _) = broadcast::channel(8);
+        let checker = HealthChecker {
+            api,
+            auth,
+            creator_auth,
+            http: reqwest::Client::new(),
+            webhook_url: Some(url),
+            events,
+        };
+        Fixture { checker, webhook, dirs }
+    }
+
+    async fn session(auth: &AuthService) -> SessionInfo {
+        auth.list_sessions().await.into_iter().find(|s| s.user_id == "u1").unwrap()
+    }
+
+    fn user_me(guest: bool) -> UserMeResponse {
+        serde_json::from_value(serde_json::json!({
+            "code": 0,
+            "success": true,
+            "msg": "成功",
+            "data": { "user_id": "u1", "guest": guest }
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn expired_session_is_invalidated_and_announced() {
+        let f = fixture().await;
+        let mut events = f.checker.subscribe();
+
+        let expired = XhsError::SessionExpired { account: "u1".into(), code: -100, msg: "登录已过期".into() };
+        f.checker.record(SessionScope::Main, "u1", Err(expired)).await;
+
+        let session = session(&f.checker.auth).await;
+        assert!(!session.is_valid);
+        assert_eq!(session.health.unwrap().status, HealthStatus::Expired);
+
+        let event = events.try_recv().unwrap();
+        assert_eq!((event.event, event.scope, event.user_id.as_str()), ("session_expired", SessionScope::Main, "u1"));
+
+        let posted = f.webhook.lock().unwrap().clone();
+        assert_eq!(posted.len(), 1);
+        assert_eq!(posted[0]["event"], "session_expired");
+        assert_eq!(posted[0]["scope"], "main");
+        assert_eq!(posted[0]["user_id"], "u1");
+    }
+
+    #[tokio::test]
+    async fn guest_and_creator_login_codes_count_as_expired() {
+        let f = fixture().await;
+
+        assert!(guest_as_expired("u1", &user_me(false)).is_ok());
+        let guest = guest_as_expired("u1", &user_me(true));
+        assert!(matches!(guest, Err(XhsError::SessionExpired { code: -101, .. })));
+        f.checker.record(SessionScope::Main, "u1", guest).await;
+        assert!(!session(&f.checker.auth).await.is_valid);
+
+        let upstream = XhsError::Upstream { code: -100, msg: "登录已过期".into() };
+        f.checker.record(SessionScope::Creator, "u1", Err(upstream)).await;
+        assert!(!session(&f.checker.creator_auth).await.is_valid);
+
+        let posted = f.webhook.lock().unwrap().clone();
+        let scopes: Vec<_> = posted.iter().map(|e| e["scope"].as_str().unwrap()).collect();
+        assert_eq!(scopes, ["main", "creator"]);
+    }
+
+    #[tokio::test]
+    async fn transport_error_keeps_session_valid_and_silent() {
+        let f = fixture().await;
+        let mut events = f.checker.subscribe();
+
+        let error = reqwest::Client::new().get("not a url").build().unwrap_err();
+        f.checker.record(SessionScope::Main, "u1", Err(XhsError::Transport(error))).await;
+
+        let session = session(&f.checker.auth).await;
+        assert!(session.is_valid);
+        assert_eq!(session.health.unwrap().status, HealthStatus::Error);
+        assert!(events.try_recv().is_err());
+        assert!(f.webhook.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn alive_session_records_health_only() {
+        let f = fixture().await;
+        let mut events = f.checker.subscribe();
+
+        f.checker.record(SessionScope::Main, "u1", Ok(())).await;
+
+        let session = session(&f.checker.auth).await;
+        assert!(session.is_valid);
+        let health = session.health.unwrap();
+        assert_eq!(health.status, HealthStatus::Alive);
+        assert!(health.detail.is_none());
+        assert!(events.try_recv().is_err());
+        assert!(f.webhook.lock().unwrap().is_empty());
+    }
+}
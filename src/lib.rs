@@ -9,7 +9,11 @@ pub mod openapi;   // OpenAPI documentation
 pub mod signature;  // 纯算法签名服务模块
 pub mod agent_manager;  // Python Agent 进程管理
 pub mod config;  // 配置管理 (环境变量)
+pub mod error;  // 统一错误类型
+pub mod health;  // 会话健康检查
+pub mod notification_sync;  // 通知增量同步
 
 pub use client::XhsClient;
-pub use auth::{UserCredentials, CredentialStorage, AuthService};
+pub use error::{XhsError, XhsResult};
+pub use auth::{UserCredentials, CredentialStorage, CredentialStore, AuthService};
 
@@ -1,28 +1,114 @@
 use xhs_rs::server;
 use xhs_rs::agent_manager;
+use xhs_rs::config::{self, Config};
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
 use tracing::{info, warn, error};
 use tracing_subscriber::fmt::time::OffsetTime;
 use time::UtcOffset;
 
+/// XHS Rust Tools Server
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Cli {
+    /// 配置文件路径 (默认 XHS_CONFIG 或 ./xhs.toml)
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+    /// 监听地址
+    #[arg(long, global = true)]
+    host: Option<String>,
+    /// 监听端口
+    #[arg(long, global = true)]
+    port: Option<u16>,
+    /// 外部 Agent 服务 URL (容器模式)
+    #[arg(long, global = true)]
+    agent_url: Option<String>,
+    /// 不启动本地 Agent
+    #[arg(long, global = true)]
+    no_agent: bool,
+    /// 启动本地 Agent 的 Python 解释器
+    #[arg(long, global = true)]
+    python: Option<String>,
+    /// 媒体下载根目录
+    #[arg(long, global = true)]
+    download_root: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// 启动 HTTP 服务 (默认)
+    Serve,
+    /// 配置管理
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum ConfigAction {
+    /// 校验并输出生效的配置 (TOML)
+    Show,
+}
+
+impl Cli {
+    /// 命令行参数覆盖配置文件与环境变量
+    fn apply(&self, config: &mut Config) {
+        if let Some(host) = &self.host {
+            config.server.host = host.clone();
+        }
+        if let Some(port) = self.port {
+            config.server.port = port;
+        }
+        if let Some(url) = &self.agent_url {
+            config.agent.url = Some(url.clone());
+        }
+        if self.no_agent {
+            config.agent.autostart = false;
+        }
+        if let Some(python) = &self.python {
+            config.agent.python = python.clone();
+        }
+        if let Some(root) = &self.download_root {
+            config.download.root = Some(root.clone());
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Load environment variables from .env file
     dotenv::dotenv().ok();
-    
-    // Initialize logging with local timezone
+
+    let cli = Cli::parse();
+
+    // Initialize logging with local timezone (stderr, so `config show` output stays clean)
     let offset = UtcOffset::current_local_offset().unwrap_or(UtcOffset::from_hms(8, 0, 0).unwrap());
     let timer = OffsetTime::new(offset, time::macros::format_description!(
         "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]"
     ));
-    
+
     tracing_subscriber::fmt()
         .with_timer(timer)
+        .with_writer(std::io::stderr)
         .init();
-    
+
+    // Layered configuration: defaults -> file -> env -> CLI
+    let mut settings = Config::load(cli.config.as_deref())?;
+    cli.apply(&mut settings);
+    config::init(settings)?;
+
+    if let Some(Command::Config { action: ConfigAction::Show }) = &cli.command {
+        print!("{}", config::get_config().to_toml()?);
+        return Ok(());
+    }
+
     info!("Starting XHS Rust Tools Server...");
-    
-    // 自动启动 Python Signature Agent (除非设置了 SKIP_LOCAL_AGENT)
-    if std::env::var("SKIP_LOCAL_AGENT").is_err() {
+
+    // 自动启动 Python Signature Agent (除非禁用了 agent.autostart)
+    if config::get_config().agent.autostart {
         info!("Starting Python Signature Agent...");
         match agent_manager::start_agent() {
             Ok(_) => info!("Python Agent started successfully"),
@@ -31,12 +117,12 @@ async fn main() -> anyhow::Result<()> {
             }
         }
     } else {
-        info!("SKIP_LOCAL_AGENT is set, skipping local agent startup.");
+        info!("agent.autostart is disabled, skipping local agent startup.");
     }
-    
+
     // 设置 Ctrl+C 信号处理，确保清理 Agent
     let shutdown = tokio::signal::ctrl_c();
-    
+
     tokio::select! {
         result = server::start_server() => {
             if let Err(e) = result {
@@ -47,10 +133,38 @@ async fn main() -> anyhow::Result<()> {
             info!("Received shutdown signal, cleaning up...");
         }
     }
-    
+
     // 清理 Agent 进程
     agent_manager::stop_agent();
     info!("Server stopped");
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cli_overrides_env_and_file() {
+        let path = std::env::temp_dir().join(format!("xhs-cli-{}.toml", std::process::id()));
+        std::fs::write(&path, "[server]\nhost = \"127.0.0.1\"\nport = 4000\n\n[agent]\npython = \"python3\"\n").unwrap();
+
+        let cli = Cli::parse_from(["xhs-rs", "--config", path.to_str().unwrap(), "--port", "6000", "--no-agent"]);
+        let vars = |name: &str| match name {
+            "PORT" => Some("5000".to_string()),
+            "XHS_HOST" => Some("0.0.0.0".to_string()),
+            _ => None,
+        };
+        let mut settings = Config::load_with_env(cli.config.as_deref(), vars).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        cli.apply(&mut settings);
+
+        assert_eq!(settings.server.port, 6000);
+        assert_eq!(settings.server.host, "0.0.0.0");
+        assert_eq!(settings.agent.python, "python3");
+        assert!(!settings.agent.autostart);
+        settings.validate().unwrap();
+    }
+}
+
@@ -0,0 +1,302 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use utoipa::ToSchema;
+
+/// 评论列表响应 - /api/sns/web/v2/comment/page
+///
+/// 分页规则请参阅 `doc/comment_pagination.md`
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "code": 0,
+    "success": true,
+    "msg": "成功",
+    "data": {
+        "cursor": "696ef72f000000000800de0b",
+        "has_more": true,
+        "time": 1768791234567_i64,
+        "comments": []
+    }
+}))]
+pub struct CommentPageResponse {
+    pub code: i32,
+    pub success: bool,
+    #[serde(default)]
+    pub msg: Option<String>,
+    #[serde(default)]
+    pub data: Option<CommentPageData>,
+}
+
+/// 一页评论
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct CommentPageData {
+    /// 一级评论列表
+    #[serde(default)]
+    pub comments: Vec<Comment>,
+    /// 下一页游标 (传入下次请求的 cursor)
+    #[serde(default)]
+    pub cursor: String,
+    /// 是否有更多评论
+    #[serde(default)]
+    pub has_more: bool,
+    /// 服务端时间戳 (毫秒)
+    #[serde(default)]
+    pub time: Option<i64>,
+    /// 笔记的安全Token
+    #[serde(default)]
+    pub xsec_token: Option<String>,
+    /// 当前登录用户ID
+    #[serde(default)]
+    pub user_id: Option<String>,
+}
+
+/// 回复列表响应 - /api/sns/web/v2/comment/sub/page
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SubCommentPageResponse {
+    pub code: i32,
+    pub success: bool,
+    #[serde(default)]
+    pub msg: Option<String>,
+    #[serde(default)]
+    pub data: Option<SubCommentPageData>,
+}
+
+/// 一页回复
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct SubCommentPageData {
+    /// 回复列表
+    #[serde(default)]
+    pub comments: Vec<SubComment>,
+    /// 下一页游标
+    #[serde(default)]
+    pub cursor: String,
+    /// 是否有更多回复
+    #[serde(default)]
+    pub has_more: bool,
+    /// 服务端时间戳 (毫秒)
+    #[serde(default)]
+    pub time: Option<i64>,
+    /// 笔记的安全Token
+    #[serde(default)]
+    pub xsec_token: Option<String>,
+    /// 当前登录用户ID
+    #[serde(default)]
+    pub user_id: Option<String>,
+}
+
+/// 一级评论
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "id": "696ef72f000000000800de0b",
+    "note_id": "696b611e000000001a0210de",
+    "content": "好看！",
+    "like_count": "12",
+    "liked": false,
+    "create_time": 1768791234000_i64,
+    "ip_location": "广东",
+    "user_info": {},
+    "sub_comment_count": "3",
+    "sub_comment_cursor": "696ef8a1000000000800de11",
+    "sub_comment_has_more": true,
+    "sub_comments": []
+}))]
+pub struct Comment {
+    /// 评论ID
+    pub id: String,
+    /// 所属笔记ID
+    #[serde(default)]
+    pub note_id: Option<String>,
+    /// 评论内容
+    #[serde(default)]
+    pub content: String,
+    /// 点赞数 (XHS 以字符串返回，如 "12"、"1.2万")
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub like_count: String,
+    /// 当前用户是否已点赞
+    #[serde(default)]
+    pub liked: bool,
+    /// 创建时间 (毫秒时间戳)
+    #[serde(default)]
+    pub create_time: i64,
+    /// IP 属地
+    #[serde(default)]
+    pub ip_location: Option<String>,
+    /// 评论状态
+    #[serde(default)]
+    pub status: Option<i32>,
+    /// 评论者信息
+    #[serde(default)]
+    pub user_info: CommentUser,
+    /// @的用户
+    #[serde(default)]
+    pub at_users: Vec<CommentUser>,
+    /// 标签 (如 "is_author")
+    #[serde(default)]
+    pub show_tags: Vec<String>,
+    /// 评论图片
+    #[serde(default)]
+    pub pictures: Vec<CommentPicture>,
+    /// 回复数
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub sub_comment_count: String,
+    /// 回复列表游标 (展开更多回复时使用)
+    #[serde(default)]
+    pub sub_comment_cursor: Option<String>,
+    /// 是否有更多回复
+    #[serde(default)]
+    pub sub_comment_has_more: bool,
+    /// 随一级评论返回的前几条回复
+    #[serde(default)]
+    pub sub_comments: Vec<SubComment>,
+}
+
+/// 二级评论 (回复)
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SubComment {
+    /// 评论ID
+    pub id: String,
+    /// 所属笔记ID
+    #[serde(default)]
+    pub note_id: Option<String>,
+    /// 评论内容
+    #[serde(default)]
+    pub content: String,
+    /// 点赞数
+    #[serde(default, deserialize_with = "string_or_number")]
+    pub like_count: String,
+    /// 当前用户是否已点赞
+    #[serde(default)]
+    pub liked: bool,
+    /// 创建时间 (毫秒时间戳)
+    #[serde(default)]
+    pub create_time: i64,
+    /// IP 属地
+    #[serde(default)]
+    pub ip_location: Option<String>,
+    /// 评论状态
+    #[serde(default)]
+    pub status: Option<i32>,
+    /// 评论者信息
+    #[serde(default)]
+    pub user_info: CommentUser,
+    /// @的用户
+    #[serde(default)]
+    pub at_users: Vec<CommentUser>,
+    /// 标签
+    #[serde(default)]
+    pub show_tags: Vec<String>,
+    /// 评论图片
+    #[serde(default)]
+    pub pictures: Vec<CommentPicture>,
+    /// 被回复的评论
+    #[serde(default)]
+    pub target_comment: Option<TargetComment>,
+}
+
+/// 被回复的评论
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TargetComment {
+    /// 评论ID
+    pub id: String,
+    /// 被回复者信息
+    #[serde(default)]
+    pub user_info: CommentUser,
+}
+
+/// 评论者信息
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "user_id": "664ec6ef0000000007004173",
+    "nickname": "小李学姐爱学习",
+    "image": "https://sns-avatar-qc.xhscdn.com/avatar/...",
+    "xsec_token": "ABgmZhb7UheMUTk-zbKLSjLizyXRfHgBLRwTg3lxgVx_s="
+}))]
+pub struct CommentUser {
+    /// 用户ID
+    #[serde(default)]
+    pub user_id: String,
+    /// 昵称
+    #[serde(default)]
+    pub nickname: String,
+    /// 头像URL
+    #[serde(default)]
+    pub image: Option<String>,
+    /// 安全Token (访问用户主页时使用)
+    #[serde(default)]
+    pub xsec_token: Option<String>,
+}
+
+/// 评论图片
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CommentPicture {
+    #[serde(default)]
+    pub width: Option<i32>,
+    #[serde(default)]
+    pub height: Option<i32>,
+    /// 预览图URL
+    #[serde(default)]
+    pub url_pre: Option<String>,
+    /// 默认图URL
+    #[serde(default)]
+    pub url_default: Option<String>,
+    /// 各场景图片 (WB_PRV / WB_DFT)
+    #[serde(default)]
+    pub info_list: Vec<CommentImageInfo>,
+}
+
+/// 评论图片场景信息
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CommentImageInfo {
+    /// 图片场景
+    #[serde(default)]
+    pub image_scene: Option<String>,
+    /// 图片URL
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+/// 计数字段兼容字符串与数字
+fn string_or_number<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(match serde_json::Value::deserialize(deserializer)? {
+        serde_json::Value::String(s) => s,
+        serde_json::Value::Number(n) => n.to_string(),
+        _ => String::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::decode;
+
+    #[test]
+    fn decodes_comment_page_with_replies() {
+        let response: CommentPageResponse = decode(include_str!("../../tests/fixtures/comment_page.json")).unwrap();
+        let data = response.data.unwrap();
+
+        assert_eq!(data.cursor, "696ef72f000000000800de0b");
+        assert!(data.has_more);
+        assert_eq!(data.time, Some(1768791234567));
+        assert_eq!(data.comments.len(), 2);
+
+        let comment = &data.comments[0];
+        assert_eq!(comment.like_count, "1.2万");
+        assert_eq!(comment.sub_comment_count, "3");
+        assert_eq!(comment.user_info.nickname, "小李学姐爱学习");
+        assert_eq!(comment.at_users[0].nickname, "旅行日记");
+        assert_eq!(comment.pictures[0].info_list.len(), 2);
+        assert!(comment.sub_comment_has_more);
+
+        let reply = &comment.sub_comments[0];
+        assert_eq!(reply.like_count, "7");
+        assert!(reply.liked);
+        assert_eq!(reply.show_tags, ["is_author"]);
+        assert!(reply.user_info.xsec_token.is_none());
+        assert_eq!(reply.target_comment.as_ref().unwrap().id, comment.id);
+
+        let comment = &data.comments[1];
+        assert_eq!(comment.like_count, "0");
+        assert_eq!(comment.sub_comment_count, "0");
+        assert!(comment.ip_location.is_none());
+        assert!(comment.at_users.is_empty());
+        assert!(comment.sub_comments.is_empty());
+    }
+}
@@ -263,3 +263,34 @@ pub struct VideoCapa {
     #[serde(default)]
     pub duration: Option<i32>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::decode;
+
+    #[test]
+    fn decodes_homefeed_page() {
+        let response: HomefeedResponse = decode(include_str!("../../../tests/fixtures/homefeed.json")).unwrap();
+        let data = response.data.unwrap();
+
+        assert_eq!(data.cursor_score.as_deref(), Some("1.7607001234560024E9"));
+        assert_eq!(data.items.len(), 2);
+
+        let item = &data.items[0];
+        assert_eq!(item.id, "69539b19000000002202c106");
+        assert_eq!(item.ignore, Some(false));
+        let card = item.note_card.as_ref().unwrap();
+        assert_eq!(card.note_type.as_deref(), Some("normal"));
+        assert!(card.video.is_none());
+        let cover = card.cover.as_ref().unwrap();
+        assert_eq!((cover.width, cover.height), (Some(1080), Some(1440)));
+        assert_eq!(cover.info_list[1].image_scene.as_deref(), Some("WB_DFT"));
+
+        let card = data.items[1].note_card.as_ref().unwrap();
+        assert_eq!(card.note_type.as_deref(), Some("video"));
+        assert_eq!(card.user.as_ref().unwrap().xsec_token, None);
+        assert_eq!(card.interact_info.as_ref().unwrap().liked_count.as_deref(), Some("3.4万"));
+        assert_eq!(card.video.as_ref().unwrap().capa.as_ref().unwrap().duration, Some(312));
+    }
+}
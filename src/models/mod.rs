@@ -1,4 +1,7 @@
+pub mod comment;
 pub mod feed;
 pub mod login;
+pub mod note;
+pub mod notification;
 pub mod search;
 pub mod user;
@@ -0,0 +1,354 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use utoipa::ToSchema;
+
+use super::feed::{CoverImageInfo, NoteUser, VideoCapa};
+use crate::error::{XhsError, XhsResult};
+
+/// 笔记详情响应 - /api/sns/web/v1/feed
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NoteDetailResponse {
+    pub code: i32,
+    pub success: bool,
+    #[serde(default)]
+    pub msg: Option<String>,
+    #[serde(default)]
+    pub data: Option<NoteDetailData>,
+}
+
+impl NoteDetailResponse {
+    /// 取出笔记详情（响应中的第一条 `note_card`）
+    ///
+    /// 没有 `note_card` 时返回解析错误，错误中带有响应内容
+    pub fn into_note(self) -> XhsResult<NoteDetail> {
+        let body = serde_json::to_string(&self).unwrap_or_default();
+        let item = self.data.and_then(|data| data.items.into_iter().next());
+        let Some((id, Some(mut note))) = item.map(|item| (item.id, item.note_card)) else {
+            return Err(XhsError::decode("No note_card found in response", &body));
+        };
+        if note.note_id.is_empty() {
+            note.note_id = id;
+        }
+        Ok(note)
+    }
+}
+
+/// 笔记详情数据
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NoteDetailData {
+    /// 笔记列表 (通常只有一条)
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub items: Vec<NoteDetailItem>,
+    /// 游标
+    #[serde(default)]
+    pub cursor_score: Option<String>,
+    /// 服务端时间戳 (毫秒)
+    #[serde(default)]
+    pub current_time: Option<i64>,
+}
+
+/// 笔记详情条目
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NoteDetailItem {
+    /// 笔记ID
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub id: String,
+    /// 模型类型 (note)
+    #[serde(default)]
+    pub model_type: Option<String>,
+    /// 笔记内容
+    #[serde(default)]
+    pub note_card: Option<NoteDetail>,
+}
+
+/// 笔记详情
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "note_id": "6965aba6000000000e03c2a2",
+    "type": "normal",
+    "title": "男生没方向，一定要去闯闯的6个职业！",
+    "desc": "#职业规划[话题]#",
+    "user": {},
+    "tag_list": [{"id": "5bd2d6c4c6a5bc0001b5d3b1", "name": "职业规划", "type": "topic"}],
+    "at_user_list": [],
+    "image_list": [],
+    "interact_info": {"liked_count": "1.2万", "collected_count": "3456", "comment_count": "321", "share_count": "89"},
+    "ip_location": "广东",
+    "time": 1768791234000_i64,
+    "last_update_time": 1768791234000_i64
+}))]
+pub struct NoteDetail {
+    /// 笔记ID
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub note_id: String,
+    /// 笔记类型 (normal=图文, video=视频)
+    #[serde(rename = "type", default, deserialize_with = "null_as_default")]
+    pub note_type: String,
+    /// 标题
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub title: String,
+    /// 正文
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub desc: String,
+    /// 作者信息
+    #[serde(default)]
+    pub user: Option<NoteUser>,
+    /// 话题标签
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub tag_list: Vec<NoteTag>,
+    /// 正文中@的用户
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub at_user_list: Vec<NoteAtUser>,
+    /// 图片列表 (视频笔记为封面)
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub image_list: Vec<NoteImage>,
+    /// 视频信息 (视频笔记才有)
+    #[serde(default)]
+    pub video: Option<NoteDetailVideo>,
+    /// 互动信息
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub interact_info: NoteInteractInfo,
+    /// IP 属地
+    #[serde(default)]
+    pub ip_location: Option<String>,
+    /// 发布时间 (毫秒时间戳)
+    #[serde(default)]
+    pub time: Option<i64>,
+    /// 最后更新时间 (毫秒时间戳)
+    #[serde(default)]
+    pub last_update_time: Option<i64>,
+    /// 安全Token
+    #[serde(default)]
+    pub xsec_token: Option<String>,
+}
+
+impl NoteDetail {
+    /// 是否为视频笔记
+    pub fn is_video(&self) -> bool {
+        self.note_type == "video"
+    }
+
+    /// 作者昵称
+    pub fn author(&self) -> String {
+        self.user
+            .as_ref()
+            .and_then(|u| u.nickname.clone().or_else(|| u.nick_name.clone()))
+            .unwrap_or_default()
+    }
+}
+
+/// 话题标签
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NoteTag {
+    /// 标签ID
+    #[serde(default)]
+    pub id: Option<String>,
+    /// 标签名称
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub name: String,
+    /// 标签类型 (topic, location, ...)
+    #[serde(rename = "type", default)]
+    pub tag_type: Option<String>,
+}
+
+/// 正文中@的用户
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NoteAtUser {
+    /// 用户ID
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub user_id: String,
+    /// 昵称
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub nickname: String,
+    /// 安全Token
+    #[serde(default)]
+    pub xsec_token: Option<String>,
+}
+
+/// 笔记图片
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NoteImage {
+    /// 宽度
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub width: i32,
+    /// 高度
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub height: i32,
+    /// 预览图URL (无水印, WB_PRV)
+    #[serde(default)]
+    pub url_pre: Option<String>,
+    /// 默认图URL (有水印, WB_DFT)
+    #[serde(default)]
+    pub url_default: Option<String>,
+    /// 各场景图片
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub info_list: Vec<CoverImageInfo>,
+    /// 是否为实况图
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub live_photo: bool,
+}
+
+impl NoteImage {
+    /// 指定场景的图片URL：优先直接字段，其次 `info_list`
+    fn scene_url(&self, direct: &Option<String>, scene: &str) -> Option<String> {
+        direct.clone().filter(|s| !s.is_empty()).or_else(|| {
+            self.info_list
+                .iter()
+                .find(|info| info.image_scene.as_deref() == Some(scene))
+                .and_then(|info| info.url.clone())
+        })
+    }
+
+    /// 无水印图片URL (url_pre / WB_PRV)
+    pub fn url_original(&self) -> Option<String> {
+        self.scene_url(&self.url_pre, "WB_PRV")
+    }
+
+    /// 有水印图片URL (url_default / WB_DFT)
+    pub fn url_watermark(&self) -> Option<String> {
+        self.scene_url(&self.url_default, "WB_DFT")
+    }
+}
+
+/// 视频信息
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct NoteDetailVideo {
+    /// 视频能力信息 (时长等)
+    #[serde(default)]
+    pub capa: Option<VideoCapa>,
+    /// 媒体流
+    #[serde(default)]
+    pub media: Option<VideoMedia>,
+}
+
+/// 视频媒体
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct VideoMedia {
+    /// 各编码的视频流
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub stream: VideoStreams,
+}
+
+/// 各编码的视频流
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct VideoStreams {
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub h264: Vec<VideoStream>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub h265: Vec<VideoStream>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub h266: Vec<VideoStream>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub av1: Vec<VideoStream>,
+}
+
+/// 单个视频流
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct VideoStream {
+    /// 主下载 URL
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub master_url: String,
+    /// 备用下载 URL
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub backup_urls: Vec<String>,
+    /// 宽度
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub width: i32,
+    /// 高度
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub height: i32,
+    /// 文件大小 (bytes)
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub size: i64,
+    /// 时长 (ms)
+    #[serde(default)]
+    pub duration: Option<i64>,
+    /// 编码 (h264, h265)
+    #[serde(default)]
+    pub video_codec: Option<String>,
+    /// 平均码率
+    #[serde(default)]
+    pub avg_bitrate: Option<i64>,
+    /// 流描述
+    #[serde(default)]
+    pub stream_desc: Option<String>,
+}
+
+/// 笔记互动信息
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct NoteInteractInfo {
+    /// 当前用户是否已点赞
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub liked: bool,
+    /// 点赞数
+    #[serde(default)]
+    pub liked_count: Option<String>,
+    /// 当前用户是否已收藏
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub collected: bool,
+    /// 收藏数
+    #[serde(default)]
+    pub collected_count: Option<String>,
+    /// 评论数
+    #[serde(default)]
+    pub comment_count: Option<String>,
+    /// 分享数
+    #[serde(default)]
+    pub share_count: Option<String>,
+    /// 当前用户是否已关注作者
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub followed: bool,
+    /// 与作者的关系
+    #[serde(default)]
+    pub relation: Option<String>,
+}
+
+/// XHS 偶尔返回 `null`，按缺省值处理
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::decode;
+
+    #[test]
+    fn decodes_video_note_with_null_fields() {
+        let response: NoteDetailResponse = decode(include_str!("../../tests/fixtures/note_detail_video.json")).unwrap();
+        let note = response.into_note().unwrap();
+
+        assert_eq!(note.note_id, "68e4d7a20000000004013c5d");
+        assert!(note.is_video());
+        assert_eq!(note.desc, "");
+        assert!(note.tag_list.is_empty());
+        assert_eq!(note.author(), "烘焙小白");
+        assert!(!note.interact_info.liked);
+        assert_eq!(note.interact_info.liked_count.as_deref(), Some("1.2万"));
+
+        let image = &note.image_list[0];
+        assert_eq!((image.width, image.height), (0, 1440));
+        assert!(!image.live_photo);
+        assert!(image.url_original().is_some());
+
+        let streams = &note.video.as_ref().unwrap().media.as_ref().unwrap().stream;
+        assert_eq!(streams.h264[0].size, 10485760);
+        assert!(streams.h264[0].backup_urls.is_empty());
+        assert_eq!(streams.h265[0].master_url, "");
+        assert!(streams.h266.is_empty());
+    }
+
+    #[test]
+    fn missing_note_card_keeps_response_in_error() {
+        let response: NoteDetailResponse =
+            decode(r#"{"code": 0, "success": true, "msg": "成功", "data": {"items": [{"id": "68e4d7a2", "note_card": null}]}}"#)
+                .unwrap();
+
+        let Err(XhsError::Decode { body, .. }) = response.into_note() else { panic!("expected a decode error") };
+        assert!(body.contains("68e4d7a2"), "{}", body);
+    }
+}
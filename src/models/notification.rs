@@ -0,0 +1,364 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use utoipa::ToSchema;
+
+/// 通知类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationType {
+    /// 赞和收藏
+    Likes,
+    /// 评论和@
+    Mentions,
+    /// 新增关注
+    Connections,
+}
+
+/// 通知消息 - 赞和收藏 / 评论和@ / 新增关注
+///
+/// 按 XHS 的 `type` 字段区分（如 `liked/item`、`comment/comment`、`mention/comment`）。
+/// 无法识别的类型或缺少必要字段的消息解析为 [`NotificationMessage::Unknown`]，保留原始 JSON。
+/// 序列化后的结构（按 `kind` 区分）也可以原样读回
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum NotificationMessage {
+    /// 赞了你的笔记
+    NoteLiked { id: String, time: i64, user: NotificationUser, note: NoteRef },
+    /// 收藏了你的笔记
+    NoteCollected { id: String, time: i64, user: NotificationUser, note: NoteRef },
+    /// 赞了你的评论
+    CommentLiked { id: String, time: i64, user: NotificationUser, note: NoteRef, comment: CommentRef },
+    /// 评论了你的笔记
+    NoteCommented { id: String, time: i64, user: NotificationUser, note: NoteRef, comment: CommentRef },
+    /// 回复了你的评论
+    CommentReplied { id: String, time: i64, user: NotificationUser, note: NoteRef, comment: CommentRef },
+    /// 在评论中@了你
+    MentionedInComment { id: String, time: i64, user: NotificationUser, note: NoteRef, comment: CommentRef },
+    /// 在笔记中@了你
+    MentionedInNote { id: String, time: i64, user: NotificationUser, note: NoteRef },
+    /// 开始关注你了
+    NewFollower { id: String, time: i64, user: NotificationUser },
+    /// 未识别的消息
+    Unknown {
+        id: String,
+        time: i64,
+        /// XHS 原始类型
+        message_type: String,
+        /// 原始消息
+        raw: serde_json::Value,
+    },
+}
+
+impl NotificationMessage {
+    /// 消息 ID
+    pub fn id(&self) -> &str {
+        match self {
+            Self::NoteLiked { id, .. }
+            | Self::NoteCollected { id, .. }
+            | Self::CommentLiked { id, .. }
+            | Self::NoteCommented { id, .. }
+            | Self::CommentReplied { id, .. }
+            | Self::MentionedInComment { id, .. }
+            | Self::MentionedInNote { id, .. }
+            | Self::NewFollower { id, .. }
+            | Self::Unknown { id, .. } => id,
+        }
+    }
+
+    /// 消息时间 (秒时间戳)
+    pub fn time(&self) -> i64 {
+        match self {
+            Self::NoteLiked { time, .. }
+            | Self::NoteCollected { time, .. }
+            | Self::CommentLiked { time, .. }
+            | Self::NoteCommented { time, .. }
+            | Self::CommentReplied { time, .. }
+            | Self::MentionedInComment { time, .. }
+            | Self::MentionedInNote { time, .. }
+            | Self::NewFollower { time, .. }
+            | Self::Unknown { time, .. } => *time,
+        }
+    }
+
+    /// 触发通知的用户（未识别的消息为 None）
+    pub fn user(&self) -> Option<&NotificationUser> {
+        match self {
+            Self::NoteLiked { user, .. }
+            | Self::NoteCollected { user, .. }
+            | Self::CommentLiked { user, .. }
+            | Self::NoteCommented { user, .. }
+            | Self::CommentReplied { user, .. }
+            | Self::MentionedInComment { user, .. }
+            | Self::MentionedInNote { user, .. }
+            | Self::NewFollower { user, .. } => Some(user),
+            Self::Unknown { .. } => None,
+        }
+    }
+
+    /// 相关笔记
+    pub fn note(&self) -> Option<&NoteRef> {
+        match self {
+            Self::NoteLiked { note, .. }
+            | Self::NoteCollected { note, .. }
+            | Self::CommentLiked { note, .. }
+            | Self::NoteCommented { note, .. }
+            | Self::CommentReplied { note, .. }
+            | Self::MentionedInComment { note, .. }
+            | Self::MentionedInNote { note, .. } => Some(note),
+            Self::NewFollower { .. } | Self::Unknown { .. } => None,
+        }
+    }
+
+    /// 相关评论
+    pub fn comment(&self) -> Option<&CommentRef> {
+        match self {
+            Self::CommentLiked { comment, .. }
+            | Self::NoteCommented { comment, .. }
+            | Self::CommentReplied { comment, .. }
+            | Self::MentionedInComment { comment, .. } => Some(comment),
+            _ => None,
+        }
+    }
+}
+
+/// 通知中的用户
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct NotificationUser {
+    /// 用户ID
+    #[serde(default, alias = "userid")]
+    pub user_id: String,
+    /// 昵称
+    #[serde(default)]
+    pub nickname: String,
+    /// 头像URL
+    #[serde(default, alias = "images")]
+    pub image: Option<String>,
+    /// 安全Token (访问用户主页时使用)
+    #[serde(default)]
+    pub xsec_token: Option<String>,
+    /// 关注状态 (新增关注通知)
+    #[serde(default)]
+    pub fstatus: Option<String>,
+}
+
+/// 通知中的笔记
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct NoteRef {
+    /// 笔记ID
+    #[serde(default, alias = "id")]
+    pub note_id: String,
+    /// 笔记内容摘要
+    #[serde(default)]
+    pub content: Option<String>,
+    /// 封面图URL
+    #[serde(default)]
+    pub image: Option<String>,
+    /// 安全Token (打开笔记详情时使用)
+    #[serde(default)]
+    pub xsec_token: Option<String>,
+}
+
+/// 通知中的评论
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct CommentRef {
+    /// 评论ID
+    #[serde(default)]
+    pub id: String,
+    /// 评论内容
+    #[serde(default)]
+    pub content: String,
+    /// 被回复的评论 (回复通知)
+    #[serde(default)]
+    #[schema(no_recursion)]
+    pub target_comment: Option<Box<CommentRef>>,
+}
+
+/// XHS 原始消息结构
+#[derive(Deserialize)]
+struct RawMessage {
+    #[serde(default, deserialize_with = "id_string")]
+    id: String,
+    #[serde(default, rename = "type")]
+    message_type: String,
+    #[serde(default)]
+    time: i64,
+    #[serde(default, alias = "user")]
+    user_info: Option<NotificationUser>,
+    #[serde(default)]
+    item_info: Option<NoteRef>,
+    #[serde(default)]
+    comment_info: Option<CommentRef>,
+}
+
+/// 序列化后的消息结构（`kind` 标签）
+#[derive(Deserialize)]
+struct TaggedMessage {
+    kind: String,
+    #[serde(default, deserialize_with = "id_string")]
+    id: String,
+    #[serde(default)]
+    time: i64,
+    #[serde(default)]
+    user: Option<NotificationUser>,
+    #[serde(default)]
+    note: Option<NoteRef>,
+    #[serde(default)]
+    comment: Option<CommentRef>,
+    #[serde(default)]
+    message_type: String,
+    #[serde(default)]
+    raw: serde_json::Value,
+}
+
+impl TaggedMessage {
+    fn into_message(self) -> Option<NotificationMessage> {
+        use NotificationMessage::*;
+
+        let TaggedMessage { kind, id, time, user, note, comment, message_type, raw } = self;
+        let message = match (kind.as_str(), user, note, comment) {
+            ("note_liked", Some(user), Some(note), _) => NoteLiked { id, time, user, note },
+            ("note_collected", Some(user), Some(note), _) => NoteCollected { id, time, user, note },
+            ("comment_liked", Some(user), Some(note), Some(comment)) => CommentLiked { id, time, user, note, comment },
+            ("note_commented", Some(user), Some(note), Some(comment)) => NoteCommented { id, time, user, note, comment },
+            ("comment_replied", Some(user), Some(note), Some(comment)) => CommentReplied { id, time, user, note, comment },
+            ("mentioned_in_comment", Some(user), Some(note), Some(comment)) => {
+                MentionedInComment { id, time, user, note, comment }
+            }
+            ("mentioned_in_note", Some(user), Some(note), _) => MentionedInNote { id, time, user, note },
+            ("new_follower", Some(user), _, _) => NewFollower { id, time, user },
+            ("unknown", ..) => Unknown { id, time, message_type, raw },
+            _ => return None,
+        };
+        Some(message)
+    }
+}
+
+impl<'de> Deserialize<'de> for NotificationMessage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = serde_json::Value::deserialize(deserializer)?;
+        if raw.get("kind").is_some_and(serde_json::Value::is_string) {
+            let tagged = TaggedMessage::deserialize(&raw).map_err(serde::de::Error::custom)?;
+            let kind = tagged.kind.clone();
+            return tagged
+                .into_message()
+                .ok_or_else(|| serde::de::Error::custom(format!("invalid notification message of kind '{}'", kind)));
+        }
+
+        let Ok(message) = RawMessage::deserialize(&raw) else {
+            return Ok(Self::unknown(&raw, String::new()));
+        };
+
+        let RawMessage { id, message_type, time, user_info, item_info, comment_info } = message;
+        let kind = message_type.as_str();
+        let parsed = match (user_info, item_info, comment_info) {
+            (Some(user), _, _) if kind.starts_with("follow") => Some(Self::NewFollower { id, time, user }),
+            (Some(user), Some(note), Some(comment)) => match kind {
+                "liked/comment" | "like/comment" => Some(Self::CommentLiked { id, time, user, note, comment }),
+                "comment/item" | "comment" => Some(Self::NoteCommented { id, time, user, note, comment }),
+                "comment/comment" => Some(Self::CommentReplied { id, time, user, note, comment }),
+                "mention/comment" => Some(Self::MentionedInComment { id, time, user, note, comment }),
+                _ => None,
+            },
+            (Some(user), Some(note), None) => match kind {
+                "liked/item" | "like/item" | "like" | "liked" => Some(Self::NoteLiked { id, time, user, note }),
+                "faved/item" | "collected/item" | "collect/item" | "faved" | "collect" => {
+                    Some(Self::NoteCollected { id, time, user, note })
+                }
+                "mention/item" | "mention" => Some(Self::MentionedInNote { id, time, user, note }),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        Ok(parsed.unwrap_or_else(|| Self::unknown(&raw, message_type)))
+    }
+}
+
+impl NotificationMessage {
+    fn unknown(raw: &serde_json::Value, message_type: String) -> Self {
+        Self::Unknown {
+            id: id_of(&raw["id"]),
+            time: raw["time"].as_i64().unwrap_or_default(),
+            message_type,
+            raw: raw.clone(),
+        }
+    }
+}
+
+/// 消息 ID 兼容字符串与数字
+fn id_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(id_of(&serde_json::Value::deserialize(deserializer)?))
+}
+
+fn id_of(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Number(n) => n.to_string(),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message_list(response: &str) -> Vec<NotificationMessage> {
+        let response: serde_json::Value = serde_json::from_str(response).unwrap();
+        serde_json::from_value(response["data"]["message_list"].clone()).unwrap()
+    }
+
+    fn kinds(messages: &[NotificationMessage]) -> Vec<String> {
+        messages.iter().map(|m| serde_json::to_value(m).unwrap()["kind"].as_str().unwrap().to_string()).collect()
+    }
+
+    /// 序列化后再读回，结果与原消息一致
+    fn assert_round_trip(messages: &[NotificationMessage]) {
+        let serialized = serde_json::to_value(messages).unwrap();
+        let restored: Vec<NotificationMessage> = serde_json::from_value(serialized.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&restored).unwrap(), serialized);
+    }
+
+    #[test]
+    fn mentions_payload_round_trips() {
+        let messages = message_list(include_str!("../../tests/fixtures/notifications/mentions.json"));
+        assert_eq!(kinds(&messages), ["note_commented", "comment_replied", "mentioned_in_comment"]);
+
+        let NotificationMessage::CommentReplied { user, note, comment, .. } = &messages[1] else { unreachable!() };
+        assert_eq!(user.user_id, "63a7e4f1000000002602b9c8");
+        assert_eq!(note.note_id, "68e4d7a20000000004013c5d");
+        assert_eq!(comment.target_comment.as_ref().unwrap().content, "蛋白一定要打到硬性发泡");
+
+        assert_round_trip(&messages);
+    }
+
+    #[test]
+    fn likes_payload_round_trips() {
+        let messages = message_list(include_str!("../../tests/fixtures/notifications/likes.json"));
+        assert_eq!(kinds(&messages), ["note_liked", "note_collected", "comment_liked"]);
+        assert_eq!(messages[0].time(), 1760615262);
+        assert_eq!(messages[2].comment().unwrap().content, "冰糖炒色是关键");
+
+        assert_round_trip(&messages);
+    }
+
+    #[test]
+    fn connections_payload_round_trips() {
+        let messages = message_list(include_str!("../../tests/fixtures/notifications/connections.json"));
+        assert_eq!(kinds(&messages), ["new_follower", "new_follower", "unknown"]);
+        assert_eq!(messages[0].id(), "7562702245881905189");
+        let user = messages[0].user().unwrap();
+        assert_eq!(user.image.as_deref(), Some("https://sns-avatar-qc.xhscdn.com/avatar/65b3c2d1.jpg"));
+        assert_eq!(user.fstatus.as_deref(), Some("fans"));
+
+        let NotificationMessage::Unknown { message_type, raw, .. } = &messages[2] else { unreachable!() };
+        assert_eq!(message_type, "system/notice");
+        assert_eq!(raw["title"], "你的笔记入选了周榜");
+
+        assert_round_trip(&messages);
+    }
+
+    #[test]
+    fn rejects_tagged_message_missing_fields() {
+        let message = serde_json::json!({ "kind": "note_liked", "id": "1", "time": 1, "user": {} });
+        assert!(serde_json::from_value::<NotificationMessage>(message).is_err());
+    }
+}
@@ -289,3 +289,53 @@ pub struct SearchUserItem {
     pub red_id: Option<String>,
     pub link: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::decode;
+
+    #[test]
+    fn decodes_search_notes_with_non_note_items() {
+        let response: SearchNotesResponse = decode(include_str!("../../tests/fixtures/search_notes.json")).unwrap();
+        let data = response.data.unwrap();
+
+        assert_eq!(data.search_id.as_deref(), Some("2f3kq8x1c0mwzb4j5v7ht"));
+        assert!(data.has_more);
+        assert_eq!(data.items.len(), 3);
+
+        let note = data.items[0].note_card.as_ref().unwrap();
+        assert_eq!(note.note_type.as_deref(), Some("normal"));
+        assert_eq!(note.user.as_ref().unwrap().nickname.as_deref(), Some("咖啡地图"));
+        assert_eq!(note.cover.as_ref().unwrap().info_list.len(), 2);
+        assert_eq!(note.interact_info.as_ref().unwrap().liked_count.as_deref(), Some("2341"));
+
+        let hot_query = &data.items[1];
+        assert_eq!(hot_query.model_type.as_deref(), Some("hot_query"));
+        assert!(hot_query.note_card.is_none());
+
+        let video = data.items[2].note_card.as_ref().unwrap();
+        assert_eq!(video.video.as_ref().unwrap().capa.as_ref().unwrap().duration, Some(185));
+        assert!(video.user.as_ref().unwrap().nickname.is_none());
+    }
+
+    #[test]
+    fn decodes_search_users() {
+        let response: SearchUserResponse = decode(include_str!("../../tests/fixtures/search_user.json")).unwrap();
+        let data = response.data.unwrap();
+
+        assert!(data.has_more);
+        assert_eq!(data.users.len(), 2);
+
+        let user = &data.users[0];
+        assert_eq!(user.id, "5b6c7d8e000000001000a1b2");
+        assert_eq!(user.fan_count.as_deref(), Some("12.3万"));
+        assert_eq!(user.note_count, Some(486));
+        assert_eq!(user.red_id.as_deref(), Some("coffeemap"));
+
+        let user = &data.users[1];
+        assert!(user.image.is_none());
+        assert!(user.desc.is_none());
+        assert!(user.link.is_none());
+    }
+}
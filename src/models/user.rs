@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
+use super::feed::{NoteCover, NoteUser};
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[schema(example = json!({
     "code": 0,
@@ -45,3 +47,367 @@ pub struct UserInfo {
     pub images: Option<String>,
     pub imageb: Option<String>,
 }
+
+// ============================================================================
+// User Profile (他人主页)
+// ============================================================================
+
+/// 用户主页响应 - /api/sns/web/v1/user/otherinfo
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UserProfileResponse {
+    pub code: i32,
+    pub success: bool,
+    #[serde(default)]
+    pub msg: Option<String>,
+    #[serde(default)]
+    pub data: Option<UserProfile>,
+}
+
+/// 用户主页信息
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "basic_info": {
+        "nickname": "小李学姐爱学习",
+        "red_id": "123456789",
+        "desc": "分享职场干货",
+        "gender": 1,
+        "ip_location": "广东",
+        "images": "https://sns-avatar-qc.xhscdn.com/avatar/xxxxxxxx"
+    },
+    "interactions": [
+        {"type": "follows", "name": "关注", "count": "12"},
+        {"type": "fans", "name": "粉丝", "count": "3.4万"},
+        {"type": "interaction", "name": "获赞与收藏", "count": "56.7万"}
+    ],
+    "tags": [{"tagType": "location", "name": "广东深圳"}]
+}))]
+pub struct UserProfile {
+    /// 基本信息
+    #[serde(default)]
+    pub basic_info: UserBasicInfo,
+    /// 关注 / 粉丝 / 获赞与收藏
+    #[serde(default)]
+    pub interactions: Vec<UserInteraction>,
+    /// 资料标签 (性别、地区、职业等)
+    #[serde(default)]
+    pub tags: Vec<UserTag>,
+    /// 与当前用户的关系
+    #[serde(default)]
+    pub extra_info: Option<UserExtraInfo>,
+}
+
+impl UserProfile {
+    /// 按类型查找计数 (follows / fans / interaction)
+    fn interaction(&self, kind: &str) -> Option<&str> {
+        self.interactions
+            .iter()
+            .find(|i| i.interaction_type == kind)
+            .map(|i| i.count.as_str())
+    }
+
+    /// 关注数
+    pub fn following_count(&self) -> Option<&str> {
+        self.interaction("follows")
+    }
+
+    /// 粉丝数
+    pub fn follower_count(&self) -> Option<&str> {
+        self.interaction("fans")
+    }
+
+    /// 获赞与收藏数
+    pub fn liked_and_collected_count(&self) -> Option<&str> {
+        self.interaction("interaction")
+    }
+}
+
+/// 用户基本信息
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct UserBasicInfo {
+    /// 昵称
+    #[serde(default)]
+    pub nickname: String,
+    /// 小红书号
+    #[serde(default)]
+    pub red_id: Option<String>,
+    /// 简介
+    #[serde(default)]
+    pub desc: Option<String>,
+    /// 性别 (0=男, 1=女, 2=未知)
+    #[serde(default)]
+    pub gender: Option<i32>,
+    /// IP 属地
+    #[serde(default)]
+    pub ip_location: Option<String>,
+    /// 头像 (小图)
+    #[serde(default)]
+    pub images: Option<String>,
+    /// 头像 (大图)
+    #[serde(default)]
+    pub imageb: Option<String>,
+}
+
+/// 互动计数
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UserInteraction {
+    /// 类型 (follows=关注, fans=粉丝, interaction=获赞与收藏)
+    #[serde(rename = "type", default)]
+    pub interaction_type: String,
+    /// 展示名称
+    #[serde(default)]
+    pub name: Option<String>,
+    /// 计数 (XHS 以字符串返回，如 "3.4万")
+    #[serde(default)]
+    pub count: String,
+}
+
+/// 资料标签
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UserTag {
+    /// 标签类型 (gender, location, profession, info)
+    #[serde(rename = "tagType", default)]
+    pub tag_type: Option<String>,
+    /// 标签内容
+    #[serde(default)]
+    pub name: Option<String>,
+    /// 图标
+    #[serde(default)]
+    pub icon: Option<String>,
+}
+
+/// 与当前用户的关系
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UserExtraInfo {
+    /// 关注状态 (none, follows, fans, both)
+    #[serde(default)]
+    pub fstatus: Option<String>,
+    /// 拉黑状态
+    #[serde(rename = "blockType", default)]
+    pub block_type: Option<String>,
+}
+
+// ============================================================================
+// User Notes (用户笔记 / 收藏 / 点赞 / 专辑笔记列表)
+// ============================================================================
+
+/// 用户笔记列表响应
+///
+/// 发布 (`user_posted`)、收藏 (`note/collect/page`)、点赞 (`note/like/page`) 和专辑 (`board/note`) 共用此结构
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UserNotesResponse {
+    pub code: i32,
+    pub success: bool,
+    #[serde(default)]
+    pub msg: Option<String>,
+    #[serde(default)]
+    pub data: Option<UserNotesData>,
+}
+
+/// 一页用户笔记
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct UserNotesData {
+    /// 笔记列表
+    #[serde(default)]
+    pub notes: Vec<UserNote>,
+    /// 下一页游标 (传入下次请求的 cursor)
+    #[serde(default)]
+    pub cursor: String,
+    /// 是否有更多笔记
+    #[serde(default)]
+    pub has_more: bool,
+}
+
+/// 用户笔记卡片
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "note_id": "6965aba6000000000e03c2a2",
+    "type": "normal",
+    "display_title": "男生没方向，一定要去闯闯的6个职业！",
+    "xsec_token": "ABgmZhb7UheMUTk-zbKLSjLizyXRfHgBLRwTg3lxgVx_s=",
+    "user": {},
+    "cover": {},
+    "interact_info": {"liked": false, "liked_count": "1008", "sticky": false}
+}))]
+pub struct UserNote {
+    /// 笔记ID
+    pub note_id: String,
+    /// 笔记类型 (normal, video)
+    #[serde(rename = "type", default)]
+    pub note_type: Option<String>,
+    /// 展示标题
+    #[serde(default)]
+    pub display_title: Option<String>,
+    /// 安全Token (打开笔记详情时使用)
+    #[serde(default)]
+    pub xsec_token: Option<String>,
+    /// 作者信息
+    #[serde(default)]
+    pub user: Option<NoteUser>,
+    /// 封面信息
+    #[serde(default)]
+    pub cover: Option<NoteCover>,
+    /// 互动信息
+    #[serde(default)]
+    pub interact_info: Option<UserNoteInteractInfo>,
+}
+
+/// 用户笔记互动信息
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct UserNoteInteractInfo {
+    /// 是否已点赞
+    #[serde(default)]
+    pub liked: Option<bool>,
+    /// 点赞数
+    #[serde(default)]
+    pub liked_count: Option<String>,
+    /// 是否置顶
+    #[serde(default)]
+    pub sticky: Option<bool>,
+}
+
+// ============================================================================
+// Boards (收藏专辑)
+// ============================================================================
+
+/// 收藏专辑列表响应 - /api/sns/web/v1/board/user
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BoardListResponse {
+    pub code: i32,
+    pub success: bool,
+    #[serde(default)]
+    pub msg: Option<String>,
+    #[serde(default)]
+    pub data: Option<BoardListData>,
+}
+
+/// 一页收藏专辑
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct BoardListData {
+    /// 专辑列表
+    #[serde(default)]
+    pub boards: Vec<Board>,
+    /// 是否有更多专辑 (翻页使用 page + 1)
+    #[serde(default)]
+    pub has_more: bool,
+}
+
+/// 收藏专辑
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "id": "65a1b2c3000000001a0210de",
+    "name": "职场干货",
+    "desc": "",
+    "total": 42,
+    "privacy": 0,
+    "fans": 3,
+    "images": ["https://sns-webpic-qc.xhscdn.com/..."]
+}))]
+pub struct Board {
+    /// 专辑ID (获取专辑笔记时使用)
+    pub id: String,
+    /// 专辑名称
+    #[serde(default)]
+    pub name: String,
+    /// 专辑描述
+    #[serde(default)]
+    pub desc: Option<String>,
+    /// 笔记数量
+    #[serde(default)]
+    pub total: i64,
+    /// 隐私设置 (0=公开, 1=私密)
+    #[serde(default)]
+    pub privacy: Option<i32>,
+    /// 关注人数
+    #[serde(default)]
+    pub fans: Option<i64>,
+    /// 封面图片
+    #[serde(default)]
+    pub images: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::decode;
+
+    #[test]
+    fn decodes_user_profile() {
+        let response: UserProfileResponse = decode(include_str!("../../tests/fixtures/user_profile.json")).unwrap();
+        let profile = response.data.unwrap();
+
+        assert_eq!(profile.basic_info.nickname, "小李学姐爱学习");
+        assert_eq!(profile.basic_info.gender, Some(1));
+        assert_eq!(profile.basic_info.ip_location.as_deref(), Some("广东"));
+        assert_eq!(profile.following_count(), Some("12"));
+        assert_eq!(profile.follower_count(), Some("3.4万"));
+        assert_eq!(profile.liked_and_collected_count(), Some("56.7万"));
+
+        assert_eq!(profile.tags.len(), 3);
+        assert_eq!(profile.tags[0].tag_type.as_deref(), Some("gender"));
+        assert!(profile.tags[0].name.is_none());
+        assert_eq!(profile.tags[1].name.as_deref(), Some("广东深圳"));
+
+        let extra = profile.extra_info.unwrap();
+        assert_eq!(extra.fstatus.as_deref(), Some("none"));
+        assert_eq!(extra.block_type.as_deref(), Some("DEFAULT"));
+    }
+
+    #[test]
+    fn decodes_posted_notes_page() {
+        let response: UserNotesResponse = decode(include_str!("../../tests/fixtures/user_posted.json")).unwrap();
+        let data = response.data.unwrap();
+
+        assert_eq!(data.cursor, "6965aba6000000000e03c2a2");
+        assert!(data.has_more);
+        assert_eq!(data.notes.len(), 2);
+
+        let note = &data.notes[0];
+        assert_eq!(note.note_id, "69539b19000000002202c106");
+        assert_eq!(note.user.as_ref().unwrap().user_id.as_deref(), Some("664ec6ef0000000007004173"));
+        assert_eq!(note.cover.as_ref().unwrap().info_list.len(), 2);
+        assert_eq!(note.interact_info.as_ref().unwrap().sticky, Some(true));
+
+        let note = &data.notes[1];
+        assert_eq!(note.note_type.as_deref(), Some("video"));
+        assert_eq!(note.display_title.as_deref(), Some(""));
+        assert_eq!(note.interact_info.as_ref().unwrap().liked_count.as_deref(), Some("2.1万"));
+    }
+
+    #[test]
+    fn decodes_boards_page() {
+        let response: BoardListResponse = decode(include_str!("../../tests/fixtures/user_boards.json")).unwrap();
+        let data = response.data.unwrap();
+
+        assert!(!data.has_more);
+        assert_eq!(data.boards.len(), 2);
+
+        let board = &data.boards[0];
+        assert_eq!(board.id, "65a1b2c3000000001a0210de");
+        assert_eq!(board.total, 42);
+        assert_eq!(board.privacy, Some(0));
+        assert_eq!(board.fans, Some(3));
+        assert_eq!(board.images.len(), 2);
+
+        let board = &data.boards[1];
+        assert_eq!(board.privacy, Some(1));
+        assert!(board.desc.is_none());
+        assert!(board.fans.is_none());
+        assert!(board.images.is_empty());
+    }
+
+    #[test]
+    fn decodes_last_collected_page() {
+        let response: UserNotesResponse = decode(include_str!("../../tests/fixtures/user_collected.json")).unwrap();
+        let data = response.data.unwrap();
+
+        assert_eq!(data.cursor, "");
+        assert!(!data.has_more);
+
+        let note = &data.notes[0];
+        assert_eq!(note.note_id, "6951a0d2000000001e03b7c8");
+        assert_eq!(note.cover.as_ref().unwrap().url_pre, None);
+        let interact = note.interact_info.as_ref().unwrap();
+        assert_eq!(interact.liked, Some(true));
+        assert!(interact.sticky.is_none());
+    }
+}
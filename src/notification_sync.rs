@@ -0,0 +1,262 @@
+//! 通知增量同步模块 (Incremental Notification Sync)
+//!
+//! 为每个账号、每种通知类型记录已见过的最新消息（高水位），
+//! 同步时从第一页开始翻页，遇到高水位即停止，只返回新通知：
+//! - 高水位为上次同步时最新一条消息的 `id` 与 `time`
+//! - 遇到相同 `id`，或 `time` 早于高水位（该消息已被删除）时停止
+//! - 首次同步没有高水位，最多返回 `max_pages` 页历史通知
+//! - 达到 `max_pages` 仍未遇到高水位时不前移高水位，并返回 `truncated: true`
+//!
+//! 高水位保存在 `<dir>/<user_id>.json`（0600，原子写入）。
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tracing::info;
+use utoipa::ToSchema;
+
+use crate::api::{Page, Paginator, XhsApiClient};
+use crate::auth::storage::{create_private_dir, slot_path, write_atomic};
+use crate::error::XhsResult;
+use crate::models::notification::{NotificationMessage, NotificationType};
+
+/// 翻页间隔
+const PAGE_DELAY: Duration = Duration::from_secs(1);
+
+/// 通知类型对应的分页器
+fn paginator(notification_type: NotificationType, api: &XhsApiClient) -> Paginator<NotificationMessage, String> {
+    match notification_type {
+        NotificationType::Likes => Paginator::likes(api),
+        NotificationType::Mentions => Paginator::mentions(api),
+        NotificationType::Connections => Paginator::connections(api),
+    }
+}
+
+/// 高水位：已见过的最新一条消息
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SyncMark {
+    /// 消息 ID
+    pub id: String,
+    /// 消息时间 (秒)
+    #[serde(default)]
+    pub time: Option<i64>,
+    /// 记录时间
+    #[schema(value_type = String, format = DateTime)]
+    pub synced_at: DateTime<Utc>,
+}
+
+impl SyncMark {
+    fn from_message(message: &NotificationMessage) -> Option<Self> {
+        if message.id().is_empty() {
+            return None;
+        }
+        Some(Self {
+            id: message.id().to_string(),
+            time: Some(message.time()).filter(|t| *t != 0),
+            synced_at: Utc::now(),
+        })
+    }
+
+    /// 消息是否已在上次同步中见过
+    fn covers(&self, message: &NotificationMessage) -> bool {
+        if message.id() == self.id {
+            return true;
+        }
+        matches!(self.time, Some(mark) if message.time() != 0 && message.time() < mark)
+    }
+}
+
+/// 同步结果
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct SyncResult {
+    /// 通知类型
+    #[serde(rename = "type")]
+    pub notification_type: NotificationType,
+    /// 账号 user_id
+    pub user_id: String,
+    /// 是否为首次同步（没有高水位）
+    pub first_sync: bool,
+    /// 新通知（最新在前）
+    pub items: Vec<NotificationMessage>,
+    /// 达到 `max_pages` 仍未翻到上次的高水位（首次同步时表示还有更早的历史通知）
+    ///
+    /// 此时高水位不前移，下次同步会再次返回这些通知；可增大 `max_pages` 重新同步以补齐
+    pub truncated: bool,
+    /// 同步后的高水位
+    pub mark: Option<SyncMark>,
+}
+
+/// 通知增量同步服务
+pub struct NotificationSync {
+    dir: PathBuf,
+    /// 串行化同步，避免并发轮询重复返回同一批通知
+    lock: Mutex<()>,
+}
+
+impl NotificationSync {
+    /// 创建同步服务，高水位保存在 `dir` 下
+    pub async fn new(dir: PathBuf) -> Result<Self> {
+        create_private_dir(&dir).await?;
+        info!("[NotificationSync] Using state directory: {}", dir.display());
+        Ok(Self { dir, lock: Mutex::new(()) })
+    }
+
+    /// 同步一种通知，返回上次同步之后的新通知
+    pub async fn sync(&self, api: &XhsApiClient, notification_type: NotificationType, max_pages: usize) -> XhsResult<SyncResult> {
+        let _guard = self.lock.lock().await;
+        let user_id = api.current_user_id().await?;
+
+        let mut marks = self.load(&user_id).await?;
+        let previous = marks.get(&notification_type).cloned();
+
+        let pages = paginator(notification_type, api).max_pages(max_pages).delay(PAGE_DELAY).into_pages();
+        let NewMessages { items, truncated } = collect_new(pages, previous.as_ref()).await?;
+
+        // 消息按时间倒序返回，第一条即为最新。
+        // 翻页上限内没有遇到旧高水位时保留旧高水位，否则两者之间的通知会永久丢失
+        let advance = previous.is_none() || !truncated;
+        let mark = match items.first().and_then(SyncMark::from_message).filter(|_| advance) {
+            Some(mark) => {
+                marks.insert(notification_type, mark.clone());
+                self.save(&user_id, &marks).await?;
+                Some(mark)
+            }
+            None => previous.clone(),
+        };
+
+        info!(
+            "[NotificationSync] {} {:?}: {} new notification(s){}",
+            user_id,
+            notification_type,
+            items.len(),
+            if truncated { " (truncated by max_pages)" } else { "" }
+        );
+        Ok(SyncResult {
+            notification_type,
+            user_id,
+            first_sync: previous.is_none(),
+            items,
+            truncated,
+            mark,
+        })
+    }
+
+    async fn load(&self, user_id: &str) -> Result<HashMap<NotificationType, SyncMark>> {
+        let path = slot_path(&self.dir, user_id, "json");
+        if !path.exists() {
+            return Ok(HashMap::new());
+        }
+        let content = tokio::fs::read_to_string(path).await?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    async fn save(&self, user_id: &str, marks: &HashMap<NotificationType, SyncMark>) -> Result<()> {
+        let path = slot_path(&self.dir, user_id, "json");
+        write_atomic(&path, &serde_json::to_vec_pretty(marks)?).await
+    }
+}
+
+/// 一次同步翻到的新通知
+struct NewMessages {
+    items: Vec<NotificationMessage>,
+    truncated: bool,
+}
+
+/// 逐页读取通知直到遇到高水位
+///
+/// 未遇到高水位、且最后一页还有下一页时（因 `max_pages` 提前结束）标记为 `truncated`
+async fn collect_new<S, C>(pages: S, previous: Option<&SyncMark>) -> XhsResult<NewMessages>
+where
+    S: Stream<Item = XhsResult<Page<NotificationMessage, C>>>,
+{
+    let mut pages = Box::pin(pages);
+    let mut items = Vec::new();
+    let mut has_more = false;
+
+    while let Some(page) = pages.next().await {
+        let page = page?;
+        has_more = page.next.is_some();
+        for message in page.items {
+            if previous.is_some_and(|mark| mark.covers(&message)) {
+                return Ok(NewMessages { items, truncated: false });
+            }
+            items.push(message);
+        }
+    }
+
+    Ok(NewMessages { items, truncated: has_more })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::notification::NotificationUser;
+
+    fn message(id: u32) -> NotificationMessage {
+        NotificationMessage::NewFollower { id: id.to_string(), time: 1_700_000_000 + id as i64, user: NotificationUser::default() }
+    }
+
+    fn mark(id: u32) -> SyncMark {
+        SyncMark::from_message(&message(id)).unwrap()
+    }
+
+    /// 通知流（最新在前），每页 `per_page` 条，id 从 `newest` 递减到 1
+    fn pages(newest: u32, per_page: u32, max_pages: usize) -> impl Stream<Item = XhsResult<Page<NotificationMessage, u32>>> {
+        Paginator::new(newest, move |top: u32| {
+            Box::pin(async move {
+                let bottom = top.saturating_sub(per_page);
+                let items = (bottom + 1..=top).rev().map(message).collect();
+                Ok(Page { items, next: (bottom > 0).then_some(bottom) })
+            })
+        })
+        .max_pages(max_pages)
+        .into_pages()
+    }
+
+    fn ids(result: &NewMessages) -> Vec<&str> {
+        result.items.iter().map(NotificationMessage::id).collect()
+    }
+
+    #[tokio::test]
+    async fn stops_at_previous_mark() {
+        let result = collect_new(pages(50, 10, 5), Some(&mark(45))).await.unwrap();
+        assert_eq!(ids(&result), ["50", "49", "48", "47", "46"]);
+        assert!(!result.truncated);
+    }
+
+    #[tokio::test]
+    async fn truncated_when_page_cap_is_hit_before_mark() {
+        let result = collect_new(pages(50, 10, 2), Some(&mark(5))).await.unwrap();
+        assert_eq!(result.items.len(), 20);
+        assert!(result.truncated);
+    }
+
+    #[tokio::test]
+    async fn not_truncated_when_stream_ends_before_cap() {
+        // 旧高水位对应的消息已被删除，且早于所有现存通知
+        let gone = SyncMark { id: "deleted".to_string(), time: Some(1), synced_at: Utc::now() };
+        let result = collect_new(pages(15, 10, 5), Some(&gone)).await.unwrap();
+        assert_eq!(result.items.len(), 15);
+        assert!(!result.truncated);
+    }
+
+    #[tokio::test]
+    async fn first_sync_reports_remaining_history() {
+        let result = collect_new(pages(50, 10, 1), None).await.unwrap();
+        assert_eq!(result.items.len(), 10);
+        assert!(result.truncated);
+    }
+
+    #[test]
+    fn mark_covers_same_id_or_older_message() {
+        let mark = mark(10);
+        assert!(mark.covers(&message(10)));
+        assert!(mark.covers(&message(3)));
+        assert!(!mark.covers(&message(11)));
+    }
+}
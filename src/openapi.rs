@@ -6,6 +6,9 @@ use utoipa::OpenApi;
 
 use crate::{
     models::{
+        comment::{CommentPageResponse, CommentPageData, SubCommentPageResponse, SubCommentPageData, Comment, SubComment, TargetComment, CommentUser, CommentPicture, CommentImageInfo},
+        notification::{NotificationMessage, NotificationUser, NoteRef, CommentRef},
+        note::{NoteDetailResponse, NoteDetailData, NoteDetailItem, NoteDetail, NoteTag, NoteAtUser, NoteImage, NoteDetailVideo, VideoMedia, VideoStreams, VideoStream, NoteInteractInfo},
         feed::{HomefeedRequest, HomefeedResponse, HomefeedData, HomefeedItem, NoteCard, NoteUser, NoteCover, CoverImageInfo, InteractInfo, NoteVideo, VideoCapa},
         search::{QueryTrendingResponse, QueryTrendingData, TrendingQuery, TrendingHintWord, SearchRecommendResponse, SearchRecommendData, SugItem,
             SearchNotesRequest, SearchNotesResponse, SearchNotesData, SearchFilterOption,
@@ -13,15 +16,20 @@ use crate::{
             SearchFilterResponse, SearchFilterData, FilterItem, FilterTag,
             SearchUserRequest, SearchUserResponse, SearchUserData, SearchUserItem
         },
-        user::{UserMeResponse, UserInfo},
+        user::{UserMeResponse, UserInfo, UserProfileResponse, UserProfile, UserBasicInfo, UserInteraction, UserTag, UserExtraInfo,
+            UserNotesResponse, UserNotesData, UserNote, UserNoteInteractInfo, BoardListResponse, BoardListData, Board},
     },
     api::notification::{
         mentions::{MentionsResponse, MentionsData},
         connections::{ConnectionsResponse, ConnectionsData},
         likes::{LikesResponse, LikesData},
+        unread::{UnreadCountResponse, UnreadCount},
     },
+    auth::service::{SessionInfo, SessionHealth, HealthStatus},
+    client::ClientProfile,
     api::login::{GuestInitResponse, CreateQrCodeResponse, PollStatusResponse, QrCodeStatusData, LoginInfo},
-    api::note::detail::{NoteDetailRequest, NoteDetailResponse},
+    api::note::detail::NoteDetailRequest,
+    api::note::resolve::{ResolveNoteRequest, ResolveNoteResponse, ResolvedNote},
     api::media::{
         video::{VideoRequest, VideoResponse, VideoData, VideoItem},
         images::{ImagesRequest, ImagesResponse, ImagesData, ImageItem},
@@ -34,6 +42,9 @@ use crate::{
 
     handlers::media as media_handlers,
     handlers::creator as creator_handlers,
+    handlers::session as session_handlers,
+    handlers::stream as stream_handlers,
+    handlers::status as status_handlers,
     api,
     api::creator::{
         models::{CreatorQrcodeCreateRequest, CreatorQrcodeStatusRequest, CreatorUserInfo, CreatorHomeInfo, CreatorGrowInfo}
@@ -50,15 +61,32 @@ use crate::{
         search_handlers::search_filter_handler,
         search_handlers::search_user_handler,
         user_handlers::user_me_handler,
+        user_handlers::user_profile_handler,
+        user_handlers::user_posted_handler,
+        user_handlers::collected_notes_handler,
+        user_handlers::liked_notes_handler,
+        user_handlers::boards_handler,
+        user_handlers::board_notes_handler,
         auth_handlers::guest_init_handler,
         auth_handlers::create_qrcode_handler,
         auth_handlers::poll_qrcode_status_handler,
+        session_handlers::list_sessions_handler,
+        session_handlers::add_session_handler,
+        session_handlers::check_sessions_handler,
+        session_handlers::remove_session_handler,
+        session_handlers::set_default_session_handler,
+        session_handlers::set_session_profile_handler,
+        session_handlers::session_history_handler,
         api::feed::category::get_category_feed,
         api::note::page::get_note_page,
         api::note::detail::get_note_detail,
+        api::note::sub_comments::get_sub_comments_handler,
+        api::note::resolve::resolve_note_handler,
         notification_handlers::mentions_handler,
         notification_handlers::connections_handler,
         notification_handlers::likes_handler,
+        notification_handlers::unread_count_handler,
+        notification_handlers::notification_sync_handler,
         media_handlers::images_handler,
         media_handlers::download_handler,
         creator_handlers::creator_guest_init_handler,
@@ -66,10 +94,27 @@ use crate::{
         creator_handlers::creator_check_qrcode_status,
         creator_handlers::creator_user_info_handler,
         creator_handlers::creator_home_info_handler,
+        stream_handlers::comments_stream_handler,
+        stream_handlers::thread_stream_handler,
+        stream_handlers::user_posted_stream_handler,
+        stream_handlers::collected_stream_handler,
+        stream_handlers::liked_stream_handler,
+        stream_handlers::board_notes_stream_handler,
+        stream_handlers::mentions_stream_handler,
+        stream_handlers::connections_stream_handler,
+        stream_handlers::likes_stream_handler,
+        stream_handlers::homefeed_stream_handler,
+        stream_handlers::search_notes_stream_handler,
+        stream_handlers::search_user_stream_handler,
+        status_handlers::limits_handler,
     ),
     components(
         schemas(
             GuestInitResponse, CreateQrCodeResponse, PollStatusResponse, QrCodeStatusData, LoginInfo,
+            SessionInfo, SessionHealth, HealthStatus, session_handlers::AddSessionRequest, session_handlers::SetProfileRequest, session_handlers::SessionListResponse,
+            ClientProfile,
+            session_handlers::SessionVersion, session_handlers::SessionHistory, session_handlers::SessionHistoryResponse,
+            crate::auth::credentials::ValidityEvent,
             QueryTrendingResponse, QueryTrendingData, TrendingQuery, TrendingHintWord,
             SearchRecommendResponse, SearchRecommendData, SugItem,
             SearchNotesRequest, SearchNotesResponse, SearchNotesData, SearchFilterOption,
@@ -77,11 +122,19 @@ use crate::{
             SearchFilterResponse, SearchFilterData, FilterItem, FilterTag,
             SearchUserRequest, SearchUserResponse, SearchUserData, SearchUserItem,
             UserMeResponse, UserInfo,
+            UserProfileResponse, UserProfile, UserBasicInfo, UserInteraction, UserTag, UserExtraInfo,
+            UserNotesResponse, UserNotesData, UserNote, UserNoteInteractInfo, BoardListResponse, BoardListData, Board,
             MentionsResponse, MentionsData,
             ConnectionsResponse, ConnectionsData,
             LikesResponse, LikesData,
+            UnreadCountResponse, UnreadCount,
+            NotificationMessage, NotificationUser, NoteRef, CommentRef,
+            status_handlers::LimitsResponse, crate::api::limiter::LimitStatus, crate::api::limiter::EndpointFamily, crate::api::limiter::CircuitState,
+            notification_handlers::SyncResponse, crate::notification_sync::SyncResult, crate::notification_sync::SyncMark, crate::models::notification::NotificationType,
             HomefeedRequest, HomefeedResponse, HomefeedData, HomefeedItem, NoteCard, NoteUser, NoteCover, CoverImageInfo, InteractInfo, NoteVideo, VideoCapa,
-            NoteDetailRequest, NoteDetailResponse,
+            NoteDetailRequest, NoteDetailResponse, NoteDetailData, NoteDetailItem, NoteDetail, NoteTag, NoteAtUser, NoteImage, NoteDetailVideo, VideoMedia, VideoStreams, VideoStream, NoteInteractInfo,
+            ResolveNoteRequest, ResolveNoteResponse, ResolvedNote,
+            CommentPageResponse, CommentPageData, SubCommentPageResponse, SubCommentPageData, Comment, SubComment, TargetComment, CommentUser, CommentPicture, CommentImageInfo,
             VideoRequest, VideoResponse, VideoData, VideoItem,
             ImagesRequest, ImagesResponse, ImagesData, ImageItem,
             DownloadRequest, DownloadResponse, DownloadData,
@@ -91,12 +144,13 @@ use crate::{
     ),
     tags(
         (name = "xhs", description = "小红书 API 接口"),
-        (name = "auth", description = "用户认证 (User Auth)"),
+        (name = "auth", description = "用户认证与多账号会话 (User Auth)。业务接口可通过 X-Xhs-Account Header 指定账号"),
         (name = "Creator", description = "创作者中心认证 (Creator Auth)"),
         (name = "Feed", description = "主页发现频道：recommend(推荐)、fashion(穿搭)、food(美食)、cosmetics(彩妆)、movie_and_tv(影视)、career(职场)、love(情感)、household_product(家居)、gaming(游戏)、travel(旅行)、fitness(健身)"),
         (name = "Note", description = "笔记相关接口：detail(详情)、page(评论)、video(视频地址)"),
         (name = "Media", description = "媒体文件操作：video(视频地址解析)、images(图片地址解析)、download(通用媒体下载)"),
-        (name = "Search", description = "搜索相关接口：notes(笔记)、usersearch(用户)、onebox(聚合)、recommend(推荐)、filter(筛选)")
+        (name = "Search", description = "搜索相关接口：notes(笔记)、usersearch(用户)、onebox(聚合)、recommend(推荐)、filter(筛选)"),
+        (name = "Status", description = "服务状态：limits(客户端限速与风控退避)")
     )
 )]
 pub struct ApiDoc;
@@ -4,21 +4,24 @@
 //! All handlers are delegated to the `handlers` module.
 
 use axum::{
-    routing::{get, post},
+    routing::{delete, get, post, put},
     Router,
 };
-use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use utoipa::OpenApi;
 use utoipa_swagger_ui::SwaggerUi;
 
 use crate::{
-    api::{self, XhsApiClient},
+    api::{self, cache::ResponseCache, XhsApiClient},
     auth::AuthService,
     client::XhsClient,
+    config::get_config,
     handlers,
+    health::HealthChecker,
+    notification_sync::NotificationSync,
     openapi::ApiDoc,
+    signature,
 };
 
 // ============================================================================
@@ -29,6 +32,10 @@ pub struct AppState {
     pub api: XhsApiClient,
     pub auth: Arc<AuthService>,
     pub creator_auth: Arc<AuthService>,
+    /// Session health checker (background task + on-demand checks)
+    pub health: Arc<HealthChecker>,
+    /// Notification high-water marks per account
+    pub notification_sync: Arc<NotificationSync>,
     /// Guest cookies for QR login (populated by guest-init)
     pub guest_cookies: Arc<RwLock<Option<std::collections::HashMap<String, String>>>>,
     /// Current QR code info (qr_id, code)
@@ -40,21 +47,40 @@ pub struct AppState {
 // ============================================================================
 
 pub async fn start_server() -> anyhow::Result<()> {
-    // Initialize AuthService (uses JSON file storage)
-    tracing::info!("Initializing AuthService with JSON file storage...");
-    let auth = Arc::new(AuthService::new(PathBuf::from("cookie.json")).await?);
+    let config = get_config();
+
+    // Initialize session registries (one JSON file per account)
+    let storage = &config.storage;
+    tracing::info!("Initializing AuthService with session directory: {}", storage.sessions_dir.display());
+    let auth = Arc::new(AuthService::new(storage.sessions_dir.clone()).await?);
+    
+    tracing::info!("Initializing CreatorAuthService with session directory: {}", storage.creator_sessions_dir.display());
+    let creator_auth = Arc::new(AuthService::new(storage.creator_sessions_dir.clone()).await?);
     
-    tracing::info!("Initializing CreatorAuthService with cookie-creator.json...");
-    let creator_auth = Arc::new(AuthService::new(PathBuf::from("cookie-creator.json")).await?);
+    // Migrate legacy single-account files into their own slots
+    auth.import_file(&storage.legacy_cookie).await?;
+    creator_auth.import_file(&storage.legacy_creator_cookie).await?;
     
     let client = XhsClient::new()?;
-    let api = XhsApiClient::new(client, auth.clone());
+    let mut api = XhsApiClient::new(client, auth.clone(), signature::default_signer());
+    
+    // Response cache for read-only endpoints (XHS_CACHE_*)
+    if let Some(cache) = ResponseCache::from_config().await? {
+        api = api.with_cache(Arc::new(cache));
+    }
+    
+    // Periodically verify sessions against XHS (XHS_HEALTH_INTERVAL)
+    let health = Arc::new(HealthChecker::new(api.with_account(None), auth.clone(), creator_auth.clone()));
+    health.clone().spawn();
+    
+    // Incremental notification sync (high-water marks per account)
+    let notification_sync = Arc::new(NotificationSync::new(storage.notification_sync_dir.clone()).await?);
     
     // Initialize shared state for login flow
     let guest_cookies = Arc::new(RwLock::new(None));
     let qrcode_info = Arc::new(RwLock::new(None));
     
-    let state = Arc::new(AppState { api, auth, creator_auth, guest_cookies, qrcode_info });
+    let state = Arc::new(AppState { api, auth, creator_auth, health, notification_sync, guest_cookies, qrcode_info });
 
     let app = Router::new()
         // Swagger UI
@@ -67,22 +93,47 @@ pub async fn start_server() -> anyhow::Result<()> {
         .route("/api/search/onebox", post(handlers::search_onebox_handler))
         .route("/api/search/filter", get(handlers::search_filter_handler))
         .route("/api/search/usersearch", post(handlers::search_user_handler))
+        .route("/api/search/notes/stream", post(handlers::search_notes_stream_handler))
+        .route("/api/search/usersearch/stream", post(handlers::search_user_stream_handler))
         
         // User routes
         .route("/api/user/me", get(handlers::user_me_handler))
+        .route("/api/user/profile/:user_id", get(handlers::user_profile_handler))
+        .route("/api/user/posted/:user_id", get(handlers::user_posted_handler))
+        .route("/api/user/posted/:user_id/stream", get(handlers::user_posted_stream_handler))
+        .route("/api/user/me/collected", get(handlers::collected_notes_handler))
+        .route("/api/user/me/collected/stream", get(handlers::collected_stream_handler))
+        .route("/api/user/me/liked", get(handlers::liked_notes_handler))
+        .route("/api/user/me/liked/stream", get(handlers::liked_stream_handler))
+        .route("/api/user/me/boards", get(handlers::boards_handler))
+        .route("/api/user/boards/:board_id/notes", get(handlers::board_notes_handler))
+        .route("/api/user/boards/:board_id/notes/stream", get(handlers::board_notes_stream_handler))
         
         // Feed routes
         .route("/api/feed/homefeed/recommend", post(handlers::homefeed_recommend_handler))
         .route("/api/feed/homefeed/:category", post(api::feed::category::get_category_feed))
+        .route("/api/feed/homefeed/:category/stream", post(handlers::homefeed_stream_handler))
         
         // Note routes
         .route("/api/note/page", get(api::note::page::get_note_page))
         .route("/api/note/detail", post(api::note::detail::get_note_detail))
+        .route("/api/note/resolve", post(api::note::resolve::resolve_note_handler))
+        .route("/api/note/sub_comments", get(api::note::sub_comments::get_sub_comments_handler))
+        .route("/api/note/comments/stream", get(handlers::comments_stream_handler))
+        .route("/api/note/thread/stream", get(handlers::thread_stream_handler))
         
         // Notification routes
         .route("/api/notification/mentions", get(handlers::mentions_handler))
         .route("/api/notification/connections", get(handlers::connections_handler))
         .route("/api/notification/likes", get(handlers::likes_handler))
+        .route("/api/notification/unread_count", get(handlers::unread_count_handler))
+        .route("/api/notification/sync", get(handlers::notification_sync_handler))
+        .route("/api/notification/mentions/stream", get(handlers::mentions_stream_handler))
+        .route("/api/notification/connections/stream", get(handlers::connections_stream_handler))
+        .route("/api/notification/likes/stream", get(handlers::likes_stream_handler))
+        
+        // Status routes
+        .route("/api/status/limits", get(handlers::limits_handler))
         
         // Media routes
         .route("/api/note/video", post(handlers::video_handler))
@@ -94,6 +145,14 @@ pub async fn start_server() -> anyhow::Result<()> {
         .route("/api/auth/qrcode/create", post(handlers::create_qrcode_handler))
         .route("/api/auth/qrcode/status", get(handlers::poll_qrcode_status_handler))
         
+        // Session registry routes
+        .route("/api/auth/sessions", get(handlers::list_sessions_handler).post(handlers::add_session_handler))
+        .route("/api/auth/sessions/check", post(handlers::check_sessions_handler))
+        .route("/api/auth/sessions/:user_id", delete(handlers::remove_session_handler))
+        .route("/api/auth/sessions/:user_id/default", post(handlers::set_default_session_handler))
+        .route("/api/auth/sessions/:user_id/profile", put(handlers::set_session_profile_handler))
+        .route("/api/auth/sessions/:user_id/history", get(handlers::session_history_handler))
+        
         // Creator routes
         .route("/api/creator/auth/guest-init", post(handlers::creator_guest_init_handler))
         .route("/api/creator/auth/qrcode/create", post(handlers::creator_create_qrcode_handler))
@@ -104,15 +163,11 @@ pub async fn start_server() -> anyhow::Result<()> {
         .route("/api/galaxy/creator/home/personal_info", get(handlers::creator_home_info_handler))
         
         // Middleware
+        .layer(axum::middleware::from_fn(api::cache::cache_layer))
         .layer(tower_http::trace::TraceLayer::new_for_http())
         .with_state(state);
 
-    // Get port from environment variable, default to 3000
-    let port = std::env::var("PORT")
-        .or_else(|_| std::env::var("XHS_API_PORT"))
-        .unwrap_or_else(|_| "3000".to_string());
-    
-    let addr = format!("0.0.0.0:{}", port);
+    let addr = format!("{}:{}", config.server.host, config.server.port);
     let listener = tokio::net::TcpListener::bind(&addr).await?;
     
     tracing::info!("Server running on http://{}/swagger-ui/", addr);
@@ -0,0 +1,106 @@
+//! 链式签名 (Chained Signer)
+//!
+//! 按顺序尝试多个签名器，前一个失败时降级到下一个。
+//! 例如 `agent → native → fixture`：Agent 未启动时使用原生签名，
+//! 原生签名出错时再回放录制的签名。
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use super::{SignRequest, Signature, Signer};
+use crate::error::{XhsError, XhsResult};
+
+/// 降级策略，由 `XHS_SIGNER_FALLBACK` / `signer.fallback` 配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FallbackPolicy {
+    /// 任何错误都尝试下一个签名器
+    #[default]
+    AnyError,
+    /// 仅当签名器不可用 (AgentUnavailable / SignerUnavailable / Transport) 时降级，其他错误直接返回
+    UnavailableOnly,
+}
+
+impl FallbackPolicy {
+    fn should_fallback(&self, error: &XhsError) -> bool {
+        match self {
+            Self::AnyError => true,
+            Self::UnavailableOnly => matches!(
+                error,
+                XhsError::AgentUnavailable(_) | XhsError::SignerUnavailable(_) | XhsError::Transport(_)
+            ),
+        }
+    }
+}
+
+impl FromStr for FallbackPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "any_error" => Ok(Self::AnyError),
+            "unavailable_only" => Ok(Self::UnavailableOnly),
+            other => Err(format!("unknown fallback policy '{}'", other)),
+        }
+    }
+}
+
+/// 按顺序降级的签名器
+pub struct ChainedSigner {
+    signers: Vec<Arc<dyn Signer>>,
+    policy: FallbackPolicy,
+}
+
+impl ChainedSigner {
+    /// 创建链式签名器（默认任何错误都降级）
+    pub fn new(signers: Vec<Arc<dyn Signer>>) -> Self {
+        Self {
+            signers,
+            policy: FallbackPolicy::default(),
+        }
+    }
+
+    /// 设置降级策略
+    pub fn with_policy(mut self, policy: FallbackPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// 链中签名器名称（按顺序）
+    pub fn names(&self) -> Vec<&'static str> {
+        self.signers.iter().map(|s| s.name()).collect()
+    }
+}
+
+#[async_trait]
+impl Signer for ChainedSigner {
+    fn name(&self) -> &'static str {
+        "chained"
+    }
+
+    async fn sign(&self, request: &SignRequest) -> XhsResult<Signature> {
+        let mut last_error = None;
+
+        for signer in &self.signers {
+            match signer.sign(request).await {
+                Ok(signature) => {
+                    if last_error.is_some() {
+                        tracing::info!("[ChainedSigner] {} {} signed by fallback '{}'", request.method, request.uri, signer.name());
+                    }
+                    return Ok(signature);
+                }
+                Err(e) => {
+                    if !self.policy.should_fallback(&e) {
+                        return Err(e);
+                    }
+                    tracing::warn!("[ChainedSigner] '{}' failed for {}: {}", signer.name(), request.uri, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| XhsError::SignerUnavailable("No signer configured".to_string())))
+    }
+}
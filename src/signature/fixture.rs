@@ -0,0 +1,150 @@
+//! 签名回放 (Fixture Signer)
+//!
+//! 从 JSON 文件中读取录制的签名（[`SignatureRecord`] 列表，格式与
+//! `scripts/record_signatures.py` 的输出一致），按请求回放。
+//! 用于离线测试，或在纯算法签名不可用时作为最后的兜底。
+
+use async_trait::async_trait;
+use std::path::Path;
+
+use super::{SignRequest, Signature, SignatureRecord, Signer};
+use crate::error::{XhsError, XhsResult};
+
+/// 回放录制签名的签名器
+pub struct FixtureSigner {
+    records: Vec<SignatureRecord>,
+}
+
+impl FixtureSigner {
+    /// 使用已加载的签名记录创建
+    pub fn new(records: Vec<SignatureRecord>) -> Self {
+        Self { records }
+    }
+
+    /// 从 JSON 文件加载签名记录
+    pub fn from_file(path: impl AsRef<Path>) -> XhsResult<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)?;
+        let records: Vec<SignatureRecord> = serde_json::from_str(&content)
+            .map_err(|e| XhsError::decode(format!("Invalid signature fixture {}: {}", path.display(), e), ""))?;
+
+        tracing::info!("[FixtureSigner] Loaded {} signatures from {}", records.len(), path.display());
+        Ok(Self::new(records))
+    }
+
+    /// 已加载的记录数
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// 是否没有任何记录
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// 查找完全匹配 (method + uri + payload) 的记录
+    ///
+    /// 签名覆盖完整的 uri 与 payload，其他请求的签名会被服务端拒绝，因此不做模糊匹配
+    fn find(&self, request: &SignRequest) -> Option<&SignatureRecord> {
+        self.records.iter().find(|r| {
+            r.method.eq_ignore_ascii_case(&request.method) && r.uri == request.uri && r.payload == request.payload
+        })
+    }
+}
+
+#[async_trait]
+impl Signer for FixtureSigner {
+    fn name(&self) -> &'static str {
+        "fixture"
+    }
+
+    async fn sign(&self, request: &SignRequest) -> XhsResult<Signature> {
+        self.find(request)
+            .map(SignatureRecord::signature)
+            .ok_or_else(|| XhsError::SignerUnavailable(format!(
+                "No recorded signature for {} {}",
+                request.method, request.uri
+            )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signature::{ChainedSigner, FallbackPolicy};
+    use std::sync::Arc;
+
+    fn signer() -> FixtureSigner {
+        let records = serde_json::from_str(include_str!("../../tests/fixtures/agent_signatures.json")).unwrap();
+        FixtureSigner::new(records)
+    }
+
+    /// 总是以给定错误失败的签名器
+    struct Failing(fn() -> XhsError);
+
+    #[async_trait]
+    impl Signer for Failing {
+        fn name(&self) -> &'static str {
+            "failing"
+        }
+
+        async fn sign(&self, _request: &SignRequest) -> XhsResult<Signature> {
+            Err((self.0)())
+        }
+    }
+
+    #[tokio::test]
+    async fn replays_exact_matches() {
+        let signer = signer();
+        assert!(!signer.is_empty());
+        for record in &signer.records {
+            let signature = signer.sign(&record.request()).await.unwrap();
+            assert_eq!(signature.x_s, record.x_s);
+            assert_eq!(signature.x_t, record.x_t);
+        }
+    }
+
+    #[tokio::test]
+    async fn rejects_requests_without_exact_match() {
+        let signer = signer();
+        let record = signer.records.iter().find(|r| r.uri.contains('?')).expect("fixture has a GET with query");
+
+        let mut other_query = record.request();
+        other_query.uri = format!("{}&extra=1", record.uri);
+        assert!(matches!(signer.sign(&other_query).await, Err(XhsError::SignerUnavailable(_))));
+
+        let mut other_method = record.request();
+        other_method.method = "DELETE".to_string();
+        assert!(matches!(signer.sign(&other_method).await, Err(XhsError::SignerUnavailable(_))));
+
+        let record = signer.records.iter().find(|r| r.payload.is_some()).expect("fixture has a POST");
+        let mut other_payload = record.request();
+        other_payload.payload = Some(serde_json::json!({ "keyword": "not recorded" }));
+        assert!(matches!(signer.sign(&other_payload).await, Err(XhsError::SignerUnavailable(_))));
+    }
+
+    #[tokio::test]
+    async fn chain_falls_back_to_fixture_according_to_policy() {
+        let request = signer().records[0].request();
+        let chain = |policy, error| {
+            ChainedSigner::new(vec![Arc::new(Failing(error)), Arc::new(signer())]).with_policy(policy)
+        };
+
+        let unavailable = || XhsError::AgentUnavailable("down".to_string());
+        let rejected = || XhsError::SignatureRejected("bad".to_string());
+
+        assert!(chain(FallbackPolicy::UnavailableOnly, unavailable).sign(&request).await.is_ok());
+        assert!(chain(FallbackPolicy::AnyError, rejected).sign(&request).await.is_ok());
+        assert!(matches!(
+            chain(FallbackPolicy::UnavailableOnly, rejected).sign(&request).await,
+            Err(XhsError::SignatureRejected(_))
+        ));
+    }
+
+    #[test]
+    fn fallback_policy_parses_config_values() {
+        assert_eq!("unavailable_only".parse(), Ok(FallbackPolicy::UnavailableOnly));
+        assert_eq!("any_error".parse(), Ok(FallbackPolicy::AnyError));
+        assert!("sometimes".parse::<FallbackPolicy>().is_err());
+    }
+}
@@ -1,18 +1,46 @@
 //! 签名服务模块 (Signature Service Module)
 //!
-//! 提供两种签名获取策略：
-//! 1. **纯算法 (Pure Algorithm)**: 调用 Python Agent 的 `/sign` 端点，使用 xhshow 库生成签名
-//! 2. **浏览器捕获 (Browser Capture)**: 从 MongoDB 读取之前通过 Playwright 捕获的签名（兜底）
+//! 所有签名实现都通过 [`Signer`] trait 提供：
+//! 1. **Agent**: [`SignatureService`]，调用 Python Agent 的 `/sign` 端点（xhshow 库）
+//! 2. **原生 (Native)**: [`NativeSigner`]，进程内纯 Rust 实现，无需 Python
+//! 3. **回放 (Fixture)**: [`FixtureSigner`]，从文件回放录制的签名，用于离线测试
+//! 4. **链式 (Chained)**: [`ChainedSigner`]，按顺序尝试多个签名器
 //!
-//! 默认优先使用纯算法，失败时自动降级到浏览器捕获。
+//! 通过环境变量 `XHS_SIGNER` 配置签名器链（逗号分隔，默认 `agent`），
+//! 例如 `XHS_SIGNER=agent,native,fixture`；回放文件路径由 `XHS_SIGNATURE_FIXTURE` 指定，
+//! 降级策略由 `XHS_SIGNER_FALLBACK` 指定（`any_error` / `unavailable_only`）。
 
-use anyhow::{Result, anyhow};
+pub mod chained;
+pub mod fixture;
+pub mod native;
+
+use async_trait::async_trait;
+use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use crate::config::get_agent_url;
+use std::sync::Arc;
+use crate::client::ClientProfile;
+use crate::config::{get_agent_url, get_client_profile, get_signature_fixture_path, get_signer_chain, get_signer_fallback, SignerKind};
+use crate::error::{XhsError, XhsResult};
+
+pub use chained::{ChainedSigner, FallbackPolicy};
+pub use fixture::FixtureSigner;
+pub use native::NativeSigner;
+
+/// 签名器接口
+///
+/// 根据请求方法、URI、Cookie 和 payload 生成一组签名 headers
+#[async_trait]
+pub trait Signer: Send + Sync {
+    /// 签名器名称（用于日志）
+    fn name(&self) -> &'static str;
+
+    /// 为请求生成签名
+    async fn sign(&self, request: &SignRequest) -> XhsResult<Signature>;
+}
 
 /// 签名请求结构
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SignRequest {
     pub method: String,
     pub uri: String,
@@ -21,6 +49,9 @@ pub struct SignRequest {
     pub params: Option<serde_json::Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub payload: Option<serde_json::Value>,
+    /// 发送请求时使用的浏览器指纹（签名中的平台与请求头保持一致）
+    #[serde(default)]
+    pub profile: ClientProfile,
 }
 
 /// 签名响应结构
@@ -45,6 +76,49 @@ pub struct Signature {
     pub x_xray_traceid: String,
 }
 
+/// 录制的签名样本（由 `scripts/record_signatures.py` 生成）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignatureRecord {
+    pub method: String,
+    pub uri: String,
+    #[serde(default)]
+    pub cookies: HashMap<String, String>,
+    #[serde(default)]
+    pub payload: Option<serde_json::Value>,
+    pub x_s: String,
+    pub x_t: String,
+    pub x_s_common: String,
+    #[serde(default)]
+    pub x_b3_traceid: String,
+    #[serde(default)]
+    pub x_xray_traceid: String,
+}
+
+impl SignatureRecord {
+    /// 还原为签名请求
+    pub fn request(&self) -> SignRequest {
+        SignRequest {
+            method: self.method.clone(),
+            uri: self.uri.clone(),
+            cookies: self.cookies.clone(),
+            params: None,
+            payload: self.payload.clone(),
+            profile: ClientProfile::default(),
+        }
+    }
+
+    /// 还原为签名结果
+    pub fn signature(&self) -> Signature {
+        Signature {
+            x_s: self.x_s.clone(),
+            x_t: self.x_t.clone(),
+            x_s_common: self.x_s_common.clone(),
+            x_b3_traceid: self.x_b3_traceid.clone(),
+            x_xray_traceid: self.x_xray_traceid.clone(),
+        }
+    }
+}
+
 /// 签名服务 - 提供签名获取的统一接口
 pub struct SignatureService {
     client: reqwest::Client,
@@ -74,37 +148,44 @@ impl SignatureService {
         uri: &str,
         cookies: HashMap<String, String>,
         payload: Option<serde_json::Value>,
-    ) -> Result<Signature> {
+    ) -> XhsResult<Signature> {
         let request = SignRequest {
             method: method.to_uppercase(),
             uri: uri.to_string(),
             cookies,
             params: None,
             payload,
+            profile: get_client_profile().clone(),
         };
 
+        self.sign_via_agent(&request).await
+    }
+
+    /// 调用 Agent `/sign` 端点
+    async fn sign_via_agent(&self, request: &SignRequest) -> XhsResult<Signature> {
+        let (method, uri) = (&request.method, &request.uri);
         let url = format!("{}/sign", get_agent_url());
         
         tracing::debug!("[SignatureService] Calling Agent: {} {}", method, uri);
         
         let response = self.client
             .post(&url)
-            .json(&request)
+            .json(request)
             .timeout(std::time::Duration::from_secs(5))
             .send()
             .await
-            .map_err(|e| anyhow!("Agent connection failed: {}. Is agent_server.py running?", e))?;
+            .map_err(|e| XhsError::AgentUnavailable(format!("Agent connection failed: {}. Is agent_server.py running?", e)))?;
 
         let sign_resp: SignResponse = response
             .json()
             .await
-            .map_err(|e| anyhow!("Failed to parse Agent response: {}", e))?;
+            .map_err(|e| XhsError::AgentUnavailable(format!("Failed to parse Agent response: {}", e)))?;
 
         if !sign_resp.success {
-            return Err(anyhow!(
+            return Err(XhsError::AgentUnavailable(format!(
                 "Agent signing failed: {}",
                 sign_resp.error.unwrap_or_else(|| "Unknown error".to_string())
-            ));
+            )));
         }
 
         Ok(Signature {
@@ -132,6 +213,54 @@ impl Default for SignatureService {
     }
 }
 
+#[async_trait]
+impl Signer for SignatureService {
+    fn name(&self) -> &'static str {
+        "agent"
+    }
+
+    async fn sign(&self, request: &SignRequest) -> XhsResult<Signature> {
+        self.sign_via_agent(request).await
+    }
+}
+
+/// 按配置构建签名器
+///
+/// 只有一个实现时直接返回该实现，多个时组合为 [`ChainedSigner`]。
+/// 回放文件无法加载时跳过该环节（记录警告）。
+pub fn build_signer(kinds: &[SignerKind]) -> Arc<dyn Signer> {
+    let mut signers: Vec<Arc<dyn Signer>> = Vec::new();
+
+    for kind in kinds {
+        match kind {
+            SignerKind::Agent => signers.push(Arc::new(SignatureService::new())),
+            SignerKind::Native => signers.push(Arc::new(NativeSigner::new())),
+            SignerKind::Fixture => match FixtureSigner::from_file(get_signature_fixture_path()) {
+                Ok(signer) => signers.push(Arc::new(signer)),
+                Err(e) => tracing::warn!("[Signature] Fixture signer disabled: {}", e),
+            },
+        }
+    }
+
+    match signers.len() {
+        0 => Arc::new(SignatureService::new()),
+        1 => signers.remove(0),
+        _ => {
+            let chained = ChainedSigner::new(signers).with_policy(get_signer_fallback());
+            tracing::info!("[Signature] Using signer chain: {} ({:?})", chained.names().join(" -> "), get_signer_fallback());
+            Arc::new(chained)
+        }
+    }
+}
+
+/// 全局默认签名器（由 `XHS_SIGNER` 决定）
+static DEFAULT_SIGNER: Lazy<Arc<dyn Signer>> = Lazy::new(|| build_signer(get_signer_chain()));
+
+/// 获取默认签名器
+pub fn default_signer() -> Arc<dyn Signer> {
+    DEFAULT_SIGNER.clone()
+}
+
 /// 将 Cookie 字符串解析为 HashMap
 pub fn parse_cookie_string(cookie_str: &str) -> HashMap<String, String> {
     let mut cookies = HashMap::new();
@@ -0,0 +1,269 @@
+//! 原生签名实现 (Native Signer)
+//!
+//! 纯 Rust 实现的 x-s / x-t / x-s-common / x-b3-traceid / x-xray-traceid 生成，
+//! 算法结构与 Python Agent 使用的 xhshow 库保持一致，无需启动 Python 进程。
+//!
+//! x-s 结构:
+//! ```text
+//! XYS_ + b64_custom({"x0": SDK, "x1": APP, "x2": platform, "x3": "mns0301_" + b64_x3(xor(payload)), "x4": ""})
+//! payload = VERSION(4) | seed(4, LE) | x-t(8, LE) | md5(content)(16) | len(a1) | a1 | len(app) | app
+//! ```
+//!
+//! 与 Agent 的一致性尚未验证：仓库中的 `tests/fixtures/agent_signatures.json` 由本签名器生成，
+//! 并非 Agent 录制。使用 `scripts/record_signatures.py` 对运行中的 Agent 重新录制后，
+//! 运行 `tests/signer_parity.rs` 中被忽略的比对测试或 `examples/check_signer_parity.rs` 进行比对。
+
+use async_trait::async_trait;
+use base64::alphabet::Alphabet;
+use base64::engine::{GeneralPurpose, GeneralPurposeConfig};
+use base64::Engine;
+use once_cell::sync::Lazy;
+use rand::Rng;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use super::{SignRequest, Signature, Signer};
+use crate::error::XhsResult;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+const CUSTOM_BASE64_ALPHABET: &str = "ZmserbBoHQtNP+wOcza/LpngG8yJq42KWYj0DSfdikx3VT16IlUAFM97hECvuRX5";
+const X3_BASE64_ALPHABET: &str = "MfgqrsbcyzPQRStuvC7mn501HIJBo2DEFTKdeNOwxWXYZap89+/A4UVLhijkl63G";
+
+const HEX_KEY: &str = "71a302257793271ddd273bcee3e4b98d9d7935e1da33f5765e2ea8afb6dc77a51a499d23b67c20660025860cbf13d4540d92497f58686c574e508f46e1956344f39139bf4faf22a3eef120b79258145b2feb5193b6478669961298e79bedca646e1a693a926154a5a7a1bd1cf0dedb742f917a747a1e388b234f2277";
+
+const VERSION_BYTES: [u8; 4] = [119, 104, 96, 41];
+const X3_PREFIX: &str = "mns0301_";
+const XYS_PREFIX: &str = "XYS_";
+
+const SDK_VERSION: &str = "4.2.6";
+const APP_ID: &str = "xhs-pc-web";
+const WEB_BUILD: &str = "4.86.0";
+
+static CUSTOM_ENGINE: Lazy<GeneralPurpose> = Lazy::new(|| build_engine(CUSTOM_BASE64_ALPHABET));
+static X3_ENGINE: Lazy<GeneralPurpose> = Lazy::new(|| build_engine(X3_BASE64_ALPHABET));
+static XOR_KEY: Lazy<Vec<u8>> = Lazy::new(|| decode_hex(HEX_KEY));
+
+fn build_engine(alphabet: &str) -> GeneralPurpose {
+    let alphabet = Alphabet::new(alphabet).expect("valid base64 alphabet");
+    GeneralPurpose::new(&alphabet, GeneralPurposeConfig::new())
+}
+
+fn decode_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .filter_map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+// ============================================================================
+// Sign Context
+// ============================================================================
+
+/// 单次签名的随机/时间输入
+///
+/// 固定这些值即可得到确定的签名输出，用于与录制的 Agent 输出做一致性比对
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignContext {
+    /// x-t (毫秒时间戳)
+    pub timestamp_ms: u64,
+    /// x3 中的随机种子
+    pub seed: u32,
+    /// x-s-common 中的签名计数 (x10)
+    pub counter: u32,
+}
+
+// ============================================================================
+// Native Signer
+// ============================================================================
+
+/// 纯 Rust 签名器
+pub struct NativeSigner {
+    counter: AtomicU32,
+}
+
+impl NativeSigner {
+    /// 创建原生签名器
+    pub fn new() -> Self {
+        Self {
+            counter: AtomicU32::new(0),
+        }
+    }
+
+    /// 生成新的签名上下文（当前时间 + 随机种子 + 递增计数）
+    pub fn next_context(&self) -> SignContext {
+        SignContext {
+            timestamp_ms: chrono::Utc::now().timestamp_millis() as u64,
+            seed: rand::thread_rng().gen(),
+            counter: self.counter.fetch_add(1, Ordering::Relaxed) + 1,
+        }
+    }
+
+    /// 使用指定上下文生成签名（确定性，trace id 除外）
+    pub fn sign_with_context(&self, request: &SignRequest, ctx: SignContext) -> Signature {
+        let a1 = request.cookies.get("a1").map(String::as_str).unwrap_or("");
+        let content = build_content_string(&request.method, &request.uri, request.payload.as_ref());
+
+        Signature {
+            x_s: build_x_s(&content, a1, request.profile.platform(), ctx),
+            x_t: ctx.timestamp_ms.to_string(),
+            x_s_common: build_x_s_common(a1, request.profile.platform(), ctx.counter),
+            x_b3_traceid: generate_b3_traceid(),
+            x_xray_traceid: generate_xray_traceid(ctx.timestamp_ms),
+        }
+    }
+
+    /// 从已有的 x-s 中还原签名上下文（counter 取自 x-s-common）
+    ///
+    /// 用于一致性比对：以 Agent 的随机输入重新签名，再比较输出
+    pub fn recover_context(x_s: &str, x_s_common: &str) -> Option<SignContext> {
+        let outer = CUSTOM_ENGINE.decode(x_s.strip_prefix(XYS_PREFIX)?).ok()?;
+        let outer: serde_json::Value = serde_json::from_slice(&outer).ok()?;
+        let x3 = outer.get("x3")?.as_str()?.strip_prefix(X3_PREFIX)?;
+        let payload = xor_with_key(&X3_ENGINE.decode(x3).ok()?);
+        if payload.len() < 16 || payload[..4] != VERSION_BYTES {
+            return None;
+        }
+
+        let common = decode_x_s_common(x_s_common)?;
+
+        Some(SignContext {
+            seed: u32::from_le_bytes(payload[4..8].try_into().ok()?),
+            timestamp_ms: u64::from_le_bytes(payload[8..16].try_into().ok()?),
+            counter: common.get("x10")?.as_u64()? as u32,
+        })
+    }
+}
+
+impl Default for NativeSigner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl Signer for NativeSigner {
+    fn name(&self) -> &'static str {
+        "native"
+    }
+
+    async fn sign(&self, request: &SignRequest) -> XhsResult<Signature> {
+        Ok(self.sign_with_context(request, self.next_context()))
+    }
+}
+
+// ============================================================================
+// Algorithm
+// ============================================================================
+
+/// 构造参与 md5 的内容字符串
+///
+/// - GET: path + "?" + 非空查询参数（与 Agent 的 parse_qs 行为一致）
+/// - POST: path + 紧凑 JSON payload
+fn build_content_string(method: &str, uri: &str, payload: Option<&serde_json::Value>) -> String {
+    let (path, query) = uri.split_once('?').unwrap_or((uri, ""));
+
+    if method.eq_ignore_ascii_case("POST") {
+        let body = payload.map(|p| p.to_string()).unwrap_or_default();
+        return format!("{}{}", path, body);
+    }
+
+    let params: Vec<&str> = query
+        .split('&')
+        .filter(|kv| kv.split_once('=').is_some_and(|(_, v)| !v.is_empty()))
+        .collect();
+
+    if params.is_empty() {
+        path.to_string()
+    } else {
+        format!("{}?{}", path, params.join("&"))
+    }
+}
+
+fn build_x_s(content: &str, a1: &str, platform: &str, ctx: SignContext) -> String {
+    let digest = md5::compute(content.as_bytes());
+
+    let mut payload = Vec::with_capacity(64 + a1.len());
+    payload.extend_from_slice(&VERSION_BYTES);
+    payload.extend_from_slice(&ctx.seed.to_le_bytes());
+    payload.extend_from_slice(&ctx.timestamp_ms.to_le_bytes());
+    payload.extend_from_slice(&digest.0);
+    payload.push(a1.len().min(u8::MAX as usize) as u8);
+    payload.extend_from_slice(&a1.as_bytes()[..a1.len().min(u8::MAX as usize)]);
+    payload.push(APP_ID.len() as u8);
+    payload.extend_from_slice(APP_ID.as_bytes());
+
+    let x3 = format!("{}{}", X3_PREFIX, X3_ENGINE.encode(xor_with_key(&payload)));
+
+    let outer = serde_json::json!({
+        "x0": SDK_VERSION,
+        "x1": APP_ID,
+        "x2": platform,
+        "x3": x3,
+        "x4": ""
+    });
+
+    format!("{}{}", XYS_PREFIX, CUSTOM_ENGINE.encode(outer.to_string()))
+}
+
+fn build_x_s_common(a1: &str, platform: &str, counter: u32) -> String {
+    let (x6, x7, x8) = ("", "", "");
+    let x9 = crc32(format!("{}{}{}", x6, x7, x8).as_bytes()) as i32;
+
+    let common = serde_json::json!({
+        "s0": 3,
+        "s1": "",
+        "x0": "1",
+        "x1": SDK_VERSION,
+        "x2": platform,
+        "x3": APP_ID,
+        "x4": WEB_BUILD,
+        "x5": a1,
+        "x6": x6,
+        "x7": x7,
+        "x8": x8,
+        "x9": x9,
+        "x10": counter,
+        "x11": "normal"
+    });
+
+    CUSTOM_ENGINE.encode(common.to_string())
+}
+
+/// 解码 x-s-common 为 JSON（用于一致性比对）
+pub fn decode_x_s_common(x_s_common: &str) -> Option<serde_json::Value> {
+    let raw = CUSTOM_ENGINE.decode(x_s_common).ok()?;
+    serde_json::from_slice(&raw).ok()
+}
+
+fn xor_with_key(data: &[u8]) -> Vec<u8> {
+    data.iter()
+        .zip(XOR_KEY.iter().cycle())
+        .map(|(b, k)| b ^ k)
+        .collect()
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+/// x-b3-traceid: 16 位随机十六进制
+fn generate_b3_traceid() -> String {
+    format!("{:016x}", rand::thread_rng().gen::<u64>())
+}
+
+/// x-xray-traceid: 时间戳高位 + 随机低位，共 32 位十六进制
+fn generate_xray_traceid(timestamp_ms: u64) -> String {
+    let mut rng = rand::thread_rng();
+    let high = (timestamp_ms << 23) | (rng.gen::<u32>() as u64 & 0x7F_FFFF);
+    format!("{:016x}{:016x}", high, rng.gen::<u64>())
+}
@@ -25,6 +25,6 @@ pub struct RequestDetails<'a> {
 /// Kept for API compatibility but will return an error.
 pub async fn generate_signature(_details: &RequestDetails<'_>) -> Result<(String, i64, String, String)> {
     Err(anyhow::anyhow!(
-        "Legacy signature generation is deprecated. Use stored credentials from MongoDB."
+        "Legacy signature generation is deprecated. Use a `Signer` (see `crate::signature`)."
     ))
 }
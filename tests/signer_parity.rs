@@ -0,0 +1,59 @@
+//! Native signer checks against recorded Agent signatures
+//!
+//! `tests/fixtures/agent_signatures.json` is meant to hold one record per URI in
+//! `endpoint_to_uri`, in the format written by `scripts/record_signatures.py`. The committed
+//! file was NOT recorded from the Agent: it was generated by the native signer itself, so
+//! comparing against it says nothing about parity with xhshow. The parity test is ignored
+//! until the file is re-recorded against a running Agent:
+//!
+//!     python scripts/record_signatures.py --output tests/fixtures/agent_signatures.json
+//!     cargo test --test signer_parity -- --include-ignored
+
+use xhs_rs::signature::{NativeSigner, SignatureRecord};
+
+const FIXTURE: &str = include_str!("fixtures/agent_signatures.json");
+
+fn records() -> Vec<SignatureRecord> {
+    serde_json::from_str(FIXTURE).expect("valid signature fixture")
+}
+
+#[test]
+fn fixture_covers_get_and_post() {
+    let records = records();
+    assert!(records.iter().any(|r| r.method == "GET"));
+    assert!(records.iter().any(|r| r.method == "POST" && r.payload.is_some()));
+}
+
+#[test]
+#[ignore = "fixture is not an Agent recording yet; re-record with scripts/record_signatures.py"]
+fn native_signer_matches_recorded_signatures() {
+    let signer = NativeSigner::new();
+
+    for record in records() {
+        let label = format!("{} {}", record.method, record.uri);
+        let ctx = NativeSigner::recover_context(&record.x_s, &record.x_s_common)
+            .unwrap_or_else(|| panic!("{}: cannot decode recorded x-s", label));
+
+        // The recorded x-t is fixed, so the recovered context must carry it unchanged
+        assert_eq!(ctx.timestamp_ms.to_string(), record.x_t, "{}: x-t", label);
+
+        let native = signer.sign_with_context(&record.request(), ctx);
+        assert_eq!(native.x_s, record.x_s, "{}: x-s", label);
+        assert_eq!(native.x_t, record.x_t, "{}: x-t", label);
+        assert_eq!(native.x_s_common, record.x_s_common, "{}: x-s-common", label);
+        assert_eq!(native.x_b3_traceid.len(), record.x_b3_traceid.len(), "{}: x-b3-traceid length", label);
+        assert_eq!(native.x_xray_traceid.len(), record.x_xray_traceid.len(), "{}: x-xray-traceid length", label);
+    }
+}
+
+#[test]
+fn signature_depends_on_request_content() {
+    let signer = NativeSigner::new();
+    let records = records();
+    let record = &records[0];
+    let ctx = NativeSigner::recover_context(&record.x_s, &record.x_s_common).unwrap();
+
+    let mut request = record.request();
+    request.uri.push_str("?extra=1");
+    assert_ne!(signer.sign_with_context(&request, ctx).x_s, record.x_s);
+}